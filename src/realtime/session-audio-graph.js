@@ -1,3 +1,8 @@
+// Frames the shared result ring holds (about 5 s at the worklet's frame rate)
+const RESULT_RING_CAPACITY = 64;
+// How often the main thread drains the ring; frames arrive every ~85 ms
+const RESULT_RING_POLL_MS = 40;
+
 const safely = (operation) => {
     try {
         operation();
@@ -15,6 +20,29 @@ const disconnectNode = (node, beforeDisconnect) => {
     return null;
 };
 
+/**
+ * Loads the WASM bindings and allocates a SharedArrayBuffer for the worklet's
+ * result ring, or returns null when the page cannot share memory (not
+ * cross-origin isolated), in which case the worklet posts frames as messages.
+ */
+const createResultRing = async () => {
+    if (globalThis.crossOriginIsolated !== true || typeof SharedArrayBuffer !== 'function') return null;
+    try {
+        const wasm = await import('../wasm/panda_audio.js');
+        await wasm.default();
+        return {
+            wasm,
+            buffer: new SharedArrayBuffer(wasm.ring_byte_length(RESULT_RING_CAPACITY)),
+            reader: null,
+            record: null,
+            timer: null,
+            inputChannels: null,
+        };
+    } catch {
+        return null;
+    }
+};
+
 /**
  * Creates the realtime session audio graph for microphone capture and feature worklet processing.
  */
@@ -28,6 +56,7 @@ export const createSessionAudioGraph = ({
     let workletNode = null;
     let sourceNode = null;
     let silenceGain = null;
+    let resultRing = null;
 
     const closeAudioContextInstance = async () => {
         if (!audioContext) return;
@@ -60,11 +89,61 @@ export const createSessionAudioGraph = ({
         throw new Error('Microphone not supported');
     };
 
+    // Same frame shape the worklet posts when it has no ring
+    const drainResultRing = () => {
+        const { reader, record } = resultRing;
+        while (reader.read_next(record)) {
+            onFeatureFrame({
+                timestamp: record.timestamp_ms,
+                frequency: record.frequency,
+                note: record.midi >= 0 ? record.note : '',
+                cents: record.cents,
+                volume: record.volume,
+                inTune: record.in_tune,
+                confidence: record.confidence,
+                onset: record.onset,
+                onsetStrength: record.onset_strength,
+                tempoBpm: record.tempo_bpm,
+                rhythmOffsetMs: record.rhythm_offset_ms,
+                hasSignal: record.has_signal,
+                inputChannels: resultRing.inputChannels,
+            });
+        }
+    };
+
+    // The worklet writes the ring header, so attach once it reports ready
+    const startResultRing = () => {
+        if (!resultRing) return;
+        try {
+            resultRing.reader = new resultRing.wasm.ResultRingReader(resultRing.buffer);
+        } catch {
+            onFallbackReason('system').catch(() => { });
+            return;
+        }
+        resultRing.record = new resultRing.wasm.FrameRecord();
+        resultRing.timer = setInterval(drainResultRing, RESULT_RING_POLL_MS);
+    };
+
+    const stopResultRing = () => {
+        if (!resultRing) return;
+        clearInterval(resultRing.timer);
+        safely(() => resultRing.reader?.free());
+        safely(() => resultRing.record?.free());
+        resultRing = null;
+    };
+
     const bindWorkletMessageHandler = () => {
         if (!workletNode) return;
         workletNode.port.onmessage = (event) => {
             const data = event.data || {};
-            if (data.ready) return;
+            if (data.ready) {
+                if (data.resultRing) startResultRing();
+                return;
+            }
+            if (data.type === 'input_channels') {
+                if (resultRing) resultRing.inputChannels = data.inputChannels;
+                return;
+            }
             if (data.error) {
                 onFallbackReason('system').catch(() => { });
                 return;
@@ -96,8 +175,11 @@ export const createSessionAudioGraph = ({
 
         await audioContext.audioWorklet.addModule(new URL('../worklets/rt-audio-processor.js', import.meta.url));
 
+        resultRing = await createResultRing();
         sourceNode = audioContext.createMediaStreamSource(micStream);
-        workletNode = new AudioWorkletNode(audioContext, 'rt-audio-processor');
+        workletNode = new AudioWorkletNode(audioContext, 'rt-audio-processor', {
+            processorOptions: { resultRing: resultRing?.buffer || null },
+        });
         silenceGain = audioContext.createGain();
         silenceGain.gain.value = 0;
 
@@ -112,6 +194,7 @@ export const createSessionAudioGraph = ({
             node.port.postMessage({ type: 'shutdown' });
             node.port.onmessage = null;
         });
+        stopResultRing();
         sourceNode = disconnectNode(sourceNode);
         silenceGain = disconnectNode(silenceGain);
         await closeAudioContextInstance();
@@ -1,218 +1,2279 @@
 /* @ts-self-types="./panda_audio.d.ts" */
 
+/**
+ * Arrangement of the samples in a multi-channel buffer
+ * @enum {0 | 1}
+ */
+export const ChannelLayout = Object.freeze({
+    /**
+     * Frame by frame: `L R L R ...`
+     */
+    Interleaved: 0, "0": "Interleaved",
+    /**
+     * Channel by channel, each one buffer long: `L L ... R R ...`
+     */
+    Planar: 1, "1": "Planar",
+});
+
+/**
+ * How a `ChannelSelector` turns several input channels into one
+ * @enum {0 | 1 | 2}
+ */
+export const ChannelMode = Object.freeze({
+    /**
+     * Follow the loudest live channel (e.g. a USB interface with the mic on
+     * input 2), switching only when another channel is clearly louder
+     */
+    Auto: 0, "0": "Auto",
+    /**
+     * Average all channels
+     */
+    Downmix: 1, "1": "Downmix",
+    /**
+     * Always use the channel chosen with `set_channel`
+     */
+    Fixed: 2, "2": "Fixed",
+});
+
+/**
+ * Reduces interleaved or planar multi-channel input to the mono signal the
+ * detectors expect, and reports the level of every channel
+ */
+export class ChannelSelector {
+    __destroy_into_raw() {
+        const ptr = this.__wbg_ptr;
+        this.__wbg_ptr = 0;
+        ChannelSelectorFinalization.unregister(this);
+        return ptr;
+    }
+    free() {
+        const ptr = this.__destroy_into_raw();
+        wasm.__wbg_channelselector_free(ptr, 0);
+    }
+    /**
+     * Smoothed RMS level of each channel
+     * @returns {Float32Array}
+     */
+    channel_levels() {
+        try {
+            const retptr = wasm.__wbindgen_add_to_stack_pointer(-16);
+            wasm.channelselector_channel_levels(retptr, this.__wbg_ptr);
+            var r0 = getDataViewMemory0().getInt32(retptr + 4 * 0, true);
+            var r1 = getDataViewMemory0().getInt32(retptr + 4 * 1, true);
+            var v1 = getArrayF32FromWasm0(r0, r1).slice();
+            wasm.__wbindgen_export3(r0, r1 * 4, 4);
+            return v1;
+        } finally {
+            wasm.__wbindgen_add_to_stack_pointer(16);
+        }
+    }
+    /**
+     * Copy the smoothed levels into `out` without allocating, for callers
+     * polling every render quantum. Returns the number of levels written.
+     * @param {Float32Array} out
+     * @returns {number}
+     */
+    channel_levels_into(out) {
+        var ptr0 = passArrayF32ToWasm0(out, wasm.__wbindgen_export);
+        var len0 = WASM_VECTOR_LEN;
+        const ret = wasm.channelselector_channel_levels_into(this.__wbg_ptr, ptr0, len0, addHeapObject(out));
+        return ret >>> 0;
+    }
+    /**
+     * Peak of each channel over the last buffer
+     * @returns {Float32Array}
+     */
+    channel_peaks() {
+        try {
+            const retptr = wasm.__wbindgen_add_to_stack_pointer(-16);
+            wasm.channelselector_channel_peaks(retptr, this.__wbg_ptr);
+            var r0 = getDataViewMemory0().getInt32(retptr + 4 * 0, true);
+            var r1 = getDataViewMemory0().getInt32(retptr + 4 * 1, true);
+            var v1 = getArrayF32FromWasm0(r0, r1).slice();
+            wasm.__wbindgen_export3(r0, r1 * 4, 4);
+            return v1;
+        } finally {
+            wasm.__wbindgen_add_to_stack_pointer(16);
+        }
+    }
+    /**
+     * Channel count of the last buffer
+     * @returns {number}
+     */
+    get channels() {
+        const ret = wasm.channelselector_channels(this.__wbg_ptr);
+        return ret >>> 0;
+    }
+    /**
+     * Number of channels carrying signal above -60 dBFS
+     * @returns {number}
+     */
+    get live_channels() {
+        const ret = wasm.channelselector_live_channels(this.__wbg_ptr);
+        return ret >>> 0;
+    }
+    /**
+     * Like `mix_into`, returning a new mono buffer
+     * @param {Float32Array} data
+     * @param {number} channels
+     * @param {ChannelLayout} layout
+     * @returns {Float32Array}
+     */
+    mix(data, channels, layout) {
+        try {
+            const retptr = wasm.__wbindgen_add_to_stack_pointer(-16);
+            const ptr0 = passArrayF32ToWasm0(data, wasm.__wbindgen_export);
+            const len0 = WASM_VECTOR_LEN;
+            wasm.channelselector_mix(retptr, this.__wbg_ptr, ptr0, len0, channels, layout);
+            var r0 = getDataViewMemory0().getInt32(retptr + 4 * 0, true);
+            var r1 = getDataViewMemory0().getInt32(retptr + 4 * 1, true);
+            var v2 = getArrayF32FromWasm0(r0, r1).slice();
+            wasm.__wbindgen_export3(r0, r1 * 4, 4);
+            return v2;
+        } finally {
+            wasm.__wbindgen_add_to_stack_pointer(16);
+        }
+    }
+    /**
+     * Reduce `data` (`channels` channels in `layout`) to mono in `out`.
+     * Returns the number of frames written, at most `out.len()`.
+     * @param {Float32Array} data
+     * @param {number} channels
+     * @param {ChannelLayout} layout
+     * @param {Float32Array} out
+     * @returns {number}
+     */
+    mix_into(data, channels, layout, out) {
+        const ptr0 = passArrayF32ToWasm0(data, wasm.__wbindgen_export);
+        const len0 = WASM_VECTOR_LEN;
+        var ptr1 = passArrayF32ToWasm0(out, wasm.__wbindgen_export);
+        var len1 = WASM_VECTOR_LEN;
+        const ret = wasm.channelselector_mix_into(this.__wbg_ptr, ptr0, len0, channels, layout, ptr1, len1, addHeapObject(out));
+        return ret >>> 0;
+    }
+    /**
+     * @returns {ChannelMode}
+     */
+    get mode() {
+        const ret = wasm.channelselector_mode(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * @param {number} sample_rate
+     */
+    constructor(sample_rate) {
+        const ret = wasm.channelselector_new(sample_rate);
+        this.__wbg_ptr = ret >>> 0;
+        ChannelSelectorFinalization.register(this, this.__wbg_ptr, this);
+        return this;
+    }
+    /**
+     * Forget levels and the auto-selected channel
+     */
+    reset() {
+        wasm.channelselector_reset(this.__wbg_ptr);
+    }
+    /**
+     * Channel feeding the output, or -1 when downmixing
+     * @returns {number}
+     */
+    get selected_channel() {
+        const ret = wasm.channelselector_selected_channel(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * Use channel `index` (0-based) and switch to `ChannelMode::Fixed`
+     * @param {number} index
+     */
+    set_channel(index) {
+        wasm.channelselector_set_channel(this.__wbg_ptr, index);
+    }
+    /**
+     * @param {ChannelMode} mode
+     */
+    set_mode(mode) {
+        wasm.channelselector_set_mode(this.__wbg_ptr, mode);
+    }
+}
+if (Symbol.dispose) ChannelSelector.prototype[Symbol.dispose] = ChannelSelector.prototype.free;
+
+/**
+ * Kind of click scheduled on a metronome tick
+ * @enum {0 | 1 | 2 | 3 | 4}
+ */
+export const ClickKind = Object.freeze({
+    /**
+     * Tick with no click (subdivisions during the count-in)
+     */
+    Silent: 0, "0": "Silent",
+    /**
+     * First beat of a bar
+     */
+    Accent: 1, "1": "Accent",
+    /**
+     * Any other beat of a bar
+     */
+    Beat: 2, "2": "Beat",
+    /**
+     * Subdivision between beats
+     */
+    Subdivision: 3, "3": "Subdivision",
+    /**
+     * Beat of a count-in bar
+     */
+    CountIn: 4, "4": "CountIn",
+});
+
+/**
+ * Accumulates timestamped pitch frames in WASM memory
+ */
+export class ContourRecorder {
+    __destroy_into_raw() {
+        const ptr = this.__wbg_ptr;
+        this.__wbg_ptr = 0;
+        ContourRecorderFinalization.unregister(this);
+        return ptr;
+    }
+    free() {
+        const ptr = this.__destroy_into_raw();
+        wasm.__wbg_contourrecorder_free(ptr, 0);
+    }
+    /**
+     * Discard all frames
+     */
+    clear() {
+        wasm.contourrecorder_clear(this.__wbg_ptr);
+    }
+    /**
+     * @returns {number}
+     */
+    get downsampled_stride() {
+        const ret = wasm.contourrecorder_downsampled_stride(this.__wbg_ptr);
+        return ret >>> 0;
+    }
+    /**
+     * Time between the first and last frames (ms)
+     * @returns {number}
+     */
+    get duration_ms() {
+        const ret = wasm.contourrecorder_duration_ms(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * Contour reduced to `points` equal-time buckets with
+     * `DOWNSAMPLED_STRIDE` values each. A bucket's frequency is the median of
+     * its voiced frames (0 if none) and its volume is the loudest frame.
+     * `points` is capped at the number of frames.
+     * @param {number} points
+     * @returns {Float32Array}
+     */
+    export_downsampled(points) {
+        try {
+            const retptr = wasm.__wbindgen_add_to_stack_pointer(-16);
+            wasm.contourrecorder_export_downsampled(retptr, this.__wbg_ptr, points);
+            var r0 = getDataViewMemory0().getInt32(retptr + 4 * 0, true);
+            var r1 = getDataViewMemory0().getInt32(retptr + 4 * 1, true);
+            var v1 = getArrayF32FromWasm0(r0, r1).slice();
+            wasm.__wbindgen_export3(r0, r1 * 4, 4);
+            return v1;
+        } finally {
+            wasm.__wbindgen_add_to_stack_pointer(16);
+        }
+    }
+    /**
+     * All frames as a flat array with `PACKED_STRIDE` values per frame.
+     * Times are milliseconds relative to `start_ms`.
+     * @returns {Float32Array}
+     */
+    export_packed() {
+        try {
+            const retptr = wasm.__wbindgen_add_to_stack_pointer(-16);
+            wasm.contourrecorder_export_packed(retptr, this.__wbg_ptr);
+            var r0 = getDataViewMemory0().getInt32(retptr + 4 * 0, true);
+            var r1 = getDataViewMemory0().getInt32(retptr + 4 * 1, true);
+            var v1 = getArrayF32FromWasm0(r0, r1).slice();
+            wasm.__wbindgen_export3(r0, r1 * 4, 4);
+            return v1;
+        } finally {
+            wasm.__wbindgen_add_to_stack_pointer(16);
+        }
+    }
+    /**
+     * @returns {boolean}
+     */
+    is_empty() {
+        const ret = wasm.contourrecorder_is_empty(this.__wbg_ptr);
+        return ret !== 0;
+    }
+    /**
+     * Number of recorded frames
+     * @returns {number}
+     */
+    get len() {
+        const ret = wasm.contourrecorder_len(this.__wbg_ptr);
+        return ret >>> 0;
+    }
+    /**
+     * Create a recorder that keeps at most `max_frames` frames
+     * (e.g. one hour at 23 frames/s is about 83,000 frames, under 2 MB)
+     * @param {number} max_frames
+     */
+    constructor(max_frames) {
+        const ret = wasm.contourrecorder_new(max_frames);
+        this.__wbg_ptr = ret >>> 0;
+        ContourRecorderFinalization.register(this, this.__wbg_ptr, this);
+        return this;
+    }
+    /**
+     * @returns {number}
+     */
+    get packed_stride() {
+        const ret = wasm.contourrecorder_packed_stride(this.__wbg_ptr);
+        return ret >>> 0;
+    }
+    /**
+     * Record a detection result at `timestamp_ms`.
+     * Returns false if the recorder is full.
+     * @param {number} timestamp_ms
+     * @param {PitchResult} result
+     * @returns {boolean}
+     */
+    push(timestamp_ms, result) {
+        _assertClass(result, PitchResult);
+        const ret = wasm.contourrecorder_push(this.__wbg_ptr, timestamp_ms, result.__wbg_ptr);
+        return ret !== 0;
+    }
+    /**
+     * Timestamp of the first frame (ms)
+     * @returns {number}
+     */
+    get start_ms() {
+        const ret = wasm.contourrecorder_start_ms(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * @returns {boolean}
+     */
+    get truncated() {
+        const ret = wasm.contourrecorder_truncated(this.__wbg_ptr);
+        return ret !== 0;
+    }
+}
+if (Symbol.dispose) ContourRecorder.prototype[Symbol.dispose] = ContourRecorder.prototype.free;
+
+/**
+ * All tunable `PitchDetector` settings in one object.
+ *
+ * Build one in JS (or from a plain object with `DetectorConfig.from_js`) and
+ * pass it to `PitchDetector.apply_config` at any time; no re-creation needed.
+ */
+export class DetectorConfig {
+    static __wrap(ptr) {
+        ptr = ptr >>> 0;
+        const obj = Object.create(DetectorConfig.prototype);
+        obj.__wbg_ptr = ptr;
+        DetectorConfigFinalization.register(obj, obj.__wbg_ptr, obj);
+        return obj;
+    }
+    __destroy_into_raw() {
+        const ptr = this.__wbg_ptr;
+        this.__wbg_ptr = 0;
+        DetectorConfigFinalization.unregister(this);
+        return ptr;
+    }
+    free() {
+        const ptr = this.__destroy_into_raw();
+        wasm.__wbg_detectorconfig_free(ptr, 0);
+    }
+    /**
+     * Build a config from a plain JS object with camelCase keys
+     * (e.g. `{ minFreq: 120, smoothing: "oneEuro" }`). Missing or invalid
+     * keys keep their defaults.
+     * @param {any} value
+     * @returns {DetectorConfig}
+     */
+    static from_js(value) {
+        try {
+            const ret = wasm.detectorconfig_from_js(addBorrowedObject(value));
+            return DetectorConfig.__wrap(ret);
+        } finally {
+            heap[stack_pointer++] = undefined;
+        }
+    }
+    /**
+     * Default violin configuration (matches a fresh `PitchDetector`)
+     */
+    constructor() {
+        const ret = wasm.detectorconfig_new();
+        this.__wbg_ptr = ret >>> 0;
+        DetectorConfigFinalization.register(this, this.__wbg_ptr, this);
+        return this;
+    }
+    /**
+     * Serialise to a plain JS object with camelCase keys (inverse of `from_js`)
+     * @returns {any}
+     */
+    to_js() {
+        const ret = wasm.detectorconfig_to_js(this.__wbg_ptr);
+        return takeObject(ret);
+    }
+    /**
+     * Copy with every value clamped to its supported range. NaN or infinite
+     * values are replaced with their defaults first.
+     * @returns {DetectorConfig}
+     */
+    validated() {
+        const ret = wasm.detectorconfig_validated(this.__wbg_ptr);
+        return DetectorConfig.__wrap(ret);
+    }
+    /**
+     * NSDF level a coarse (downsampled) peak must exceed to be considered
+     * @returns {number}
+     */
+    get coarse_threshold() {
+        const ret = wasm.__wbg_get_detectorconfig_coarse_threshold(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * NSDF peak required to accept a pitch
+     * @returns {number}
+     */
+    get confidence_threshold() {
+        const ret = wasm.__wbg_get_detectorconfig_confidence_threshold(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * Whether octave/fifth errors are corrected
+     * @returns {boolean}
+     */
+    get harmonic_correction() {
+        const ret = wasm.__wbg_get_detectorconfig_harmonic_correction(this.__wbg_ptr);
+        return ret !== 0;
+    }
+    /**
+     * Maximum frequency to detect (Hz)
+     * @returns {number}
+     */
+    get max_freq() {
+        const ret = wasm.__wbg_get_detectorconfig_max_freq(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * Median filter length in frames
+     * @returns {number}
+     */
+    get median_window() {
+        const ret = wasm.__wbg_get_detectorconfig_median_window(this.__wbg_ptr);
+        return ret >>> 0;
+    }
+    /**
+     * Minimum frequency to detect (Hz)
+     * @returns {number}
+     */
+    get min_freq() {
+        const ret = wasm.__wbg_get_detectorconfig_min_freq(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * Jump (cents) treated as a new note by note-aware smoothing
+     * @returns {number}
+     */
+    get note_change_cents() {
+        const ret = wasm.__wbg_get_detectorconfig_note_change_cents(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * One-euro speed coefficient
+     * @returns {number}
+     */
+    get one_euro_beta() {
+        const ret = wasm.__wbg_get_detectorconfig_one_euro_beta(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * One-euro minimum cutoff (Hz)
+     * @returns {number}
+     */
+    get one_euro_min_cutoff() {
+        const ret = wasm.__wbg_get_detectorconfig_one_euro_min_cutoff(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * Weight of the newest frame for exponential and note-aware smoothing
+     * @returns {number}
+     */
+    get smoothing_alpha() {
+        const ret = wasm.__wbg_get_detectorconfig_smoothing_alpha(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * Pitch smoothing strategy
+     * @returns {SmoothingMode}
+     */
+    get smoothing() {
+        const ret = wasm.__wbg_get_detectorconfig_smoothing(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * Sub-harmonic energy ratio above which a lower octave/twelfth is chosen
+     * @returns {number}
+     */
+    get subharmonic_ratio() {
+        const ret = wasm.__wbg_get_detectorconfig_subharmonic_ratio(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * Tolerance for "in tune" detection (cents)
+     * @returns {number}
+     */
+    get tune_tolerance() {
+        const ret = wasm.__wbg_get_detectorconfig_tune_tolerance(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * RMS volume below which frames are treated as silence
+     * @returns {number}
+     */
+    get volume_threshold() {
+        const ret = wasm.__wbg_get_detectorconfig_volume_threshold(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * NSDF level a coarse (downsampled) peak must exceed to be considered
+     * @param {number} arg0
+     */
+    set coarse_threshold(arg0) {
+        wasm.__wbg_set_detectorconfig_coarse_threshold(this.__wbg_ptr, arg0);
+    }
+    /**
+     * NSDF peak required to accept a pitch
+     * @param {number} arg0
+     */
+    set confidence_threshold(arg0) {
+        wasm.__wbg_set_detectorconfig_confidence_threshold(this.__wbg_ptr, arg0);
+    }
+    /**
+     * Whether octave/fifth errors are corrected
+     * @param {boolean} arg0
+     */
+    set harmonic_correction(arg0) {
+        wasm.__wbg_set_detectorconfig_harmonic_correction(this.__wbg_ptr, arg0);
+    }
+    /**
+     * Maximum frequency to detect (Hz)
+     * @param {number} arg0
+     */
+    set max_freq(arg0) {
+        wasm.__wbg_set_detectorconfig_max_freq(this.__wbg_ptr, arg0);
+    }
+    /**
+     * Median filter length in frames
+     * @param {number} arg0
+     */
+    set median_window(arg0) {
+        wasm.__wbg_set_detectorconfig_median_window(this.__wbg_ptr, arg0);
+    }
+    /**
+     * Minimum frequency to detect (Hz)
+     * @param {number} arg0
+     */
+    set min_freq(arg0) {
+        wasm.__wbg_set_detectorconfig_min_freq(this.__wbg_ptr, arg0);
+    }
+    /**
+     * Jump (cents) treated as a new note by note-aware smoothing
+     * @param {number} arg0
+     */
+    set note_change_cents(arg0) {
+        wasm.__wbg_set_detectorconfig_note_change_cents(this.__wbg_ptr, arg0);
+    }
+    /**
+     * One-euro speed coefficient
+     * @param {number} arg0
+     */
+    set one_euro_beta(arg0) {
+        wasm.__wbg_set_detectorconfig_one_euro_beta(this.__wbg_ptr, arg0);
+    }
+    /**
+     * One-euro minimum cutoff (Hz)
+     * @param {number} arg0
+     */
+    set one_euro_min_cutoff(arg0) {
+        wasm.__wbg_set_detectorconfig_one_euro_min_cutoff(this.__wbg_ptr, arg0);
+    }
+    /**
+     * Weight of the newest frame for exponential and note-aware smoothing
+     * @param {number} arg0
+     */
+    set smoothing_alpha(arg0) {
+        wasm.__wbg_set_detectorconfig_smoothing_alpha(this.__wbg_ptr, arg0);
+    }
+    /**
+     * Pitch smoothing strategy
+     * @param {SmoothingMode} arg0
+     */
+    set smoothing(arg0) {
+        wasm.__wbg_set_detectorconfig_smoothing(this.__wbg_ptr, arg0);
+    }
+    /**
+     * Sub-harmonic energy ratio above which a lower octave/twelfth is chosen
+     * @param {number} arg0
+     */
+    set subharmonic_ratio(arg0) {
+        wasm.__wbg_set_detectorconfig_subharmonic_ratio(this.__wbg_ptr, arg0);
+    }
+    /**
+     * Tolerance for "in tune" detection (cents)
+     * @param {number} arg0
+     */
+    set tune_tolerance(arg0) {
+        wasm.__wbg_set_detectorconfig_tune_tolerance(this.__wbg_ptr, arg0);
+    }
+    /**
+     * RMS volume below which frames are treated as silence
+     * @param {number} arg0
+     */
+    set volume_threshold(arg0) {
+        wasm.__wbg_set_detectorconfig_volume_threshold(this.__wbg_ptr, arg0);
+    }
+}
+if (Symbol.dispose) DetectorConfig.prototype[Symbol.dispose] = DetectorConfig.prototype.free;
+
 /**
  * Echo recording and evaluation buffer
  */
-export class EchoBuffer {
+export class EchoBuffer {
+    __destroy_into_raw() {
+        const ptr = this.__wbg_ptr;
+        this.__wbg_ptr = 0;
+        EchoBufferFinalization.unregister(this);
+        return ptr;
+    }
+    free() {
+        const ptr = this.__destroy_into_raw();
+        wasm.__wbg_echobuffer_free(ptr, 0);
+    }
+    /**
+     * Simplified envelope extractor.
+     * Condenses 48kHz audio into `target_bins` (e.g., 400 slices) based on RMS amplitude.
+     * @param {number} target_bins
+     * @returns {Float32Array}
+     */
+    extract_envelope(target_bins) {
+        try {
+            const retptr = wasm.__wbindgen_add_to_stack_pointer(-16);
+            wasm.echobuffer_extract_envelope(retptr, this.__wbg_ptr, target_bins);
+            var r0 = getDataViewMemory0().getInt32(retptr + 4 * 0, true);
+            var r1 = getDataViewMemory0().getInt32(retptr + 4 * 1, true);
+            var v1 = getArrayF32FromWasm0(r0, r1).slice();
+            wasm.__wbindgen_export3(r0, r1 * 4, 4);
+            return v1;
+        } finally {
+            wasm.__wbindgen_add_to_stack_pointer(16);
+        }
+    }
+    /**
+     * Start time, sample rate, length, levels and overflow of the current take
+     * @returns {EchoTakeMetadata}
+     */
+    metadata() {
+        const ret = wasm.echobuffer_metadata(this.__wbg_ptr);
+        return EchoTakeMetadata.__wrap(ret);
+    }
+    /**
+     * @param {number} capacity
+     */
+    constructor(capacity) {
+        const ret = wasm.echobuffer_new(capacity);
+        this.__wbg_ptr = ret >>> 0;
+        EchoBufferFinalization.register(this, this.__wbg_ptr, this);
+        return this;
+    }
+    /**
+     * Push an array of audio samples into the linear buffer.
+     * Returns true if the buffer hit capacity during this write.
+     * @param {Float32Array} samples
+     * @returns {boolean}
+     */
+    push_chunk(samples) {
+        const ptr0 = passArrayF32ToWasm0(samples, wasm.__wbindgen_export);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.echobuffer_push_chunk(this.__wbg_ptr, ptr0, len0);
+        return ret !== 0;
+    }
+    /**
+     * Reset internal state, ready for a new recording
+     */
+    reset() {
+        wasm.echobuffer_reset(this.__wbg_ptr);
+    }
+    /**
+     * @param {boolean} state
+     */
+    set_recording(state) {
+        wasm.echobuffer_set_recording(this.__wbg_ptr, state);
+    }
+    /**
+     * Sample rate of the pushed audio (defaults to 48kHz)
+     * @param {number} sample_rate
+     */
+    set_sample_rate(sample_rate) {
+        wasm.echobuffer_set_sample_rate(this.__wbg_ptr, sample_rate);
+    }
+    /**
+     * Reset and start a new take captured at `timestamp_ms`
+     * (e.g. the worklet's `currentTime * 1000`)
+     * @param {number} timestamp_ms
+     */
+    start_recording(timestamp_ms) {
+        wasm.echobuffer_start_recording(this.__wbg_ptr, timestamp_ms);
+    }
+}
+if (Symbol.dispose) EchoBuffer.prototype[Symbol.dispose] = EchoBuffer.prototype.free;
+
+/**
+ * Integrity metadata for one `EchoBuffer` recording: when it started, at
+ * what rate, how much was kept and how loud it was
+ */
+export class EchoTakeMetadata {
+    static __wrap(ptr) {
+        ptr = ptr >>> 0;
+        const obj = Object.create(EchoTakeMetadata.prototype);
+        obj.__wbg_ptr = ptr;
+        EchoTakeMetadataFinalization.register(obj, obj.__wbg_ptr, obj);
+        return obj;
+    }
+    __destroy_into_raw() {
+        const ptr = this.__wbg_ptr;
+        this.__wbg_ptr = 0;
+        EchoTakeMetadataFinalization.unregister(this);
+        return ptr;
+    }
+    free() {
+        const ptr = this.__destroy_into_raw();
+        wasm.__wbg_echotakemetadata_free(ptr, 0);
+    }
+    /**
+     * @returns {number}
+     */
+    get capacity() {
+        const ret = wasm.echotakemetadata_capacity(this.__wbg_ptr);
+        return ret >>> 0;
+    }
+    /**
+     * Percentage (0-100) of samples at full scale
+     * @returns {number}
+     */
+    get clipping_percent() {
+        const ret = wasm.echotakemetadata_clipping_percent(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * Samples pushed after the buffer was full and thrown away
+     * @returns {number}
+     */
+    get dropped_samples() {
+        const ret = wasm.echotakemetadata_dropped_samples(this.__wbg_ptr);
+        return ret >>> 0;
+    }
+    /**
+     * Length of the stored audio
+     * @returns {number}
+     */
+    get duration_ms() {
+        const ret = wasm.echotakemetadata_duration_ms(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * Whether the buffer filled up, so the take may be cut short
+     * @returns {boolean}
+     */
+    get hit_capacity() {
+        const ret = wasm.echotakemetadata_hit_capacity(this.__wbg_ptr);
+        return ret !== 0;
+    }
+    /**
+     * @returns {InputHealth}
+     */
+    get input_health() {
+        const ret = wasm.echotakemetadata_input_health(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * Largest absolute sample (0-1)
+     * @returns {number}
+     */
+    get peak() {
+        const ret = wasm.echotakemetadata_peak(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * RMS level over the whole take
+     * @returns {number}
+     */
+    get rms() {
+        const ret = wasm.echotakemetadata_rms(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * Samples actually stored in the buffer
+     * @returns {number}
+     */
+    get sample_count() {
+        const ret = wasm.echotakemetadata_sample_count(this.__wbg_ptr);
+        return ret >>> 0;
+    }
+    /**
+     * @returns {number}
+     */
+    get sample_rate() {
+        const ret = wasm.echotakemetadata_sample_rate(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * Caller-supplied time the recording started (e.g. `currentTime * 1000`)
+     * @returns {number}
+     */
+    get start_timestamp_ms() {
+        const ret = wasm.echotakemetadata_start_timestamp_ms(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * Plain JS object with the same fields, for storage alongside the take
+     * @returns {any}
+     */
+    to_js() {
+        const ret = wasm.echotakemetadata_to_js(this.__wbg_ptr);
+        return takeObject(ret);
+    }
+}
+if (Symbol.dispose) EchoTakeMetadata.prototype[Symbol.dispose] = EchoTakeMetadata.prototype.free;
+
+/**
+ * One decoded analysis frame. Reuse a single instance on the main thread.
+ */
+export class FrameRecord {
+    __destroy_into_raw() {
+        const ptr = this.__wbg_ptr;
+        this.__wbg_ptr = 0;
+        FrameRecordFinalization.unregister(this);
+        return ptr;
+    }
+    free() {
+        const ptr = this.__destroy_into_raw();
+        wasm.__wbg_framerecord_free(ptr, 0);
+    }
+    /**
+     * @returns {number}
+     */
+    get cents() {
+        const ret = wasm.framerecord_cents(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * @returns {number}
+     */
+    get clipping_percent() {
+        const ret = wasm.framerecord_clipping_percent(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * @returns {number}
+     */
+    get confidence() {
+        const ret = wasm.framerecord_confidence(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * @returns {boolean}
+     */
+    get corrected() {
+        const ret = wasm.framerecord_corrected(this.__wbg_ptr);
+        return ret !== 0;
+    }
+    /**
+     * @returns {number}
+     */
+    get frequency() {
+        const ret = wasm.framerecord_frequency(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * @returns {boolean}
+     */
+    get has_signal() {
+        const ret = wasm.framerecord_has_signal(this.__wbg_ptr);
+        return ret !== 0;
+    }
+    /**
+     * @returns {boolean}
+     */
+    get in_tune() {
+        const ret = wasm.framerecord_in_tune(this.__wbg_ptr);
+        return ret !== 0;
+    }
+    /**
+     * @returns {InputHealth}
+     */
+    get input_health() {
+        const ret = wasm.framerecord_input_health(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * @returns {number}
+     */
+    get midi() {
+        const ret = wasm.framerecord_midi(this.__wbg_ptr);
+        return ret;
+    }
+    constructor() {
+        const ret = wasm.framerecord_new();
+        this.__wbg_ptr = ret >>> 0;
+        FrameRecordFinalization.register(this, this.__wbg_ptr, this);
+        return this;
+    }
+    /**
+     * Note name (e.g., "A4"), or "-" when no pitch was detected
+     * @returns {string}
+     */
+    get note() {
+        let deferred1_0;
+        let deferred1_1;
+        try {
+            const retptr = wasm.__wbindgen_add_to_stack_pointer(-16);
+            wasm.framerecord_note(retptr, this.__wbg_ptr);
+            var r0 = getDataViewMemory0().getInt32(retptr + 4 * 0, true);
+            var r1 = getDataViewMemory0().getInt32(retptr + 4 * 1, true);
+            deferred1_0 = r0;
+            deferred1_1 = r1;
+            return getStringFromWasm0(r0, r1);
+        } finally {
+            wasm.__wbindgen_add_to_stack_pointer(16);
+            wasm.__wbindgen_export3(deferred1_0, deferred1_1, 1);
+        }
+    }
+    /**
+     * @returns {boolean}
+     */
+    get onset() {
+        const ret = wasm.framerecord_onset(this.__wbg_ptr);
+        return ret !== 0;
+    }
+    /**
+     * @returns {number}
+     */
+    get onset_strength() {
+        const ret = wasm.framerecord_onset_strength(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * @returns {number}
+     */
+    get raw_frequency() {
+        const ret = wasm.framerecord_raw_frequency(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * @returns {number}
+     */
+    get rhythm_offset_ms() {
+        const ret = wasm.framerecord_rhythm_offset_ms(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * @returns {number}
+     */
+    get sequence() {
+        const ret = wasm.framerecord_sequence(this.__wbg_ptr);
+        return ret >>> 0;
+    }
+    /**
+     * @returns {number}
+     */
+    get tempo_bpm() {
+        const ret = wasm.framerecord_tempo_bpm(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * @returns {number}
+     */
+    get timestamp_ms() {
+        const ret = wasm.framerecord_timestamp_ms(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * @returns {number}
+     */
+    get voicing_probability() {
+        const ret = wasm.framerecord_voicing_probability(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * @returns {number}
+     */
+    get volume() {
+        const ret = wasm.framerecord_volume(this.__wbg_ptr);
+        return ret;
+    }
+}
+if (Symbol.dispose) FrameRecord.prototype[Symbol.dispose] = FrameRecord.prototype.free;
+
+/**
+ * State of the microphone signal behind a detection result
+ * @enum {0 | 1 | 2}
+ */
+export const InputHealth = Object.freeze({
+    /**
+     * Nothing wrong with the input
+     */
+    Good: 0, "0": "Good",
+    /**
+     * Too many samples at full scale: the player is too close or too loud
+     */
+    Clipping: 1, "1": "Clipping",
+    /**
+     * The input contained NaN or infinite samples (a glitching device);
+     * they were replaced with silence before detection
+     */
+    Invalid: 2, "2": "Invalid",
+});
+
+/**
+ * Supported string instruments (fractional violins share violin tuning)
+ * @enum {0 | 1 | 2 | 3 | 4 | 5 | 6 | 7}
+ */
+export const Instrument = Object.freeze({
+    Violin: 0, "0": "Violin",
+    ViolinThreeQuarter: 1, "1": "ViolinThreeQuarter",
+    ViolinHalf: 2, "2": "ViolinHalf",
+    ViolinQuarter: 3, "3": "ViolinQuarter",
+    ViolinEighth: 4, "4": "ViolinEighth",
+    ViolinSixteenth: 5, "5": "ViolinSixteenth",
+    Viola: 6, "6": "Viola",
+    Cello: 7, "7": "Cello",
+});
+
+/**
+ * Detection settings and tuner targets for one instrument
+ */
+export class InstrumentPreset {
+    static __wrap(ptr) {
+        ptr = ptr >>> 0;
+        const obj = Object.create(InstrumentPreset.prototype);
+        obj.__wbg_ptr = ptr;
+        InstrumentPresetFinalization.register(obj, obj.__wbg_ptr, obj);
+        return obj;
+    }
+    __destroy_into_raw() {
+        const ptr = this.__wbg_ptr;
+        this.__wbg_ptr = 0;
+        InstrumentPresetFinalization.unregister(this);
+        return ptr;
+    }
+    free() {
+        const ptr = this.__destroy_into_raw();
+        wasm.__wbg_instrumentpreset_free(ptr, 0);
+    }
+    /**
+     * Detector configuration for this instrument
+     * @returns {DetectorConfig}
+     */
+    detector_config() {
+        const ret = wasm.instrumentpreset_detector_config(this.__wbg_ptr);
+        return DetectorConfig.__wrap(ret);
+    }
+    /**
+     * Look up a preset by name: "violin", "violin-3/4", "violin-1/2",
+     * "violin-1/4", "violin-1/8", "violin-1/16", "viola" or "cello"
+     * @param {string} name
+     * @returns {InstrumentPreset | undefined}
+     */
+    static from_name(name) {
+        const ptr0 = passStringToWasm0(name, wasm.__wbindgen_export, wasm.__wbindgen_export2);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.instrumentpreset_from_name(ptr0, len0);
+        return ret === 0 ? undefined : InstrumentPreset.__wrap(ret);
+    }
+    /**
+     * @returns {Instrument}
+     */
+    get instrument() {
+        const ret = wasm.instrumentpreset_instrument(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * @returns {number}
+     */
+    get max_freq() {
+        const ret = wasm.instrumentpreset_max_freq(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * @returns {number}
+     */
+    get min_buffer_size() {
+        const ret = wasm.instrumentpreset_min_buffer_size(this.__wbg_ptr);
+        return ret >>> 0;
+    }
+    /**
+     * @returns {number}
+     */
+    get min_freq() {
+        const ret = wasm.instrumentpreset_min_freq(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * Index of the open string closest to `frequency` (tuner target)
+     * @param {number} frequency
+     * @returns {number}
+     */
+    nearest_string(frequency) {
+        const ret = wasm.instrumentpreset_nearest_string(this.__wbg_ptr, frequency);
+        return ret >>> 0;
+    }
+    /**
+     * @param {Instrument} instrument
+     */
+    constructor(instrument) {
+        const ret = wasm.instrumentpreset_new(instrument);
+        this.__wbg_ptr = ret >>> 0;
+        InstrumentPresetFinalization.register(this, this.__wbg_ptr, this);
+        return this;
+    }
+    /**
+     * Open string frequencies (Hz), lowest first
+     * @returns {Float32Array}
+     */
+    open_string_frequencies() {
+        try {
+            const retptr = wasm.__wbindgen_add_to_stack_pointer(-16);
+            wasm.instrumentpreset_open_string_frequencies(retptr, this.__wbg_ptr);
+            var r0 = getDataViewMemory0().getInt32(retptr + 4 * 0, true);
+            var r1 = getDataViewMemory0().getInt32(retptr + 4 * 1, true);
+            var v1 = getArrayF32FromWasm0(r0, r1).slice();
+            wasm.__wbindgen_export3(r0, r1 * 4, 4);
+            return v1;
+        } finally {
+            wasm.__wbindgen_add_to_stack_pointer(16);
+        }
+    }
+    /**
+     * Open string note names (e.g., "G3"), lowest first
+     * @returns {string[]}
+     */
+    open_string_notes() {
+        try {
+            const retptr = wasm.__wbindgen_add_to_stack_pointer(-16);
+            wasm.instrumentpreset_open_string_notes(retptr, this.__wbg_ptr);
+            var r0 = getDataViewMemory0().getInt32(retptr + 4 * 0, true);
+            var r1 = getDataViewMemory0().getInt32(retptr + 4 * 1, true);
+            var v1 = getArrayJsValueFromWasm0(r0, r1).slice();
+            wasm.__wbindgen_export3(r0, r1 * 4, 4);
+            return v1;
+        } finally {
+            wasm.__wbindgen_add_to_stack_pointer(16);
+        }
+    }
+    /**
+     * Cents from open string `index` to `frequency` (positive = sharp)
+     * @param {number} index
+     * @param {number} frequency
+     * @returns {number}
+     */
+    string_cents(index, frequency) {
+        const ret = wasm.instrumentpreset_string_cents(this.__wbg_ptr, index, frequency);
+        return ret;
+    }
+    /**
+     * @returns {number}
+     */
+    get tune_tolerance() {
+        const ret = wasm.instrumentpreset_tune_tolerance(this.__wbg_ptr);
+        return ret;
+    }
+}
+if (Symbol.dispose) InstrumentPreset.prototype[Symbol.dispose] = InstrumentPreset.prototype.free;
+
+/**
+ * Plays a known click pattern and finds it in the recorded microphone input.
+ *
+ * Usage: play `render_pattern()` through the speakers while pushing the mic
+ * input with `push_input` from the moment playback starts, then call
+ * `compute`.
+ */
+export class LatencyCalibrator {
+    __destroy_into_raw() {
+        const ptr = this.__wbg_ptr;
+        this.__wbg_ptr = 0;
+        LatencyCalibratorFinalization.unregister(this);
+        return ptr;
+    }
+    free() {
+        const ptr = this.__destroy_into_raw();
+        wasm.__wbg_latencycalibrator_free(ptr, 0);
+    }
+    /**
+     * Estimate round-trip latency from the recorded input
+     * @returns {LatencyResult}
+     */
+    compute() {
+        const ret = wasm.latencycalibrator_compute(this.__wbg_ptr);
+        return LatencyResult.__wrap(ret);
+    }
+    /**
+     * @param {number} sample_rate
+     */
+    constructor(sample_rate) {
+        const ret = wasm.latencycalibrator_new(sample_rate);
+        this.__wbg_ptr = ret >>> 0;
+        LatencyCalibratorFinalization.register(this, this.__wbg_ptr, this);
+        return this;
+    }
+    /**
+     * Append microphone input. Returns true once enough input has been recorded.
+     * @param {Float32Array} samples
+     * @returns {boolean}
+     */
+    push_input(samples) {
+        const ptr0 = passArrayF32ToWasm0(samples, wasm.__wbindgen_export);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.latencycalibrator_push_input(this.__wbg_ptr, ptr0, len0);
+        return ret !== 0;
+    }
+    /**
+     * Render the calibration click pattern for playback
+     * @returns {Float32Array}
+     */
+    render_pattern() {
+        try {
+            const retptr = wasm.__wbindgen_add_to_stack_pointer(-16);
+            wasm.latencycalibrator_render_pattern(retptr, this.__wbg_ptr);
+            var r0 = getDataViewMemory0().getInt32(retptr + 4 * 0, true);
+            var r1 = getDataViewMemory0().getInt32(retptr + 4 * 1, true);
+            var v1 = getArrayF32FromWasm0(r0, r1).slice();
+            wasm.__wbindgen_export3(r0, r1 * 4, 4);
+            return v1;
+        } finally {
+            wasm.__wbindgen_add_to_stack_pointer(16);
+        }
+    }
+    /**
+     * Discard recorded input, ready for another attempt
+     */
+    reset() {
+        wasm.latencycalibrator_reset(this.__wbg_ptr);
+    }
+}
+if (Symbol.dispose) LatencyCalibrator.prototype[Symbol.dispose] = LatencyCalibrator.prototype.free;
+
+/**
+ * Latency calibration outcome
+ */
+export class LatencyResult {
+    static __wrap(ptr) {
+        ptr = ptr >>> 0;
+        const obj = Object.create(LatencyResult.prototype);
+        obj.__wbg_ptr = ptr;
+        LatencyResultFinalization.register(obj, obj.__wbg_ptr, obj);
+        return obj;
+    }
+    __destroy_into_raw() {
+        const ptr = this.__wbg_ptr;
+        this.__wbg_ptr = 0;
+        LatencyResultFinalization.unregister(this);
+        return ptr;
+    }
+    free() {
+        const ptr = this.__destroy_into_raw();
+        wasm.__wbg_latencyresult_free(ptr, 0);
+    }
+    /**
+     * Remove the measured latency from a rhythm offset (ms)
+     * @param {number} offset_ms
+     * @returns {number}
+     */
+    compensate(offset_ms) {
+        const ret = wasm.latencyresult_compensate(this.__wbg_ptr, offset_ms);
+        return ret;
+    }
+    /**
+     * @returns {number}
+     */
+    get confidence() {
+        const ret = wasm.latencyresult_confidence(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * @returns {number}
+     */
+    get detected_clicks() {
+        const ret = wasm.latencyresult_detected_clicks(this.__wbg_ptr);
+        return ret >>> 0;
+    }
+    /**
+     * @returns {number}
+     */
+    get latency_ms() {
+        const ret = wasm.latencyresult_latency_ms(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * @returns {number}
+     */
+    get latency_samples() {
+        const ret = wasm.latencyresult_latency_samples(this.__wbg_ptr);
+        return ret >>> 0;
+    }
+    /**
+     * @returns {number}
+     */
+    get spread_ms() {
+        const ret = wasm.latencyresult_spread_ms(this.__wbg_ptr);
+        return ret;
+    }
+}
+if (Symbol.dispose) LatencyResult.prototype[Symbol.dispose] = LatencyResult.prototype.free;
+
+/**
+ * Sample-accurate metronome that renders clicks for any tempo and meter.
+ *
+ * Sample position 0 is the first count-in beat (or bar 1 when there is no
+ * count-in). Rendering is stateless with respect to position, so blocks can
+ * be requested in any order and always line up on the same grid.
+ */
+export class Metronome {
+    __destroy_into_raw() {
+        const ptr = this.__wbg_ptr;
+        this.__wbg_ptr = 0;
+        MetronomeFinalization.unregister(this);
+        return ptr;
+    }
+    free() {
+        const ptr = this.__destroy_into_raw();
+        wasm.__wbg_metronome_free(ptr, 0);
+    }
+    /**
+     * @returns {number}
+     */
+    get beat_unit() {
+        const ret = wasm.metronome_beat_unit(this.__wbg_ptr);
+        return ret >>> 0;
+    }
+    /**
+     * @returns {number}
+     */
+    get beats_per_bar() {
+        const ret = wasm.metronome_beats_per_bar(this.__wbg_ptr);
+        return ret >>> 0;
+    }
+    /**
+     * @returns {number}
+     */
+    get bpm() {
+        const ret = wasm.metronome_bpm(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * @returns {number}
+     */
+    get count_in_bars() {
+        const ret = wasm.metronome_count_in_bars(this.__wbg_ptr);
+        return ret >>> 0;
+    }
+    /**
+     * Sample position at which bar 1 starts (the length of the count-in)
+     * @returns {number}
+     */
+    count_in_samples() {
+        const ret = wasm.metronome_count_in_samples(this.__wbg_ptr);
+        return ret >>> 0;
+    }
+    /**
+     * Create a new metronome in 4/4 with no subdivision or count-in
+     *
+     * # Arguments
+     * * `sample_rate` - Output sample rate (typically 48000)
+     * * `bpm` - Tempo in beats per minute
+     * @param {number} sample_rate
+     * @param {number} bpm
+     */
+    constructor(sample_rate, bpm) {
+        const ret = wasm.metronome_new(sample_rate, bpm);
+        this.__wbg_ptr = ret >>> 0;
+        MetronomeFinalization.register(this, this.__wbg_ptr, this);
+        return this;
+    }
+    /**
+     * Render `length` samples starting at absolute sample position `start`
+     * @param {number} start
+     * @param {number} length
+     * @returns {Float32Array}
+     */
+    render(start, length) {
+        try {
+            const retptr = wasm.__wbindgen_add_to_stack_pointer(-16);
+            wasm.metronome_render(retptr, this.__wbg_ptr, start, length);
+            var r0 = getDataViewMemory0().getInt32(retptr + 4 * 0, true);
+            var r1 = getDataViewMemory0().getInt32(retptr + 4 * 1, true);
+            var v1 = getArrayF32FromWasm0(r0, r1).slice();
+            wasm.__wbindgen_export3(r0, r1 * 4, 4);
+            return v1;
+        } finally {
+            wasm.__wbindgen_add_to_stack_pointer(16);
+        }
+    }
+    /**
+     * Render the count-in followed by `bars` bars as a single buffer
+     * @param {number} bars
+     * @returns {Float32Array}
+     */
+    render_bars(bars) {
+        try {
+            const retptr = wasm.__wbindgen_add_to_stack_pointer(-16);
+            wasm.metronome_render_bars(retptr, this.__wbg_ptr, bars);
+            var r0 = getDataViewMemory0().getInt32(retptr + 4 * 0, true);
+            var r1 = getDataViewMemory0().getInt32(retptr + 4 * 1, true);
+            var v1 = getArrayF32FromWasm0(r0, r1).slice();
+            wasm.__wbindgen_export3(r0, r1 * 4, 4);
+            return v1;
+        } finally {
+            wasm.__wbindgen_add_to_stack_pointer(16);
+        }
+    }
+    /**
+     * Mix clicks into `output`, whose first sample is at absolute position `start`
+     * @param {number} start
+     * @param {Float32Array} output
+     */
+    render_into(start, output) {
+        var ptr0 = passArrayF32ToWasm0(output, wasm.__wbindgen_export);
+        var len0 = WASM_VECTOR_LEN;
+        wasm.metronome_render_into(this.__wbg_ptr, start, ptr0, len0, addHeapObject(output));
+    }
+    /**
+     * Number of samples in one bar
+     * @returns {number}
+     */
+    samples_per_bar() {
+        const ret = wasm.metronome_samples_per_bar(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * Number of samples per beat (fractional, never rounded)
+     * @returns {number}
+     */
+    samples_per_beat() {
+        const ret = wasm.metronome_samples_per_beat(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * Set number of count-in bars played before bar 1 (clamped to 0-4)
+     * @param {number} bars
+     */
+    set_count_in(bars) {
+        wasm.metronome_set_count_in(this.__wbg_ptr, bars);
+    }
+    /**
+     * Set number of clicks per beat (clamped to 1-8)
+     * @param {number} clicks_per_beat
+     */
+    set_subdivision(clicks_per_beat) {
+        wasm.metronome_set_subdivision(this.__wbg_ptr, clicks_per_beat);
+    }
+    /**
+     * Set tempo in beats per minute (clamped to 20-300; NaN or infinite
+     * values select 120)
+     * @param {number} bpm
+     */
+    set_tempo(bpm) {
+        wasm.metronome_set_tempo(this.__wbg_ptr, bpm);
+    }
+    /**
+     * Set time signature from a song `time` field such as `"3/4"`.
+     * Returns false (leaving the meter unchanged) if the string is invalid.
+     * @param {string} time
+     * @returns {boolean}
+     */
+    set_time_signature(time) {
+        const ptr0 = passStringToWasm0(time, wasm.__wbindgen_export, wasm.__wbindgen_export2);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.metronome_set_time_signature(this.__wbg_ptr, ptr0, len0);
+        return ret !== 0;
+    }
+    /**
+     * Set output gain (clamped to 0.0-1.0)
+     * @param {number} volume
+     */
+    set_volume(volume) {
+        wasm.metronome_set_volume(this.__wbg_ptr, volume);
+    }
+    /**
+     * @returns {number}
+     */
+    get subdivision() {
+        const ret = wasm.metronome_subdivision(this.__wbg_ptr);
+        return ret >>> 0;
+    }
+    /**
+     * Kind of click played on tick `index`
+     * @param {number} index
+     * @returns {ClickKind}
+     */
+    tick_kind(index) {
+        const ret = wasm.metronome_tick_kind(this.__wbg_ptr, index);
+        return ret;
+    }
+    /**
+     * Sample position of the onset of tick `index`
+     * @param {number} index
+     * @returns {number}
+     */
+    tick_position(index) {
+        const ret = wasm.metronome_tick_position(this.__wbg_ptr, index);
+        return ret >>> 0;
+    }
+}
+if (Symbol.dispose) Metronome.prototype[Symbol.dispose] = Metronome.prototype.free;
+
+/**
+ * Pitch detector using autocorrelation algorithm
+ */
+export class PitchDetector {
+    static __wrap(ptr) {
+        ptr = ptr >>> 0;
+        const obj = Object.create(PitchDetector.prototype);
+        obj.__wbg_ptr = ptr;
+        PitchDetectorFinalization.register(obj, obj.__wbg_ptr, obj);
+        return obj;
+    }
     __destroy_into_raw() {
         const ptr = this.__wbg_ptr;
         this.__wbg_ptr = 0;
-        EchoBufferFinalization.unregister(this);
+        PitchDetectorFinalization.unregister(this);
         return ptr;
     }
     free() {
         const ptr = this.__destroy_into_raw();
-        wasm.__wbg_echobuffer_free(ptr, 0);
+        wasm.__wbg_pitchdetector_free(ptr, 0);
+    }
+    /**
+     * Apply a configuration at runtime. Buffers of any size may be passed to
+     * `detect` afterwards; internal scratch space grows as needed.
+     * @param {DetectorConfig} config
+     */
+    apply_config(config) {
+        _assertClass(config, DetectorConfig);
+        wasm.pitchdetector_apply_config(this.__wbg_ptr, config.__wbg_ptr);
+    }
+    /**
+     * Current configuration
+     * @returns {DetectorConfig}
+     */
+    config() {
+        const ret = wasm.pitchdetector_config(this.__wbg_ptr);
+        return DetectorConfig.__wrap(ret);
+    }
+    /**
+     * Analyze audio buffer and detect pitch
+     *
+     * # Arguments
+     * * `buffer` - Audio samples as f32 array
+     *
+     * # Returns
+     * PitchResult with detected frequency, note, cents, etc. NaN/infinite
+     * samples are treated as silence and reported through `input_health`;
+     * the pre-processing chain runs before detection.
+     * @param {Float32Array} buffer
+     * @returns {PitchResult}
+     */
+    detect(buffer) {
+        const ptr0 = passArrayF32ToWasm0(buffer, wasm.__wbindgen_export);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.pitchdetector_detect(this.__wbg_ptr, ptr0, len0);
+        return PitchResult.__wrap(ret);
+    }
+    /**
+     * Create a new pitch detector
+     *
+     * # Arguments
+     * * `sample_rate` - Audio sample rate (typically 48000)
+     * * `buffer_size` - FFT buffer size (typically 2048 or 4096)
+     * @param {number} sample_rate
+     * @param {number} buffer_size
+     */
+    constructor(sample_rate, buffer_size) {
+        const ret = wasm.pitchdetector_new(sample_rate, buffer_size);
+        this.__wbg_ptr = ret >>> 0;
+        PitchDetectorFinalization.register(this, this.__wbg_ptr, this);
+        return this;
+    }
+    /**
+     * Current input pre-processing settings
+     * @returns {PreprocessConfig}
+     */
+    preprocessing() {
+        const ret = wasm.pitchdetector_preprocessing(this.__wbg_ptr);
+        return PreprocessConfig.__wrap(ret);
+    }
+    /**
+     * Forget smoothing, pitch history and filter state (call between exercises)
+     */
+    reset() {
+        wasm.pitchdetector_reset(this.__wbg_ptr);
+    }
+    /**
+     * Enable or disable octave/fifth error correction
+     * @param {boolean} enabled
+     */
+    set_harmonic_correction(enabled) {
+        wasm.pitchdetector_set_harmonic_correction(this.__wbg_ptr, enabled);
+    }
+    /**
+     * Apply the range, tune tolerance, octave check and volume threshold of
+     * an instrument preset. Smoothing and the other settings are kept.
+     * @param {Instrument} instrument
+     */
+    set_instrument(instrument) {
+        wasm.pitchdetector_set_instrument(this.__wbg_ptr, instrument);
+    }
+    /**
+     * Set the median filter length in frames (rounded up to odd, 3-15)
+     * @param {number} frames
+     */
+    set_median_window(frames) {
+        wasm.pitchdetector_set_median_window(this.__wbg_ptr, frames);
+    }
+    /**
+     * Set the jump (cents) that note-aware smoothing treats as a new note
+     * @param {number} cents
+     */
+    set_note_change_cents(cents) {
+        wasm.pitchdetector_set_note_change_cents(this.__wbg_ptr, cents);
+    }
+    /**
+     * Set one-euro parameters: minimum cutoff (Hz) and speed coefficient
+     * @param {number} min_cutoff
+     * @param {number} beta
+     */
+    set_one_euro(min_cutoff, beta) {
+        wasm.pitchdetector_set_one_euro(this.__wbg_ptr, min_cutoff, beta);
+    }
+    /**
+     * Replace the input pre-processing chain. Filters keep state between
+     * calls to `detect`, so buffers must be consecutive pieces of one stream.
+     * @param {PreprocessConfig} config
+     */
+    set_preprocessing(config) {
+        _assertClass(config, PreprocessConfig);
+        wasm.pitchdetector_set_preprocessing(this.__wbg_ptr, config.__wbg_ptr);
+    }
+    /**
+     * Select the pitch smoothing strategy
+     * @param {SmoothingMode} mode
+     */
+    set_smoothing(mode) {
+        wasm.pitchdetector_set_smoothing(this.__wbg_ptr, mode);
+    }
+    /**
+     * Set the weight of the newest frame for exponential and note-aware
+     * smoothing (0.05 = very smooth, 1.0 = no smoothing)
+     * @param {number} alpha
+     */
+    set_smoothing_alpha(alpha) {
+        wasm.pitchdetector_set_smoothing_alpha(this.__wbg_ptr, alpha);
+    }
+    /**
+     * Set tune tolerance in cents
+     * @param {number} cents
+     */
+    set_tune_tolerance(cents) {
+        wasm.pitchdetector_set_tune_tolerance(this.__wbg_ptr, cents);
+    }
+    /**
+     * Set volume threshold for pitch detection
+     * @param {number} threshold
+     */
+    set_volume_threshold(threshold) {
+        wasm.pitchdetector_set_volume_threshold(this.__wbg_ptr, threshold);
+    }
+    /**
+     * Create a pitch detector with the given configuration
+     * @param {number} sample_rate
+     * @param {number} buffer_size
+     * @param {DetectorConfig} config
+     * @returns {PitchDetector}
+     */
+    static with_config(sample_rate, buffer_size, config) {
+        _assertClass(config, DetectorConfig);
+        const ret = wasm.pitchdetector_with_config(sample_rate, buffer_size, config.__wbg_ptr);
+        return PitchDetector.__wrap(ret);
+    }
+}
+if (Symbol.dispose) PitchDetector.prototype[Symbol.dispose] = PitchDetector.prototype.free;
+
+/**
+ * Pitch detection result
+ */
+export class PitchResult {
+    static __wrap(ptr) {
+        ptr = ptr >>> 0;
+        const obj = Object.create(PitchResult.prototype);
+        obj.__wbg_ptr = ptr;
+        PitchResultFinalization.register(obj, obj.__wbg_ptr, obj);
+        return obj;
+    }
+    __destroy_into_raw() {
+        const ptr = this.__wbg_ptr;
+        this.__wbg_ptr = 0;
+        PitchResultFinalization.unregister(this);
+        return ptr;
+    }
+    free() {
+        const ptr = this.__destroy_into_raw();
+        wasm.__wbg_pitchresult_free(ptr, 0);
+    }
+    /**
+     * @returns {number}
+     */
+    get cents() {
+        const ret = wasm.pitchresult_cents(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * @returns {number}
+     */
+    get clipping_percent() {
+        const ret = wasm.pitchresult_clipping_percent(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * @returns {number}
+     */
+    get confidence() {
+        const ret = wasm.pitchresult_confidence(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * @returns {boolean}
+     */
+    get corrected() {
+        const ret = wasm.pitchresult_corrected(this.__wbg_ptr);
+        return ret !== 0;
+    }
+    /**
+     * @returns {number}
+     */
+    get frequency() {
+        const ret = wasm.pitchresult_frequency(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * @returns {boolean}
+     */
+    get in_tune() {
+        const ret = wasm.pitchresult_in_tune(this.__wbg_ptr);
+        return ret !== 0;
+    }
+    /**
+     * @returns {InputHealth}
+     */
+    get input_health() {
+        const ret = wasm.pitchresult_input_health(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * @returns {string}
+     */
+    get note() {
+        let deferred1_0;
+        let deferred1_1;
+        try {
+            const retptr = wasm.__wbindgen_add_to_stack_pointer(-16);
+            wasm.pitchresult_note(retptr, this.__wbg_ptr);
+            var r0 = getDataViewMemory0().getInt32(retptr + 4 * 0, true);
+            var r1 = getDataViewMemory0().getInt32(retptr + 4 * 1, true);
+            deferred1_0 = r0;
+            deferred1_1 = r1;
+            return getStringFromWasm0(r0, r1);
+        } finally {
+            wasm.__wbindgen_add_to_stack_pointer(16);
+            wasm.__wbindgen_export3(deferred1_0, deferred1_1, 1);
+        }
+    }
+    /**
+     * @returns {number}
+     */
+    get raw_frequency() {
+        const ret = wasm.pitchresult_raw_frequency(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * @returns {number}
+     */
+    get voicing_probability() {
+        const ret = wasm.pitchresult_voicing_probability(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * @returns {number}
+     */
+    get volume() {
+        const ret = wasm.pitchresult_volume(this.__wbg_ptr);
+        return ret;
+    }
+}
+if (Symbol.dispose) PitchResult.prototype[Symbol.dispose] = PitchResult.prototype.free;
+
+/**
+ * Settings of the pre-processing chain run on the input before analysis.
+ * Stages run in field order and each can be enabled on its own.
+ */
+export class PreprocessConfig {
+    static __wrap(ptr) {
+        ptr = ptr >>> 0;
+        const obj = Object.create(PreprocessConfig.prototype);
+        obj.__wbg_ptr = ptr;
+        PreprocessConfigFinalization.register(obj, obj.__wbg_ptr, obj);
+        return obj;
+    }
+    __destroy_into_raw() {
+        const ptr = this.__wbg_ptr;
+        this.__wbg_ptr = 0;
+        PreprocessConfigFinalization.unregister(this);
+        return ptr;
+    }
+    free() {
+        const ptr = this.__destroy_into_raw();
+        wasm.__wbg_preprocessconfig_free(ptr, 0);
+    }
+    /**
+     * Subtract a running mean (removes DC offset)
+     * @returns {boolean}
+     */
+    get dc_blocker() {
+        const ret = wasm.__wbg_get_preprocessconfig_dc_blocker(this.__wbg_ptr);
+        return ret !== 0;
     }
     /**
-     * Simplified envelope extractor.
-     * Condenses 48kHz audio into `target_bins` (e.g., 400 slices) based on RMS amplitude.
-     * @param {number} target_bins
-     * @returns {Float32Array}
+     * High-pass cutoff (Hz)
+     * @returns {number}
      */
-    extract_envelope(target_bins) {
-        const ret = wasm.echobuffer_extract_envelope(this.__wbg_ptr, target_bins);
-        var v1 = getArrayF32FromWasm0(ret[0], ret[1]).slice();
-        wasm.__wbindgen_free(ret[0], ret[1] * 4, 4);
-        return v1;
+    get high_pass_hz() {
+        const ret = wasm.__wbg_get_detectorconfig_min_freq(this.__wbg_ptr);
+        return ret;
     }
     /**
-     * @param {number} capacity
+     * Second-order Butterworth high-pass (removes handling noise and rumble)
+     * @returns {boolean}
      */
-    constructor(capacity) {
-        const ret = wasm.echobuffer_new(capacity);
-        this.__wbg_ptr = ret >>> 0;
-        EchoBufferFinalization.register(this, this.__wbg_ptr, this);
-        return this;
+    get high_pass() {
+        const ret = wasm.__wbg_get_preprocessconfig_high_pass(this.__wbg_ptr);
+        return ret !== 0;
     }
     /**
-     * Push an array of audio samples into the linear buffer.
-     * Returns true if the buffer hit capacity during this write.
-     * @param {Float32Array} samples
+     * Mains frequency (50 or 60 Hz)
+     * @returns {number}
+     */
+    get hum_hz() {
+        const ret = wasm.__wbg_get_detectorconfig_max_freq(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * Narrow notches at the mains frequency and its second harmonic
      * @returns {boolean}
      */
-    push_chunk(samples) {
-        const ptr0 = passArrayF32ToWasm0(samples, wasm.__wbindgen_malloc);
-        const len0 = WASM_VECTOR_LEN;
-        const ret = wasm.echobuffer_push_chunk(this.__wbg_ptr, ptr0, len0);
+    get hum_notch() {
+        const ret = wasm.__wbg_get_preprocessconfig_hum_notch(this.__wbg_ptr);
         return ret !== 0;
     }
     /**
-     * Reset internal state, ready for a new recording
+     * Pre-emphasis coefficient `k`
+     * @returns {number}
      */
-    reset() {
-        wasm.echobuffer_reset(this.__wbg_ptr);
+    get pre_emphasis_coeff() {
+        const ret = wasm.__wbg_get_detectorconfig_volume_threshold(this.__wbg_ptr);
+        return ret;
     }
     /**
-     * @param {boolean} state
+     * First-order pre-emphasis, `y[n] = x[n] - k * x[n-1]`
+     * @returns {boolean}
      */
-    set_recording(state) {
-        wasm.echobuffer_set_recording(this.__wbg_ptr, state);
+    get pre_emphasis() {
+        const ret = wasm.__wbg_get_preprocessconfig_pre_emphasis(this.__wbg_ptr);
+        return ret !== 0;
     }
-}
-if (Symbol.dispose) EchoBuffer.prototype[Symbol.dispose] = EchoBuffer.prototype.free;
-
-/**
- * Pitch detector using autocorrelation algorithm
- */
-export class PitchDetector {
-    __destroy_into_raw() {
-        const ptr = this.__wbg_ptr;
-        this.__wbg_ptr = 0;
-        PitchDetectorFinalization.unregister(this);
-        return ptr;
+    /**
+     * Whether any stage is enabled
+     * @returns {boolean}
+     */
+    get active() {
+        const ret = wasm.preprocessconfig_active(this.__wbg_ptr);
+        return ret !== 0;
     }
-    free() {
-        const ptr = this.__destroy_into_raw();
-        wasm.__wbg_pitchdetector_free(ptr, 0);
+    /**
+     * Every stage disabled: samples pass through unchanged (non-finite ones
+     * are still zeroed)
+     * @returns {PreprocessConfig}
+     */
+    static bypass() {
+        const ret = wasm.preprocessconfig_bypass();
+        return PreprocessConfig.__wrap(ret);
     }
     /**
-     * Analyze audio buffer and detect pitch
-     *
-     * # Arguments
-     * * `buffer` - Audio samples as f32 array
-     *
-     * # Returns
-     * PitchResult with detected frequency, note, cents, etc.
-     * @param {Float32Array} buffer
-     * @returns {PitchResult}
+     * Build a config from a plain JS object with camelCase keys (e.g.
+     * `{ humNotch: true, humHz: 50 }`). Missing or invalid keys keep their
+     * defaults.
+     * @param {any} value
+     * @returns {PreprocessConfig}
      */
-    detect(buffer) {
-        const ptr0 = passArrayF32ToWasm0(buffer, wasm.__wbindgen_malloc);
-        const len0 = WASM_VECTOR_LEN;
-        const ret = wasm.pitchdetector_detect(this.__wbg_ptr, ptr0, len0);
-        return PitchResult.__wrap(ret);
+    static from_js(value) {
+        try {
+            const ret = wasm.preprocessconfig_from_js(addBorrowedObject(value));
+            return PreprocessConfig.__wrap(ret);
+        } finally {
+            heap[stack_pointer++] = undefined;
+        }
     }
     /**
-     * Create a new pitch detector
-     *
-     * # Arguments
-     * * `sample_rate` - Audio sample rate (typically 48000)
-     * * `buffer_size` - FFT buffer size (typically 2048 or 4096)
-     * @param {number} sample_rate
-     * @param {number} buffer_size
+     * Default chain: DC blocker and a 40 Hz high-pass; notch and
+     * pre-emphasis off
      */
-    constructor(sample_rate, buffer_size) {
-        const ret = wasm.pitchdetector_new(sample_rate, buffer_size);
+    constructor() {
+        const ret = wasm.preprocessconfig_new();
         this.__wbg_ptr = ret >>> 0;
-        PitchDetectorFinalization.register(this, this.__wbg_ptr, this);
+        PreprocessConfigFinalization.register(this, this.__wbg_ptr, this);
         return this;
     }
     /**
-     * Set tune tolerance in cents
-     * @param {number} cents
+     * Serialise to a plain JS object with camelCase keys (inverse of `from_js`)
+     * @returns {any}
      */
-    set_tune_tolerance(cents) {
-        wasm.pitchdetector_set_tune_tolerance(this.__wbg_ptr, cents);
+    to_js() {
+        const ret = wasm.preprocessconfig_to_js(this.__wbg_ptr);
+        return takeObject(ret);
     }
     /**
-     * Set volume threshold for pitch detection
-     * @param {number} threshold
+     * Copy with every value clamped to its supported range
+     * @returns {PreprocessConfig}
      */
-    set_volume_threshold(threshold) {
-        wasm.pitchdetector_set_volume_threshold(this.__wbg_ptr, threshold);
+    validated() {
+        const ret = wasm.preprocessconfig_validated(this.__wbg_ptr);
+        return PreprocessConfig.__wrap(ret);
+    }
+    /**
+     * Subtract a running mean (removes DC offset)
+     * @param {boolean} arg0
+     */
+    set dc_blocker(arg0) {
+        wasm.__wbg_set_preprocessconfig_dc_blocker(this.__wbg_ptr, arg0);
+    }
+    /**
+     * High-pass cutoff (Hz)
+     * @param {number} arg0
+     */
+    set high_pass_hz(arg0) {
+        wasm.__wbg_set_detectorconfig_min_freq(this.__wbg_ptr, arg0);
+    }
+    /**
+     * Second-order Butterworth high-pass (removes handling noise and rumble)
+     * @param {boolean} arg0
+     */
+    set high_pass(arg0) {
+        wasm.__wbg_set_preprocessconfig_high_pass(this.__wbg_ptr, arg0);
+    }
+    /**
+     * Mains frequency (50 or 60 Hz)
+     * @param {number} arg0
+     */
+    set hum_hz(arg0) {
+        wasm.__wbg_set_detectorconfig_max_freq(this.__wbg_ptr, arg0);
+    }
+    /**
+     * Narrow notches at the mains frequency and its second harmonic
+     * @param {boolean} arg0
+     */
+    set hum_notch(arg0) {
+        wasm.__wbg_set_preprocessconfig_hum_notch(this.__wbg_ptr, arg0);
+    }
+    /**
+     * Pre-emphasis coefficient `k`
+     * @param {number} arg0
+     */
+    set pre_emphasis_coeff(arg0) {
+        wasm.__wbg_set_detectorconfig_volume_threshold(this.__wbg_ptr, arg0);
+    }
+    /**
+     * First-order pre-emphasis, `y[n] = x[n] - k * x[n-1]`
+     * @param {boolean} arg0
+     */
+    set pre_emphasis(arg0) {
+        wasm.__wbg_set_preprocessconfig_pre_emphasis(this.__wbg_ptr, arg0);
     }
 }
-if (Symbol.dispose) PitchDetector.prototype[Symbol.dispose] = PitchDetector.prototype.free;
+if (Symbol.dispose) PreprocessConfig.prototype[Symbol.dispose] = PreprocessConfig.prototype.free;
 
 /**
- * Pitch detection result
+ * Main-thread side: reads frames in order from a ring shared with the worklet
  */
-export class PitchResult {
-    static __wrap(ptr) {
-        ptr = ptr >>> 0;
-        const obj = Object.create(PitchResult.prototype);
-        obj.__wbg_ptr = ptr;
-        PitchResultFinalization.register(obj, obj.__wbg_ptr, obj);
-        return obj;
-    }
+export class ResultRingReader {
     __destroy_into_raw() {
         const ptr = this.__wbg_ptr;
         this.__wbg_ptr = 0;
-        PitchResultFinalization.unregister(this);
+        ResultRingReaderFinalization.unregister(this);
         return ptr;
     }
     free() {
         const ptr = this.__destroy_into_raw();
-        wasm.__wbg_pitchresult_free(ptr, 0);
+        wasm.__wbg_resultringreader_free(ptr, 0);
     }
     /**
      * @returns {number}
      */
-    get cents() {
-        const ret = wasm.pitchresult_cents(this.__wbg_ptr);
-        return ret;
+    get capacity() {
+        const ret = wasm.resultringreader_capacity(this.__wbg_ptr);
+        return ret >>> 0;
     }
     /**
+     * Frames that were overwritten or skipped before being read
      * @returns {number}
      */
-    get confidence() {
-        const ret = wasm.pitchresult_confidence(this.__wbg_ptr);
-        return ret;
+    get dropped() {
+        const ret = wasm.resultringreader_dropped(this.__wbg_ptr);
+        return ret >>> 0;
     }
     /**
-     * @returns {number}
+     * Attach to a ring already initialised by a `ResultRingWriter`.
+     * Fails if `buffer` is not an ArrayBuffer or SharedArrayBuffer, or if the
+     * header's magic, version or record size do not match.
+     * @param {any} buffer
      */
-    get frequency() {
-        const ret = wasm.pitchresult_frequency(this.__wbg_ptr);
-        return ret;
+    constructor(buffer) {
+        try {
+            const retptr = wasm.__wbindgen_add_to_stack_pointer(-16);
+            wasm.resultringreader_new(retptr, addBorrowedObject(buffer));
+            var r0 = getDataViewMemory0().getInt32(retptr + 4 * 0, true);
+            var r1 = getDataViewMemory0().getInt32(retptr + 4 * 1, true);
+            var r2 = getDataViewMemory0().getInt32(retptr + 4 * 2, true);
+            if (r2) {
+                throw takeObject(r1);
+            }
+            this.__wbg_ptr = r0 >>> 0;
+            ResultRingReaderFinalization.register(this, this.__wbg_ptr, this);
+            return this;
+        } finally {
+            wasm.__wbindgen_add_to_stack_pointer(16);
+            heap[stack_pointer++] = undefined;
+        }
     }
     /**
+     * Copy only the newest frame into `out`, skipping any backlog
+     * @param {FrameRecord} out
      * @returns {boolean}
      */
-    get in_tune() {
-        const ret = wasm.pitchresult_in_tune(this.__wbg_ptr);
+    read_latest(out) {
+        _assertClass(out, FrameRecord);
+        const ret = wasm.resultringreader_read_latest(this.__wbg_ptr, out.__wbg_ptr);
         return ret !== 0;
     }
     /**
-     * @returns {string}
+     * Copy the next unread frame into `out`. Returns false when caught up.
+     * Frames overwritten before they were read are skipped and counted.
+     * @param {FrameRecord} out
+     * @returns {boolean}
      */
-    get note() {
-        let deferred1_0;
-        let deferred1_1;
+    read_next(out) {
+        _assertClass(out, FrameRecord);
+        const ret = wasm.resultringreader_read_next(this.__wbg_ptr, out.__wbg_ptr);
+        return ret !== 0;
+    }
+}
+if (Symbol.dispose) ResultRingReader.prototype[Symbol.dispose] = ResultRingReader.prototype.free;
+
+/**
+ * Worklet side: writes frames into a ring allocated with `ring_byte_length`
+ */
+export class ResultRingWriter {
+    __destroy_into_raw() {
+        const ptr = this.__wbg_ptr;
+        this.__wbg_ptr = 0;
+        ResultRingWriterFinalization.unregister(this);
+        return ptr;
+    }
+    free() {
+        const ptr = this.__destroy_into_raw();
+        wasm.__wbg_resultringwriter_free(ptr, 0);
+    }
+    /**
+     * @returns {number}
+     */
+    get capacity() {
+        const ret = wasm.resultringwriter_capacity(this.__wbg_ptr);
+        return ret >>> 0;
+    }
+    /**
+     * Initialise the header of `buffer` (a SharedArrayBuffer or ArrayBuffer).
+     * Fails for any other value or a buffer too small for one record.
+     * @param {any} buffer
+     */
+    constructor(buffer) {
         try {
-            const ret = wasm.pitchresult_note(this.__wbg_ptr);
-            deferred1_0 = ret[0];
-            deferred1_1 = ret[1];
-            return getStringFromWasm0(ret[0], ret[1]);
+            const retptr = wasm.__wbindgen_add_to_stack_pointer(-16);
+            wasm.resultringwriter_new(retptr, addBorrowedObject(buffer));
+            var r0 = getDataViewMemory0().getInt32(retptr + 4 * 0, true);
+            var r1 = getDataViewMemory0().getInt32(retptr + 4 * 1, true);
+            var r2 = getDataViewMemory0().getInt32(retptr + 4 * 2, true);
+            if (r2) {
+                throw takeObject(r1);
+            }
+            this.__wbg_ptr = r0 >>> 0;
+            ResultRingWriterFinalization.register(this, this.__wbg_ptr, this);
+            return this;
         } finally {
-            wasm.__wbindgen_free(deferred1_0, deferred1_1, 1);
+            wasm.__wbindgen_add_to_stack_pointer(16);
+            heap[stack_pointer++] = undefined;
         }
     }
     /**
+     * Publish a detection result with the worklet's onset/rhythm state.
+     * Returns the record's sequence number.
+     * @param {number} timestamp_ms
+     * @param {PitchResult} result
+     * @param {boolean} onset
+     * @param {number} onset_strength
+     * @param {number} tempo_bpm
+     * @param {number} rhythm_offset_ms
+     * @param {boolean} has_signal
      * @returns {number}
      */
-    get volume() {
-        const ret = wasm.pitchresult_volume(this.__wbg_ptr);
-        return ret;
+    write(timestamp_ms, result, onset, onset_strength, tempo_bpm, rhythm_offset_ms, has_signal) {
+        _assertClass(result, PitchResult);
+        const ret = wasm.resultringwriter_write(this.__wbg_ptr, timestamp_ms, result.__wbg_ptr, onset, onset_strength, tempo_bpm, rhythm_offset_ms, has_signal);
+        return ret >>> 0;
     }
 }
-if (Symbol.dispose) PitchResult.prototype[Symbol.dispose] = PitchResult.prototype.free;
+if (Symbol.dispose) ResultRingWriter.prototype[Symbol.dispose] = ResultRingWriter.prototype.free;
+
+/**
+ * Pitch smoothing strategy. All strategies work on a semitone (log-frequency)
+ * scale so their parameters mean the same thing across the whole range, and
+ * all of them start afresh after an unvoiced frame.
+ * @enum {0 | 1 | 2 | 3 | 4}
+ */
+export const SmoothingMode = Object.freeze({
+    /**
+     * Report every frame as detected
+     */
+    None: 0, "0": "None",
+    /**
+     * Exponential blend with the previous value (the default). Unlike the
+     * original detector, which blended in Hz and carried the last pitch
+     * across silence, it blends semitones and does not glide in from the
+     * previous note.
+     */
+    Exponential: 1, "1": "Exponential",
+    /**
+     * Median of the last few frames (rejects single-frame outliers)
+     */
+    Median: 2, "2": "Median",
+    /**
+     * One-euro filter: steady when the pitch holds, responsive when it moves
+     */
+    OneEuro: 3, "3": "OneEuro",
+    /**
+     * Exponential blend that restarts as soon as a new note begins
+     */
+    NoteAware: 4, "4": "NoteAware",
+});
 
-let _isInit = false;
 export function init() {
-    if (_isInit) return;
     wasm.init();
-    _isInit = true;
+}
+
+/**
+ * Run the pre-processing chain over a complete recording
+ * @param {Float32Array} samples
+ * @param {number} sample_rate
+ * @param {PreprocessConfig} config
+ * @returns {Float32Array}
+ */
+export function preprocess_recording(samples, sample_rate, config) {
+    try {
+        const retptr = wasm.__wbindgen_add_to_stack_pointer(-16);
+        const ptr0 = passArrayF32ToWasm0(samples, wasm.__wbindgen_export);
+        const len0 = WASM_VECTOR_LEN;
+        _assertClass(config, PreprocessConfig);
+        wasm.preprocess_recording(retptr, ptr0, len0, sample_rate, config.__wbg_ptr);
+        var r0 = getDataViewMemory0().getInt32(retptr + 4 * 0, true);
+        var r1 = getDataViewMemory0().getInt32(retptr + 4 * 1, true);
+        var v2 = getArrayF32FromWasm0(r0, r1).slice();
+        wasm.__wbindgen_export3(r0, r1 * 4, 4);
+        return v2;
+    } finally {
+        wasm.__wbindgen_add_to_stack_pointer(16);
+    }
+}
+
+/**
+ * Bytes needed for a ring holding `capacity` records
+ * @param {number} capacity
+ * @returns {number}
+ */
+export function ring_byte_length(capacity) {
+    const ret = wasm.ring_byte_length(capacity);
+    return ret >>> 0;
+}
+
+/**
+ * Layout version written to (and required in) the ring header
+ * @returns {number}
+ */
+export function ring_protocol_version() {
+    const ret = wasm.ring_protocol_version();
+    return ret >>> 0;
 }
 
 function __wbg_get_imports() {
     const import0 = {
         __proto__: null,
-        __wbg___wbindgen_throw_be289d5034ed271b: function (arg0, arg1) {
+        __wbg___wbindgen_boolean_get_bbbb1c18aa2f5e25: function(arg0) {
+            const v = getObject(arg0);
+            const ret = typeof(v) === 'boolean' ? v : undefined;
+            return isLikeNone(ret) ? 0xFFFFFF : ret ? 1 : 0;
+        },
+        __wbg___wbindgen_copy_to_typed_array_fc0809a4dec43528: function(arg0, arg1, arg2) {
+            new Uint8Array(getObject(arg2).buffer, getObject(arg2).byteOffset, getObject(arg2).byteLength).set(getArrayU8FromWasm0(arg0, arg1));
+        },
+        __wbg___wbindgen_is_object_5ae8e5880f2c1fbd: function(arg0) {
+            const val = getObject(arg0);
+            const ret = typeof(val) === 'object' && val !== null;
+            return ret;
+        },
+        __wbg___wbindgen_number_get_8ff4255516ccad3e: function(arg0, arg1) {
+            const obj = getObject(arg1);
+            const ret = typeof(obj) === 'number' ? obj : undefined;
+            getDataViewMemory0().setFloat64(arg0 + 8 * 1, isLikeNone(ret) ? 0 : ret, true);
+            getDataViewMemory0().setInt32(arg0 + 4 * 0, !isLikeNone(ret), true);
+        },
+        __wbg___wbindgen_string_get_72fb696202c56729: function(arg0, arg1) {
+            const obj = getObject(arg1);
+            const ret = typeof(obj) === 'string' ? obj : undefined;
+            var ptr1 = isLikeNone(ret) ? 0 : passStringToWasm0(ret, wasm.__wbindgen_export, wasm.__wbindgen_export2);
+            var len1 = WASM_VECTOR_LEN;
+            getDataViewMemory0().setInt32(arg0 + 4 * 1, len1, true);
+            getDataViewMemory0().setInt32(arg0 + 4 * 0, ptr1, true);
+        },
+        __wbg___wbindgen_throw_be289d5034ed271b: function(arg0, arg1) {
             throw new Error(getStringFromWasm0(arg0, arg1));
         },
-        __wbg_error_7534b8e9a36f1ab4: function (arg0, arg1) {
+        __wbg_byteLength_7b03c17ff1e4037f: function(arg0) {
+            const ret = getObject(arg0).byteLength;
+            return ret;
+        },
+        __wbg_byteLength_b5d783d06792ed2c: function(arg0) {
+            const ret = getObject(arg0).byteLength;
+            return ret;
+        },
+        __wbg_error_7534b8e9a36f1ab4: function(arg0, arg1) {
             let deferred0_0;
             let deferred0_1;
             try {
@@ -220,28 +2281,87 @@ function __wbg_get_imports() {
                 deferred0_1 = arg1;
                 console.error(getStringFromWasm0(arg0, arg1));
             } finally {
-                wasm.__wbindgen_free(deferred0_0, deferred0_1, 1);
+                wasm.__wbindgen_export3(deferred0_0, deferred0_1, 1);
+            }
+        },
+        __wbg_get_b3ed3ad4be2bc8ac: function() { return handleError(function (arg0, arg1) {
+            const ret = Reflect.get(getObject(arg0), getObject(arg1));
+            return addHeapObject(ret);
+        }, arguments); },
+        __wbg_get_index_253dd2a9e007656d: function(arg0, arg1) {
+            const ret = getObject(arg0)[arg1 >>> 0];
+            return ret;
+        },
+        __wbg_instanceof_ArrayBuffer_c367199e2fa2aa04: function(arg0) {
+            let result;
+            try {
+                result = getObject(arg0) instanceof ArrayBuffer;
+            } catch (_) {
+                result = false;
+            }
+            const ret = result;
+            return ret;
+        },
+        __wbg_instanceof_SharedArrayBuffer_91d8a2fa16a2cc26: function(arg0) {
+            let result;
+            try {
+                result = getObject(arg0) instanceof SharedArrayBuffer;
+            } catch (_) {
+                result = false;
             }
+            const ret = result;
+            return ret;
+        },
+        __wbg_length_b1593d937f31cef9: function(arg0) {
+            const ret = getObject(arg0).length;
+            return ret;
+        },
+        __wbg_load_eff64ff5e429a422: function() { return handleError(function (arg0, arg1) {
+            const ret = Atomics.load(getObject(arg0), arg1 >>> 0);
+            return ret;
+        }, arguments); },
+        __wbg_new_361308b2356cecd0: function() {
+            const ret = new Object();
+            return addHeapObject(ret);
+        },
+        __wbg_new_72c627ba80de1c21: function(arg0) {
+            const ret = new Int32Array(getObject(arg0));
+            return addHeapObject(ret);
         },
-        __wbg_new_8a6f238a6ece86ea: function () {
+        __wbg_new_8a6f238a6ece86ea: function() {
             const ret = new Error();
+            return addHeapObject(ret);
+        },
+        __wbg_set_6cb8631f80447a67: function() { return handleError(function (arg0, arg1, arg2) {
+            const ret = Reflect.set(getObject(arg0), getObject(arg1), getObject(arg2));
             return ret;
+        }, arguments); },
+        __wbg_set_index_f259b54e97f7b177: function(arg0, arg1, arg2) {
+            getObject(arg0)[arg1 >>> 0] = arg2;
         },
-        __wbg_stack_0ed75d68575b0f3c: function (arg0, arg1) {
-            const ret = arg1.stack;
-            const ptr1 = passStringToWasm0(ret, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        __wbg_stack_0ed75d68575b0f3c: function(arg0, arg1) {
+            const ret = getObject(arg1).stack;
+            const ptr1 = passStringToWasm0(ret, wasm.__wbindgen_export, wasm.__wbindgen_export2);
             const len1 = WASM_VECTOR_LEN;
             getDataViewMemory0().setInt32(arg0 + 4 * 1, len1, true);
             getDataViewMemory0().setInt32(arg0 + 4 * 0, ptr1, true);
         },
-        __wbindgen_init_externref_table: function () {
-            const table = wasm.__wbindgen_externrefs;
-            const offset = table.grow(4);
-            table.set(0, undefined);
-            table.set(offset + 0, undefined);
-            table.set(offset + 1, null);
-            table.set(offset + 2, true);
-            table.set(offset + 3, false);
+        __wbg_store_d1d951c98d9bed41: function() { return handleError(function (arg0, arg1, arg2) {
+            const ret = Atomics.store(getObject(arg0), arg1 >>> 0, arg2);
+            return ret;
+        }, arguments); },
+        __wbindgen_cast_0000000000000001: function(arg0) {
+            // Cast intrinsic for `F64 -> Externref`.
+            const ret = arg0;
+            return addHeapObject(ret);
+        },
+        __wbindgen_cast_0000000000000002: function(arg0, arg1) {
+            // Cast intrinsic for `Ref(String) -> Externref`.
+            const ret = getStringFromWasm0(arg0, arg1);
+            return addHeapObject(ret);
+        },
+        __wbindgen_object_drop_ref: function(arg0) {
+            takeObject(arg0);
         },
     };
     return {
@@ -250,21 +2370,99 @@ function __wbg_get_imports() {
     };
 }
 
+const ChannelSelectorFinalization = (typeof FinalizationRegistry === 'undefined')
+    ? { register: () => {}, unregister: () => {} }
+    : new FinalizationRegistry(ptr => wasm.__wbg_channelselector_free(ptr >>> 0, 1));
+const ContourRecorderFinalization = (typeof FinalizationRegistry === 'undefined')
+    ? { register: () => {}, unregister: () => {} }
+    : new FinalizationRegistry(ptr => wasm.__wbg_contourrecorder_free(ptr >>> 0, 1));
+const DetectorConfigFinalization = (typeof FinalizationRegistry === 'undefined')
+    ? { register: () => {}, unregister: () => {} }
+    : new FinalizationRegistry(ptr => wasm.__wbg_detectorconfig_free(ptr >>> 0, 1));
 const EchoBufferFinalization = (typeof FinalizationRegistry === 'undefined')
-    ? { register: () => { }, unregister: () => { } }
+    ? { register: () => {}, unregister: () => {} }
     : new FinalizationRegistry(ptr => wasm.__wbg_echobuffer_free(ptr >>> 0, 1));
+const EchoTakeMetadataFinalization = (typeof FinalizationRegistry === 'undefined')
+    ? { register: () => {}, unregister: () => {} }
+    : new FinalizationRegistry(ptr => wasm.__wbg_echotakemetadata_free(ptr >>> 0, 1));
+const FrameRecordFinalization = (typeof FinalizationRegistry === 'undefined')
+    ? { register: () => {}, unregister: () => {} }
+    : new FinalizationRegistry(ptr => wasm.__wbg_framerecord_free(ptr >>> 0, 1));
+const InstrumentPresetFinalization = (typeof FinalizationRegistry === 'undefined')
+    ? { register: () => {}, unregister: () => {} }
+    : new FinalizationRegistry(ptr => wasm.__wbg_instrumentpreset_free(ptr >>> 0, 1));
+const LatencyCalibratorFinalization = (typeof FinalizationRegistry === 'undefined')
+    ? { register: () => {}, unregister: () => {} }
+    : new FinalizationRegistry(ptr => wasm.__wbg_latencycalibrator_free(ptr >>> 0, 1));
+const LatencyResultFinalization = (typeof FinalizationRegistry === 'undefined')
+    ? { register: () => {}, unregister: () => {} }
+    : new FinalizationRegistry(ptr => wasm.__wbg_latencyresult_free(ptr >>> 0, 1));
+const MetronomeFinalization = (typeof FinalizationRegistry === 'undefined')
+    ? { register: () => {}, unregister: () => {} }
+    : new FinalizationRegistry(ptr => wasm.__wbg_metronome_free(ptr >>> 0, 1));
 const PitchDetectorFinalization = (typeof FinalizationRegistry === 'undefined')
-    ? { register: () => { }, unregister: () => { } }
+    ? { register: () => {}, unregister: () => {} }
     : new FinalizationRegistry(ptr => wasm.__wbg_pitchdetector_free(ptr >>> 0, 1));
 const PitchResultFinalization = (typeof FinalizationRegistry === 'undefined')
-    ? { register: () => { }, unregister: () => { } }
+    ? { register: () => {}, unregister: () => {} }
     : new FinalizationRegistry(ptr => wasm.__wbg_pitchresult_free(ptr >>> 0, 1));
+const PreprocessConfigFinalization = (typeof FinalizationRegistry === 'undefined')
+    ? { register: () => {}, unregister: () => {} }
+    : new FinalizationRegistry(ptr => wasm.__wbg_preprocessconfig_free(ptr >>> 0, 1));
+const ResultRingReaderFinalization = (typeof FinalizationRegistry === 'undefined')
+    ? { register: () => {}, unregister: () => {} }
+    : new FinalizationRegistry(ptr => wasm.__wbg_resultringreader_free(ptr >>> 0, 1));
+const ResultRingWriterFinalization = (typeof FinalizationRegistry === 'undefined')
+    ? { register: () => {}, unregister: () => {} }
+    : new FinalizationRegistry(ptr => wasm.__wbg_resultringwriter_free(ptr >>> 0, 1));
+
+function addHeapObject(obj) {
+    if (heap_next === heap.length) heap.push(heap.length + 1);
+    const idx = heap_next;
+    heap_next = heap[idx];
+
+    heap[idx] = obj;
+    return idx;
+}
+
+function _assertClass(instance, klass) {
+    if (!(instance instanceof klass)) {
+        throw new Error(`expected instance of ${klass.name}`);
+    }
+}
+
+function addBorrowedObject(obj) {
+    if (stack_pointer == 1) throw new Error('out of js stack');
+    heap[--stack_pointer] = obj;
+    return stack_pointer;
+}
+
+function dropObject(idx) {
+    if (idx < 132) return;
+    heap[idx] = heap_next;
+    heap_next = idx;
+}
 
 function getArrayF32FromWasm0(ptr, len) {
     ptr = ptr >>> 0;
     return getFloat32ArrayMemory0().subarray(ptr / 4, ptr / 4 + len);
 }
 
+function getArrayJsValueFromWasm0(ptr, len) {
+    ptr = ptr >>> 0;
+    const mem = getDataViewMemory0();
+    const result = [];
+    for (let i = ptr; i < ptr + 4 * len; i += 4) {
+        result.push(takeObject(mem.getUint32(i, true)));
+    }
+    return result;
+}
+
+function getArrayU8FromWasm0(ptr, len) {
+    ptr = ptr >>> 0;
+    return getUint8ArrayMemory0().subarray(ptr / 1, ptr / 1 + len);
+}
+
 let cachedDataViewMemory0 = null;
 function getDataViewMemory0() {
     if (cachedDataViewMemory0 === null || cachedDataViewMemory0.buffer.detached === true || (cachedDataViewMemory0.buffer.detached === undefined && cachedDataViewMemory0.buffer !== wasm.memory.buffer)) {
@@ -294,6 +2492,25 @@ function getUint8ArrayMemory0() {
     return cachedUint8ArrayMemory0;
 }
 
+function getObject(idx) { return heap[idx]; }
+
+function handleError(f, args) {
+    try {
+        return f.apply(this, args);
+    } catch (e) {
+        wasm.__wbindgen_export4(addHeapObject(e));
+    }
+}
+
+let heap = new Array(128).fill(undefined);
+heap.push(undefined, null, true, false);
+
+let heap_next = heap.length;
+
+function isLikeNone(x) {
+    return x === undefined || x === null;
+}
+
 function passArrayF32ToWasm0(arg, malloc) {
     const ptr = malloc(arg.length * 4, 4) >>> 0;
     getFloat32ArrayMemory0().set(arg, ptr / 4);
@@ -338,6 +2555,14 @@ function passStringToWasm0(arg, malloc, realloc) {
     return ptr;
 }
 
+let stack_pointer = 128;
+
+function takeObject(idx) {
+    const ret = getObject(idx);
+    dropObject(idx);
+    return ret;
+}
+
 let cachedTextDecoder = new TextDecoder('utf-8', { ignoreBOM: true, fatal: true });
 cachedTextDecoder.decode();
 const MAX_SAFARI_DECODE_BYTES = 2146435072;
@@ -419,7 +2644,7 @@ function initSync(module) {
 
     if (module !== undefined) {
         if (Object.getPrototypeOf(module) === Object.prototype) {
-            ({ module } = module)
+            ({module} = module)
         } else {
             console.warn('using deprecated parameters for `initSync()`; pass a single object instead')
         }
@@ -439,7 +2664,7 @@ async function __wbg_init(module_or_path) {
 
     if (module_or_path !== undefined) {
         if (Object.getPrototypeOf(module_or_path) === Object.prototype) {
-            ({ module_or_path } = module_or_path)
+            ({module_or_path} = module_or_path)
         } else {
             console.warn('using deprecated parameters for the initialization function; pass a single object instead')
         }
@@ -1,7 +1,16 @@
-import initWasm, { PitchDetector, EchoBuffer } from '../wasm/panda_audio.js';
+import initWasm, {
+    PitchDetector,
+    EchoBuffer,
+    ChannelSelector,
+    ChannelLayout,
+    ResultRingWriter,
+} from '../wasm/panda_audio.js';
 
 const wasmReady = initWasm();
 
+// With the shared result ring, channel levels are posted every this many frames
+const INPUT_CHANNELS_EVERY_FRAMES = 16;
+
 const median = (values) => {
     if (!values.length) return 0;
     const sorted = [...values].sort((a, b) => a - b);
@@ -13,7 +22,7 @@ const median = (values) => {
 };
 
 class RealtimeAudioProcessor extends AudioWorkletProcessor {
-    constructor() {
+    constructor(options) {
         super();
         this.bufferSize = 2048;
         this.buffer = new Float32Array(this.bufferSize);
@@ -32,6 +41,18 @@ class RealtimeAudioProcessor extends AudioWorkletProcessor {
         this.echoBuffer = null;
         this.echoRecording = false;
 
+        // Multi-channel input (e.g. a USB interface with the mic on input 2)
+        this.channelSelector = null;
+        this.planarScratch = null;
+        this.monoScratch = null;
+        // Reused for every frame message: { count, selected, levels }
+        this.inputChannels = null;
+
+        // Frames go through a SharedArrayBuffer ring when the page is
+        // cross-origin isolated; otherwise they are posted as messages
+        this.resultRing = options?.processorOptions?.resultRing || null;
+        this.ringWriter = null;
+
         this.ready = wasmReady
             .then(() => {
                 this.detector = new PitchDetector(sampleRate, this.bufferSize);
@@ -41,8 +62,19 @@ class RealtimeAudioProcessor extends AudioWorkletProcessor {
                 // Allocate 3 seconds at 48kHz (roughly 144,000 samples)
                 const echoCapacity = Math.floor(sampleRate * 3.0);
                 this.echoBuffer = new EchoBuffer(echoCapacity);
+                this.echoBuffer.set_sample_rate(sampleRate);
 
-                this.port.postMessage({ ready: true });
+                this.channelSelector = new ChannelSelector(sampleRate);
+
+                if (this.resultRing) {
+                    try {
+                        this.ringWriter = new ResultRingWriter(this.resultRing);
+                    } catch {
+                        this.ringWriter = null;
+                    }
+                }
+
+                this.port.postMessage({ ready: true, resultRing: Boolean(this.ringWriter) });
             })
             .catch((error) => {
                 this.port.postMessage({ error: 'WASM init failed', detail: `${error}` });
@@ -73,11 +105,18 @@ class RealtimeAudioProcessor extends AudioWorkletProcessor {
                     this.echoBuffer.free();
                     this.echoBuffer = null;
                 }
+                if (this.channelSelector && typeof this.channelSelector.free === 'function') {
+                    this.channelSelector.free();
+                    this.channelSelector = null;
+                }
+                if (this.ringWriter && typeof this.ringWriter.free === 'function') {
+                    this.ringWriter.free();
+                    this.ringWriter = null;
+                }
             }
             if (type === 'echo_record') {
                 if (this.echoBuffer) {
-                    this.echoBuffer.reset();
-                    this.echoBuffer.set_recording(true);
+                    this.echoBuffer.start_recording(currentTime * 1000);
                     this.echoRecording = true;
                 }
             }
@@ -133,7 +172,51 @@ class RealtimeAudioProcessor extends AudioWorkletProcessor {
     emitEchoEnvelope() {
         if (!this.echoBuffer) return;
         const envelope = this.echoBuffer.extract_envelope(400);
-        this.port.postMessage({ type: 'echo_envelope', payload: Array.from(envelope) });
+        const take = this.echoBuffer.metadata();
+        const metadata = take.to_js();
+        take.free();
+        this.port.postMessage({ type: 'echo_envelope', payload: Array.from(envelope), metadata });
+    }
+
+    selectInputChannel(input) {
+        if (input.length < 2 || !this.channelSelector) return input[0];
+
+        const frames = input[0].length;
+        if (!this.monoScratch || this.monoScratch.length !== frames) {
+            this.monoScratch = new Float32Array(frames);
+        }
+        if (!this.planarScratch || this.planarScratch.length !== frames * input.length) {
+            this.planarScratch = new Float32Array(frames * input.length);
+        }
+        for (let c = 0; c < input.length; c += 1) {
+            this.planarScratch.set(input[c], c * frames);
+        }
+        this.channelSelector.mix_into(
+            this.planarScratch,
+            input.length,
+            ChannelLayout.Planar,
+            this.monoScratch,
+        );
+        return this.monoScratch;
+    }
+
+    describeInputChannels(input) {
+        if (input.length < 2 || !this.channelSelector) return null;
+        if (!this.inputChannels || this.inputChannels.count !== input.length) {
+            this.inputChannels = { count: input.length, selected: 0, levels: new Float32Array(input.length) };
+        }
+        this.inputChannels.selected = this.channelSelector.selected_channel;
+        this.channelSelector.channel_levels_into(this.inputChannels.levels);
+        return this.inputChannels;
+    }
+
+    // Ring frames carry no channel levels; post them now and then instead
+    postInputChannels(input) {
+        if (this.frameCounter % INPUT_CHANNELS_EVERY_FRAMES !== 0) return;
+        const inputChannels = this.describeInputChannels(input);
+        if (inputChannels) {
+            this.port.postMessage({ type: 'input_channels', inputChannels });
+        }
     }
 
     process(inputs, outputs) {
@@ -146,7 +229,7 @@ class RealtimeAudioProcessor extends AudioWorkletProcessor {
 
         if (!input || !input[0] || !this.detector) return true;
 
-        const channel = input[0];
+        const channel = this.selectInputChannel(input);
         let offset = 0;
         while (offset < channel.length) {
             const space = this.bufferSize - this.bufferIndex;
@@ -174,23 +257,38 @@ class RealtimeAudioProcessor extends AudioWorkletProcessor {
             const onset = this.detectOnset(result.volume, nowMs);
             const tempoBpm = this.lastTempoBpm ? Math.round(this.lastTempoBpm * 10) / 10 : 0;
             const rhythmOffsetMs = this.getRhythmOffset(nowMs);
+            const hasSignal = result.volume >= this.noiseFloor;
 
             this.frameCounter += 1;
             if (this.frameCounter % 2 === 0) {
-                this.port.postMessage({
-                    timestamp: nowMs,
-                    frequency: result.frequency,
-                    note: result.note,
-                    cents: result.cents,
-                    volume: result.volume,
-                    inTune: result.in_tune,
-                    confidence: result.confidence,
-                    onset: onset.hasOnset,
-                    onsetStrength: onset.onsetStrength,
-                    tempoBpm,
-                    rhythmOffsetMs,
-                    hasSignal: result.volume >= this.noiseFloor,
-                });
+                if (this.ringWriter) {
+                    this.ringWriter.write(
+                        nowMs,
+                        result,
+                        onset.hasOnset,
+                        onset.onsetStrength,
+                        tempoBpm,
+                        rhythmOffsetMs,
+                        hasSignal,
+                    );
+                    this.postInputChannels(input);
+                } else {
+                    this.port.postMessage({
+                        timestamp: nowMs,
+                        frequency: result.frequency,
+                        note: result.note,
+                        cents: result.cents,
+                        volume: result.volume,
+                        inTune: result.in_tune,
+                        confidence: result.confidence,
+                        onset: onset.hasOnset,
+                        onsetStrength: onset.onsetStrength,
+                        tempoBpm,
+                        rhythmOffsetMs,
+                        hasSignal,
+                        inputChannels: this.describeInputChannels(input),
+                    });
+                }
             }
 
             this.bufferIndex = 0;
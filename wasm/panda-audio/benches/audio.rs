@@ -0,0 +1,73 @@
+//! Criterion benchmarks for the realtime and offline audio paths.
+//!
+//! Run with `cargo bench -p panda-audio`.
+
+#[path = "../tests/common/mod.rs"]
+mod common;
+
+use std::hint::black_box;
+
+use common::ViolinTone;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use panda_audio::{
+    analyze_recording, classify_articulation, compute_spectrogram, EchoBuffer, PitchDetector,
+};
+
+const SAMPLE_RATE: f32 = 48000.0;
+
+fn tone(frequency: f32, len: usize) -> Vec<f32> {
+    ViolinTone::new(frequency, SAMPLE_RATE).vibrato(5.5, 20.0).noise(0.02).render(0, len)
+}
+
+fn bench_detect(c: &mut Criterion) {
+    let mut group = c.benchmark_group("detect");
+    for size in [1024, 2048, 4096] {
+        let buffer = tone(196.0, size);
+        let mut detector = PitchDetector::new(SAMPLE_RATE, size);
+        group.throughput(Throughput::Elements(size as u64));
+        group.bench_with_input(BenchmarkId::new("g3_vibrato", size), &buffer, |b, buffer| {
+            b.iter(|| detector.detect(black_box(buffer)))
+        });
+    }
+
+    // Silence exits early on the volume gate
+    let silence = vec![0.0f32; 2048];
+    let mut detector = PitchDetector::new(SAMPLE_RATE, 2048);
+    group.bench_function("silence/2048", |b| b.iter(|| detector.detect(black_box(&silence))));
+    group.finish();
+}
+
+fn bench_envelope(c: &mut Criterion) {
+    let mut group = c.benchmark_group("echo_envelope");
+    for seconds in [2usize, 10] {
+        let samples = tone(440.0, seconds * SAMPLE_RATE as usize);
+        let mut echo = EchoBuffer::new(samples.len());
+        echo.set_recording(true);
+        for chunk in samples.chunks(2048) {
+            echo.push_chunk(chunk);
+        }
+        group.bench_with_input(BenchmarkId::new("extract_200_bins", seconds), &echo, |b, echo| {
+            b.iter(|| echo.extract_envelope(black_box(200)))
+        });
+    }
+    group.finish();
+}
+
+fn bench_offline(c: &mut Criterion) {
+    let mut group = c.benchmark_group("offline");
+    group.sample_size(10);
+    let recording = tone(293.66, 5 * SAMPLE_RATE as usize);
+    group.bench_function("analyze_recording/5s", |b| {
+        b.iter(|| analyze_recording(black_box(&recording), SAMPLE_RATE))
+    });
+    group.bench_function("classify_articulation/5s", |b| {
+        b.iter(|| classify_articulation(black_box(&recording), SAMPLE_RATE))
+    });
+    group.bench_function("compute_spectrogram/5s", |b| {
+        b.iter(|| compute_spectrogram(black_box(&recording), SAMPLE_RATE, 200, 64, 100.0, 8000.0))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_detect, bench_envelope, bench_offline);
+criterion_main!(benches);
@@ -0,0 +1,25 @@
+//! Arbitrary bytes as a sample rate, a buffer size and raw f32 samples
+//! (including NaN, infinities and subnormals).
+//!
+//! `cargo +nightly fuzz run detect`
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use panda_audio::PitchDetector;
+
+fuzz_target!(|data: &[u8]| {
+    if data.len() < 6 {
+        return;
+    }
+    let sample_rate = f32::from_le_bytes([data[0], data[1], data[2], data[3]]);
+    let buffer_size = u16::from_le_bytes([data[4], data[5]]) as usize;
+    let samples: Vec<f32> = data[6..]
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect();
+
+    let mut detector = PitchDetector::new(sample_rate, buffer_size);
+    let _ = detector.detect(&samples);
+    let _ = detector.detect(&samples);
+});
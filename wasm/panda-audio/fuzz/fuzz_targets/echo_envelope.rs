@@ -0,0 +1,28 @@
+//! Arbitrary capacity, chunking and bin count for the echo buffer.
+//!
+//! `cargo +nightly fuzz run echo_envelope`
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use panda_audio::EchoBuffer;
+
+fuzz_target!(|data: &[u8]| {
+    if data.len() < 5 {
+        return;
+    }
+    let capacity = u16::from_le_bytes([data[0], data[1]]) as usize;
+    let bins = u16::from_le_bytes([data[2], data[3]]) as usize;
+    let chunk = (data[4] as usize).max(1);
+    let samples: Vec<f32> = data[5..]
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect();
+
+    let mut echo = EchoBuffer::new(capacity);
+    echo.set_recording(true);
+    for part in samples.chunks(chunk) {
+        echo.push_chunk(part);
+    }
+    assert_eq!(echo.extract_envelope(bins).len(), bins);
+});
@@ -0,0 +1,26 @@
+//! Arbitrary recordings through the offline analysis paths.
+//!
+//! `cargo +nightly fuzz run offline_analysis`
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use panda_audio::{analyze_recording, classify_articulation, compute_chroma, compute_spectrogram};
+
+fuzz_target!(|data: &[u8]| {
+    if data.len() < 6 {
+        return;
+    }
+    let sample_rate = f32::from_le_bytes([data[0], data[1], data[2], data[3]]);
+    let time_bins = data[4] as usize;
+    let bands = data[5] as usize;
+    let samples: Vec<f32> = data[6..]
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect();
+
+    let _ = analyze_recording(&samples, sample_rate);
+    let _ = classify_articulation(&samples, sample_rate);
+    let _ = compute_spectrogram(&samples, sample_rate, time_bins, bands, 80.0, 8000.0);
+    let _ = compute_chroma(&samples, sample_rate, time_bins);
+});
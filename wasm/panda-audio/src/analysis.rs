@@ -0,0 +1,492 @@
+use wasm_bindgen::prelude::*;
+use crate::{
+    amplitude_to_db, clamp_sample_rate, compute_rms, frequency_to_midi, midi_to_note_name, DetectorConfig, PitchDetector,
+    SILENCE_DB,
+};
+use crate::preprocess::{preprocess_recording, PreprocessConfig};
+
+// ============================================================================
+// Offline Recording Analysis
+// ============================================================================
+
+/// Default analysis frame size (matches the worklet buffer size)
+pub(crate) const DEFAULT_FRAME_SIZE: usize = 2048;
+/// Default hop between analysis frames
+pub(crate) const DEFAULT_HOP_SIZE: usize = 512;
+
+/// Shortest note kept by segmentation (seconds)
+const MIN_NOTE_SECONDS: f32 = 0.06;
+/// Longest unvoiced gap bridged inside a note (seconds)
+const MAX_NOTE_GAP_SECONDS: f32 = 0.03;
+
+/// A sustained note found in a recording
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct NoteEvent {
+    /// Note start time (seconds)
+    start: f32,
+    /// Note end time (seconds)
+    end: f32,
+    /// MIDI note number (A4 = 69)
+    midi: i32,
+    /// Median detected frequency (Hz)
+    frequency: f32,
+    /// Mean deviation from the nominal pitch (cents)
+    cents: f32,
+    /// Mean detection confidence
+    confidence: f32,
+    /// Mean RMS volume
+    volume: f32,
+}
+
+#[wasm_bindgen]
+impl NoteEvent {
+    #[wasm_bindgen(getter)]
+    pub fn start(&self) -> f32 { self.start }
+
+    #[wasm_bindgen(getter)]
+    pub fn end(&self) -> f32 { self.end }
+
+    #[wasm_bindgen(getter)]
+    pub fn duration(&self) -> f32 { self.end - self.start }
+
+    #[wasm_bindgen(getter)]
+    pub fn midi(&self) -> i32 { self.midi }
+
+    #[wasm_bindgen(getter)]
+    pub fn note(&self) -> String { midi_to_note_name(self.midi) }
+
+    #[wasm_bindgen(getter)]
+    pub fn frequency(&self) -> f32 { self.frequency }
+
+    #[wasm_bindgen(getter)]
+    pub fn cents(&self) -> f32 { self.cents }
+
+    #[wasm_bindgen(getter)]
+    pub fn confidence(&self) -> f32 { self.confidence }
+
+    #[wasm_bindgen(getter)]
+    pub fn volume(&self) -> f32 { self.volume }
+}
+
+/// Complete analysis of a recording
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct AnalysisReport {
+    sample_rate: f32,
+    /// Total recording length (seconds)
+    duration: f32,
+    /// Time between frames (seconds)
+    hop_seconds: f32,
+    /// Frame centre times (seconds)
+    times: Vec<f32>,
+    /// Per-frame frequency (Hz, 0 when unvoiced)
+    frequencies: Vec<f32>,
+    /// Per-frame cents deviation (0 when unvoiced)
+    cents: Vec<i32>,
+    /// Per-frame detection confidence
+    confidences: Vec<f32>,
+    /// Per-frame loudness (dBFS)
+    loudness_db: Vec<f32>,
+    /// Per-frame in-tune flag
+    in_tune: Vec<bool>,
+    /// Segmented notes
+    notes: Vec<NoteEvent>,
+    /// Onset times (seconds)
+    onsets: Vec<f32>,
+}
+
+#[wasm_bindgen]
+impl AnalysisReport {
+    #[wasm_bindgen(getter)]
+    pub fn sample_rate(&self) -> f32 { self.sample_rate }
+
+    #[wasm_bindgen(getter)]
+    pub fn duration(&self) -> f32 { self.duration }
+
+    #[wasm_bindgen(getter)]
+    pub fn hop_seconds(&self) -> f32 { self.hop_seconds }
+
+    #[wasm_bindgen(getter)]
+    pub fn frame_count(&self) -> usize { self.times.len() }
+
+    #[wasm_bindgen(getter)]
+    pub fn times(&self) -> Vec<f32> { self.times.clone() }
+
+    #[wasm_bindgen(getter)]
+    pub fn frequencies(&self) -> Vec<f32> { self.frequencies.clone() }
+
+    #[wasm_bindgen(getter)]
+    pub fn cents(&self) -> Vec<i32> { self.cents.clone() }
+
+    #[wasm_bindgen(getter)]
+    pub fn confidences(&self) -> Vec<f32> { self.confidences.clone() }
+
+    #[wasm_bindgen(getter)]
+    pub fn loudness_db(&self) -> Vec<f32> { self.loudness_db.clone() }
+
+    #[wasm_bindgen(getter)]
+    pub fn notes(&self) -> Vec<NoteEvent> { self.notes.clone() }
+
+    #[wasm_bindgen(getter)]
+    pub fn onsets(&self) -> Vec<f32> { self.onsets.clone() }
+
+    /// Fraction of frames with a detected pitch
+    #[wasm_bindgen(getter)]
+    pub fn voiced_ratio(&self) -> f32 {
+        if self.frequencies.is_empty() {
+            return 0.0;
+        }
+        self.voiced_frames().count() as f32 / self.frequencies.len() as f32
+    }
+
+    /// Fraction of voiced frames that were in tune
+    #[wasm_bindgen(getter)]
+    pub fn in_tune_ratio(&self) -> f32 {
+        let voiced = self.voiced_frames().count();
+        if voiced == 0 {
+            return 0.0;
+        }
+        self.voiced_frames().filter(|&i| self.in_tune[i]).count() as f32 / voiced as f32
+    }
+
+    /// Mean signed cents deviation over voiced frames (positive = sharp)
+    #[wasm_bindgen(getter)]
+    pub fn mean_cents(&self) -> f32 {
+        mean(self.voiced_frames().map(|i| self.cents[i] as f32))
+    }
+
+    /// Mean absolute cents deviation over voiced frames
+    #[wasm_bindgen(getter)]
+    pub fn mean_abs_cents(&self) -> f32 {
+        mean(self.voiced_frames().map(|i| self.cents[i].abs() as f32))
+    }
+
+    /// Standard deviation of cents over voiced frames
+    #[wasm_bindgen(getter)]
+    pub fn cents_std_dev(&self) -> f32 {
+        let avg = self.mean_cents();
+        mean(self.voiced_frames().map(|i| (self.cents[i] as f32 - avg).powi(2))).sqrt()
+    }
+
+    /// Mean loudness over voiced frames (dBFS)
+    #[wasm_bindgen(getter)]
+    pub fn mean_loudness_db(&self) -> f32 {
+        let voiced = self.voiced_frames().count();
+        if voiced == 0 {
+            return SILENCE_DB;
+        }
+        mean(self.voiced_frames().map(|i| self.loudness_db[i]))
+    }
+
+    /// Loudest frame (dBFS)
+    #[wasm_bindgen(getter)]
+    pub fn peak_loudness_db(&self) -> f32 {
+        self.loudness_db.iter().copied().fold(SILENCE_DB, f32::max)
+    }
+}
+
+impl AnalysisReport {
+    fn voiced_frames(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.frequencies.len()).filter(|&i| self.frequencies[i] > 0.0)
+    }
+}
+
+fn mean(values: impl Iterator<Item = f32>) -> f32 {
+    let (sum, count) = values.fold((0.0f32, 0usize), |(s, c), v| (s + v, c + 1));
+    if count == 0 { 0.0 } else { sum / count as f32 }
+}
+
+/// Worklet block duration the onset smoothing constant was tuned for (seconds)
+const ONSET_REFERENCE_SECONDS: f32 = 2048.0 / 48000.0;
+
+/// Novelty below which a fired onset tracker re-arms (half the firing level)
+const ONSET_REARM_NOVELTY: f32 = 0.003;
+
+/// Energy-novelty onset detector using the realtime worklet's rule, with the
+/// energy average rescaled to the analysis hop. After firing it stays disarmed
+/// until the novelty falls away, so one attack yields one onset.
+struct OnsetTracker {
+    energy_ema: f32,
+    decay: f32,
+    armed: bool,
+    last_onset: Option<f32>,
+}
+
+impl OnsetTracker {
+    fn new(hop_seconds: f32) -> OnsetTracker {
+        OnsetTracker {
+            energy_ema: 0.0,
+            decay: 0.86f32.powf(hop_seconds / ONSET_REFERENCE_SECONDS),
+            armed: true,
+            last_onset: None,
+        }
+    }
+
+    /// Feed one frame's RMS volume; returns true if an onset occurred
+    fn process(&mut self, volume: f32, time: f32) -> bool {
+        self.energy_ema = if self.energy_ema > 0.0 {
+            self.energy_ema * self.decay + volume * (1.0 - self.decay)
+        } else {
+            volume
+        };
+        let novelty = (volume - self.energy_ema).max(0.0);
+        let strength = if novelty > 0.006 { (novelty * 60.0).min(1.0) } else { 0.0 };
+        if strength <= 0.35 {
+            // Re-arm only once the novelty has clearly fallen away, so level
+            // jitter around the threshold on a held note cannot re-trigger
+            if novelty < ONSET_REARM_NOVELTY {
+                self.armed = true;
+            }
+            return false;
+        }
+        let spaced = self.last_onset.is_none_or(|last| time - last > 0.09);
+        if self.armed && spaced {
+            self.armed = false;
+            self.last_onset = Some(time);
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Analyze a complete decoded recording with default frame and hop sizes
+#[wasm_bindgen]
+pub fn analyze_recording(samples: &[f32], sample_rate: f32) -> AnalysisReport {
+    analyze_recording_with(samples, sample_rate, DEFAULT_FRAME_SIZE, DEFAULT_HOP_SIZE)
+}
+
+/// Analyze a complete decoded recording with the default pre-processing
+///
+/// # Arguments
+/// * `samples` - Mono PCM samples
+/// * `sample_rate` - Sample rate of `samples`
+/// * `frame_size` - Analysis window (typically 2048)
+/// * `hop_size` - Samples between successive frames (typically 512)
+#[wasm_bindgen]
+pub fn analyze_recording_with(
+    samples: &[f32],
+    sample_rate: f32,
+    frame_size: usize,
+    hop_size: usize,
+) -> AnalysisReport {
+    analyze_recording_with_preprocessing(samples, sample_rate, frame_size, hop_size, &PreprocessConfig::new())
+}
+
+/// Analyze a complete decoded recording after running `preprocessing` over it
+#[wasm_bindgen]
+pub fn analyze_recording_with_preprocessing(
+    samples: &[f32],
+    sample_rate: f32,
+    frame_size: usize,
+    hop_size: usize,
+    preprocessing: &PreprocessConfig,
+) -> AnalysisReport {
+    let filtered = preprocess_recording(samples, sample_rate, preprocessing);
+    analyze_prepared(&filtered, sample_rate, frame_size, hop_size)
+}
+
+/// Analyze a recording that has already been pre-processed. Frames overlap,
+/// so filtering happens once over the whole recording rather than per frame.
+pub(crate) fn analyze_prepared(
+    samples: &[f32],
+    sample_rate: f32,
+    frame_size: usize,
+    hop_size: usize,
+) -> AnalysisReport {
+    analyze_prepared_with(samples, sample_rate, frame_size, hop_size, &DetectorConfig::new())
+}
+
+/// `analyze_prepared` with the detector set up by `config` (e.g. a wider
+/// pitch range) instead of the violin defaults
+pub(crate) fn analyze_prepared_with(
+    samples: &[f32],
+    sample_rate: f32,
+    frame_size: usize,
+    hop_size: usize,
+    config: &DetectorConfig,
+) -> AnalysisReport {
+    let sample_rate = clamp_sample_rate(sample_rate);
+    let frame_size = frame_size.max(64);
+    let hop_size = hop_size.max(1);
+    let hop_seconds = hop_size as f32 / sample_rate;
+
+    let mut detector = PitchDetector::new(sample_rate, frame_size);
+    detector.apply_config(config);
+    detector.set_preprocessing(&PreprocessConfig::bypass());
+    let mut onset_tracker = OnsetTracker::new(hop_seconds);
+
+    let mut report = AnalysisReport {
+        sample_rate,
+        duration: samples.len() as f32 / sample_rate,
+        hop_seconds,
+        times: Vec::new(),
+        frequencies: Vec::new(),
+        cents: Vec::new(),
+        confidences: Vec::new(),
+        loudness_db: Vec::new(),
+        in_tune: Vec::new(),
+        notes: Vec::new(),
+        onsets: Vec::new(),
+    };
+
+    let mut start = 0;
+    while start + frame_size <= samples.len() {
+        let frame = &samples[start..start + frame_size];
+        let time = (start + frame_size / 2) as f32 / sample_rate;
+        let result = detector.detect(frame);
+
+        let db = amplitude_to_db(compute_rms(frame));
+
+        if onset_tracker.process(result.volume, time) {
+            report.onsets.push(time);
+        }
+
+        report.times.push(time);
+        report.frequencies.push(result.frequency);
+        report.cents.push(result.cents);
+        report.confidences.push(result.confidence);
+        report.loudness_db.push(db);
+        report.in_tune.push(result.in_tune);
+
+        start += hop_size;
+    }
+
+    report.notes = segment_notes(&report);
+    report
+}
+
+/// Group consecutive voiced frames of the same pitch into notes.
+/// A new note also starts at an energy onset (e.g., a re-bowed repeated note).
+fn segment_notes(report: &AnalysisReport) -> Vec<NoteEvent> {
+    let max_gap = (MAX_NOTE_GAP_SECONDS / report.hop_seconds).ceil() as usize;
+    let mut notes = Vec::new();
+    let mut current: Vec<usize> = Vec::new();
+    let mut current_midi = 0;
+    let mut gap = 0;
+    let mut onset_idx = 0;
+
+    for i in 0..report.frequencies.len() {
+        let time = report.times[i];
+        let mut onset_here = false;
+        while onset_idx < report.onsets.len() && report.onsets[onset_idx] <= time {
+            onset_here = report.onsets[onset_idx] == time;
+            onset_idx += 1;
+        }
+
+        let frequency = report.frequencies[i];
+        if frequency <= 0.0 {
+            gap += 1;
+            if gap > max_gap && !current.is_empty() {
+                push_note(report, &current, current_midi, &mut notes);
+                current.clear();
+            }
+            continue;
+        }
+
+        let midi = frequency_to_midi(frequency).round() as i32;
+        if !current.is_empty() && (midi != current_midi || onset_here) {
+            push_note(report, &current, current_midi, &mut notes);
+            current.clear();
+        }
+        if current.is_empty() {
+            current_midi = midi;
+        }
+        current.push(i);
+        gap = 0;
+    }
+    if !current.is_empty() {
+        push_note(report, &current, current_midi, &mut notes);
+    }
+    notes
+}
+
+fn push_note(report: &AnalysisReport, frames: &[usize], midi: i32, notes: &mut Vec<NoteEvent>) {
+    let half_hop = report.hop_seconds / 2.0;
+    let start = report.times[frames[0]] - half_hop;
+    let end = report.times[frames[frames.len() - 1]] + half_hop;
+    if end - start < MIN_NOTE_SECONDS {
+        return;
+    }
+
+    let mut freqs: Vec<f32> = frames.iter().map(|&i| report.frequencies[i]).collect();
+    freqs.sort_by(|a, b| a.total_cmp(b));
+    let frequency = freqs[freqs.len() / 2];
+
+    let volumes = frames.iter().map(|&i| 10.0f32.powf(report.loudness_db[i] / 20.0));
+    notes.push(NoteEvent {
+        start: start.max(0.0),
+        end,
+        midi,
+        frequency,
+        cents: mean(frames.iter().map(|&i| report.cents[i] as f32)),
+        confidence: mean(frames.iter().map(|&i| report.confidences[i])),
+        volume: mean(volumes),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tone(frequency: f32, seconds: f32, sample_rate: f32) -> Vec<f32> {
+        let len = (seconds * sample_rate) as usize;
+        (0..len)
+            .map(|i| {
+                let t = i as f32 / sample_rate;
+                let phase = 2.0 * std::f32::consts::PI * frequency * t;
+                0.3 * phase.sin() + 0.15 * (2.0 * phase).sin()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_segments_notes_from_recording() {
+        let sample_rate = 48000.0;
+        let mut samples = vec![0.0; 4800];
+        samples.extend(tone(440.0, 0.5, sample_rate)); // A4
+        samples.extend(vec![0.0; 4800]);
+        samples.extend(tone(587.33, 0.5, sample_rate)); // D5
+        samples.extend(vec![0.0; 4800]);
+
+        let report = analyze_recording(&samples, sample_rate);
+        let notes = report.notes();
+        assert_eq!(notes.len(), 2);
+        assert_eq!(notes[0].note(), "A4");
+        assert_eq!(notes[1].note(), "D5");
+        assert!((notes[0].duration() - 0.5).abs() < 0.1);
+        assert_eq!(report.onsets().len(), 2);
+        assert!(report.mean_abs_cents() < 5.0);
+        assert!(report.voiced_ratio() > 0.5);
+    }
+
+    #[test]
+    fn test_silence_report_is_empty() {
+        let report = analyze_recording(&vec![0.0; 48000], 48000.0);
+        assert!(report.frame_count() > 0);
+        assert!(report.notes().is_empty());
+        assert!(report.onsets().is_empty());
+        assert_eq!(report.voiced_ratio(), 0.0);
+        assert_eq!(report.peak_loudness_db(), SILENCE_DB);
+    }
+
+    #[test]
+    fn test_onset_tracker_ignores_jitter_on_held_note() {
+        let hop_seconds = DEFAULT_HOP_SIZE as f32 / 48000.0;
+        let mut tracker = OnsetTracker::new(hop_seconds);
+        let mut onsets = 0;
+        for frame in 0..200 {
+            // Quiet lead-in, then a swelling held note with small bow-pressure
+            // bumps: novelty hovers just under the firing level between bumps
+            let volume = match frame {
+                0..10 => 0.01,
+                _ => 0.1 + 0.0002 * frame as f32 + if frame % 12 == 0 { 0.003 } else { 0.0 },
+            };
+            if tracker.process(volume, frame as f32 * hop_seconds) {
+                onsets += 1;
+            }
+        }
+        assert_eq!(onsets, 1);
+    }
+}
@@ -0,0 +1,300 @@
+use wasm_bindgen::prelude::*;
+use crate::analysis::{analyze_prepared, DEFAULT_FRAME_SIZE, DEFAULT_HOP_SIZE};
+use crate::preprocess::{preprocess_recording, PreprocessConfig};
+use crate::{amplitude_to_db, clamp_sample_rate, compute_rms, midi_to_note_name};
+
+// ============================================================================
+// Articulation (pizzicato / arco, staccato / legato)
+// ============================================================================
+
+/// How a note was played
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Articulation {
+    /// Plucked: near-instant attack followed by a free exponential decay
+    Pizzicato,
+    /// Bowed and connected to the next note
+    Legato,
+    /// Bowed, short and separated from the next note
+    Staccato,
+}
+
+/// Envelope resolution (seconds)
+const ENVELOPE_SECONDS: f32 = 0.005;
+/// Envelope level below which nothing is sounding (dBFS)
+const FLOOR_DB: f32 = -45.0;
+/// Rise (dB) over `RISE_WINDOW` frames that marks a new attack
+const RISE_DB: f32 = 9.0;
+const RISE_WINDOW: usize = 10;
+/// Boundaries closer than this (seconds) belong to the same note
+const MIN_NOTE_GAP_SECONDS: f32 = 0.08;
+/// A note is sounding while within this many dB of its peak
+const SOUNDING_DB: f32 = 20.0;
+/// Window after the peak over which the decay rate is measured (seconds)
+const DECAY_WINDOW_SECONDS: f32 = 0.1;
+/// Decay faster than this (dB/s) with a quick attack means pizzicato
+const PIZZICATO_DECAY_DB_PER_S: f32 = -25.0;
+const PIZZICATO_MAX_ATTACK_MS: f32 = 40.0;
+/// Silence (seconds) after a short bowed note that makes it staccato
+const STACCATO_MIN_GAP_SECONDS: f32 = 0.05;
+const STACCATO_MAX_SECONDS: f32 = 0.4;
+
+/// One note with its envelope measurements and label
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct ArticulatedNote {
+    /// Attack start (seconds)
+    start: f32,
+    /// Time the note fell more than 20 dB below its peak (seconds)
+    end: f32,
+    /// MIDI note number, or -1 if no stable pitch was found
+    midi: i32,
+    /// 10%-to-90% amplitude rise time (ms)
+    attack_ms: f32,
+    /// Level change just after the peak (dB/s; 0 for a sustained note)
+    decay_db_per_s: f32,
+    /// Silence before the next note (seconds)
+    gap: f32,
+    articulation: Articulation,
+}
+
+#[wasm_bindgen]
+impl ArticulatedNote {
+    #[wasm_bindgen(getter)]
+    pub fn start(&self) -> f32 { self.start }
+
+    #[wasm_bindgen(getter)]
+    pub fn end(&self) -> f32 { self.end }
+
+    #[wasm_bindgen(getter)]
+    pub fn midi(&self) -> i32 { self.midi }
+
+    #[wasm_bindgen(getter)]
+    pub fn note(&self) -> String {
+        if self.midi < 0 { "-".to_string() } else { midi_to_note_name(self.midi) }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn attack_ms(&self) -> f32 { self.attack_ms }
+
+    #[wasm_bindgen(getter)]
+    pub fn decay_db_per_s(&self) -> f32 { self.decay_db_per_s }
+
+    #[wasm_bindgen(getter)]
+    pub fn gap(&self) -> f32 { self.gap }
+
+    #[wasm_bindgen(getter)]
+    pub fn articulation(&self) -> Articulation { self.articulation }
+
+    /// Whether the note was bowed (legato or staccato)
+    #[wasm_bindgen(getter)]
+    pub fn arco(&self) -> bool { self.articulation != Articulation::Pizzicato }
+}
+
+/// Articulation of every note in a recording
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct ArticulationReport {
+    notes: Vec<ArticulatedNote>,
+}
+
+#[wasm_bindgen]
+impl ArticulationReport {
+    #[wasm_bindgen(getter)]
+    pub fn notes(&self) -> Vec<ArticulatedNote> { self.notes.clone() }
+
+    #[wasm_bindgen(getter)]
+    pub fn note_count(&self) -> usize { self.notes.len() }
+
+    /// Number of notes with `articulation`
+    #[wasm_bindgen]
+    pub fn count(&self, articulation: Articulation) -> usize {
+        self.notes.iter().filter(|n| n.articulation == articulation).count()
+    }
+
+    /// Fraction of notes with `articulation` (0.0 to 1.0), for checking
+    /// that the requested technique was used
+    #[wasm_bindgen]
+    pub fn fraction(&self, articulation: Articulation) -> f32 {
+        if self.notes.is_empty() {
+            return 0.0;
+        }
+        self.count(articulation) as f32 / self.notes.len() as f32
+    }
+}
+
+/// Envelope in dBFS at `ENVELOPE_SECONDS` resolution
+fn envelope_db(samples: &[f32], hop: usize) -> Vec<f32> {
+    samples
+        .chunks(hop)
+        .map(|chunk| amplitude_to_db(compute_rms(chunk)))
+        .collect()
+}
+
+/// Envelope frames where a new attack begins
+fn attack_frames(env: &[f32]) -> Vec<usize> {
+    let mut onsets = Vec::new();
+    let min_gap = (MIN_NOTE_GAP_SECONDS / ENVELOPE_SECONDS) as usize;
+    for k in 1..env.len() {
+        if env[k] < FLOOR_DB {
+            continue;
+        }
+        let window_start = k.saturating_sub(RISE_WINDOW);
+        let (low_idx, low) = env[window_start..k]
+            .iter()
+            .enumerate()
+            .fold((0, f32::INFINITY), |best, (i, &v)| if v < best.1 { (i, v) } else { best });
+        if env[k] - low < RISE_DB {
+            continue;
+        }
+        let onset = window_start + low_idx + 1;
+        if onsets.last().is_none_or(|&last| onset >= last + min_gap) {
+            onsets.push(onset);
+        }
+    }
+    onsets
+}
+
+/// Classify the articulation of every note in a recording (after the default
+/// pre-processing)
+#[wasm_bindgen]
+pub fn classify_articulation(samples: &[f32], sample_rate: f32) -> ArticulationReport {
+    let sample_rate = clamp_sample_rate(sample_rate);
+    let samples = &preprocess_recording(samples, sample_rate, &PreprocessConfig::new());
+    let hop = ((ENVELOPE_SECONDS * sample_rate) as usize).max(1);
+    let frame_seconds = hop as f32 / sample_rate;
+    let env = envelope_db(samples, hop);
+    let pitch_notes = analyze_prepared(samples, sample_rate, DEFAULT_FRAME_SIZE, DEFAULT_HOP_SIZE).notes();
+
+    // Attacks from the envelope plus slurred pitch changes from the pitch track
+    let min_gap = (MIN_NOTE_GAP_SECONDS / frame_seconds) as usize;
+    let mut boundaries = attack_frames(&env);
+    for note in &pitch_notes {
+        let frame = (note.start() / frame_seconds) as usize;
+        if boundaries.iter().all(|&b| b.abs_diff(frame) >= min_gap) {
+            boundaries.push(frame);
+        }
+    }
+    boundaries.sort_unstable();
+
+    let mut notes = Vec::new();
+    for (i, &start) in boundaries.iter().enumerate() {
+        let stop = boundaries.get(i + 1).copied().unwrap_or(env.len()).min(env.len());
+        if start >= stop {
+            continue;
+        }
+        let segment = &env[start..stop];
+        let (peak_idx, peak) = segment
+            .iter()
+            .enumerate()
+            .fold((0, f32::NEG_INFINITY), |best, (i, &v)| if v > best.1 { (i, v) } else { best });
+        if peak < FLOOR_DB {
+            continue;
+        }
+
+        // 10% amplitude is -20 dB, 90% is about -1 dB
+        let rise_start = segment.iter().position(|&v| v >= peak - 20.0).unwrap_or(0);
+        let rise_end = segment.iter().position(|&v| v >= peak - 1.0).unwrap_or(peak_idx);
+        let attack_ms = (rise_end.saturating_sub(rise_start)) as f32 * frame_seconds * 1000.0;
+
+        let sounding_end = segment.iter().rposition(|&v| v >= peak - SOUNDING_DB).unwrap_or(peak_idx) + 1;
+        let decay_frames = ((DECAY_WINDOW_SECONDS / frame_seconds) as usize)
+            .min((sounding_end - peak_idx) / 2)
+            .max(1);
+        let decay_end = (peak_idx + decay_frames).min(segment.len() - 1);
+        let decay_db_per_s = if decay_end > peak_idx {
+            (segment[decay_end] - peak) / ((decay_end - peak_idx) as f32 * frame_seconds)
+        } else {
+            0.0
+        };
+
+        let start_time = start as f32 * frame_seconds;
+        let end_time = (start + sounding_end) as f32 * frame_seconds;
+        let gap = (stop - start - sounding_end) as f32 * frame_seconds;
+
+        let articulation = if decay_db_per_s < PIZZICATO_DECAY_DB_PER_S && attack_ms < PIZZICATO_MAX_ATTACK_MS {
+            Articulation::Pizzicato
+        } else if gap >= STACCATO_MIN_GAP_SECONDS && end_time - start_time <= STACCATO_MAX_SECONDS {
+            Articulation::Staccato
+        } else {
+            Articulation::Legato
+        };
+
+        // Pitch of the detected note overlapping this one the most
+        let midi = pitch_notes
+            .iter()
+            .map(|n| (n.end().min(end_time) - n.start().max(start_time), n.midi()))
+            .filter(|&(overlap, _)| overlap > 0.0)
+            .max_by(|a, b| a.0.total_cmp(&b.0))
+            .map_or(-1, |(_, midi)| midi);
+
+        notes.push(ArticulatedNote {
+            start: start_time,
+            end: end_time,
+            midi,
+            attack_ms,
+            decay_db_per_s,
+            gap,
+            articulation,
+        });
+    }
+
+    ArticulationReport { notes }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SR: f32 = 48000.0;
+
+    /// A string tone shaped by `envelope(t)` followed by `silence` seconds
+    fn note(out: &mut Vec<f32>, frequency: f32, seconds: f32, silence: f32, envelope: impl Fn(f32) -> f32) {
+        let len = (seconds * SR) as usize;
+        for i in 0..len {
+            let t = i as f32 / SR;
+            let phase = 2.0 * std::f32::consts::PI * frequency * t;
+            out.push(envelope(t) * (0.3 * phase.sin() + 0.15 * (2.0 * phase).sin() + 0.08 * (3.0 * phase).sin()));
+        }
+        out.resize(out.len() + (silence * SR) as usize, 0.0);
+    }
+
+    fn pluck(t: f32) -> f32 {
+        (t / 0.002).min(1.0) * (-t / 0.12).exp()
+    }
+
+    fn bow(seconds: f32) -> impl Fn(f32) -> f32 {
+        move |t| (t / 0.06).min(1.0) * ((seconds - t) / 0.02).min(1.0)
+    }
+
+    #[test]
+    fn test_pizzicato_notes() {
+        let mut audio = Vec::new();
+        for frequency in [392.0, 440.0, 493.88] {
+            note(&mut audio, frequency, 0.6, 0.0, pluck);
+        }
+        let report = classify_articulation(&audio, SR);
+        assert_eq!(report.note_count(), 3);
+        assert_eq!(report.fraction(Articulation::Pizzicato), 1.0);
+        assert_eq!(report.notes()[1].note(), "A4");
+    }
+
+    #[test]
+    fn test_legato_and_staccato_bowing() {
+        let mut legato = Vec::new();
+        for frequency in [392.0, 440.0, 493.88] {
+            note(&mut legato, frequency, 0.5, 0.0, |t| (t / 0.06).min(1.0));
+        }
+        let report = classify_articulation(&legato, SR);
+        assert_eq!(report.note_count(), 3);
+        assert_eq!(report.count(Articulation::Legato), 3);
+
+        let mut staccato = Vec::new();
+        for frequency in [392.0, 440.0, 493.88] {
+            note(&mut staccato, frequency, 0.18, 0.2, bow(0.18));
+        }
+        let report = classify_articulation(&staccato, SR);
+        assert_eq!(report.note_count(), 3);
+        assert_eq!(report.count(Articulation::Staccato), 3);
+    }
+}
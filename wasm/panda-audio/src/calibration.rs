@@ -0,0 +1,250 @@
+use wasm_bindgen::prelude::*;
+use crate::clamp_sample_rate;
+use crate::metronome::render_click;
+
+// ============================================================================
+// Round-Trip Latency Calibration
+// ============================================================================
+
+/// Click onsets of the calibration pattern (seconds from pattern start).
+/// Intervals are irregular and longer than `MAX_LATENCY_SECONDS` so that each
+/// click's search window can only contain that click's arrival.
+const CLICK_TIMES: [f32; 8] = [0.25, 0.80, 1.45, 1.95, 2.65, 3.20, 3.75, 4.45];
+
+/// Largest round-trip latency we search for (seconds)
+const MAX_LATENCY_SECONDS: f32 = 0.4;
+
+/// Calibration click tone (Hz)
+const CLICK_FREQ: f32 = 1760.0;
+
+/// Fewest detected clicks needed for a usable estimate
+const MIN_DETECTED_CLICKS: usize = 4;
+
+/// Latency calibration outcome
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct LatencyResult {
+    /// Round-trip latency in samples
+    latency_samples: u32,
+    /// Round-trip latency in milliseconds
+    latency_ms: f32,
+    /// Confidence in the estimate (0.0 to 1.0)
+    confidence: f32,
+    /// Number of pattern clicks found in the input
+    detected_clicks: u32,
+    /// Median absolute deviation of per-click latencies (ms)
+    spread_ms: f32,
+}
+
+#[wasm_bindgen]
+impl LatencyResult {
+    #[wasm_bindgen(getter)]
+    pub fn latency_samples(&self) -> u32 { self.latency_samples }
+
+    #[wasm_bindgen(getter)]
+    pub fn latency_ms(&self) -> f32 { self.latency_ms }
+
+    #[wasm_bindgen(getter)]
+    pub fn confidence(&self) -> f32 { self.confidence }
+
+    #[wasm_bindgen(getter)]
+    pub fn detected_clicks(&self) -> u32 { self.detected_clicks }
+
+    #[wasm_bindgen(getter)]
+    pub fn spread_ms(&self) -> f32 { self.spread_ms }
+
+    /// Remove the measured latency from a rhythm offset (ms)
+    #[wasm_bindgen]
+    pub fn compensate(&self, offset_ms: f32) -> f32 {
+        offset_ms - self.latency_ms
+    }
+}
+
+fn median(values: &mut [f32]) -> f32 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    values.sort_by(|a, b| a.total_cmp(b));
+    let middle = values.len() / 2;
+    if values.len().is_multiple_of(2) {
+        (values[middle - 1] + values[middle]) / 2.0
+    } else {
+        values[middle]
+    }
+}
+
+/// Plays a known click pattern and finds it in the recorded microphone input.
+///
+/// Usage: play `render_pattern()` through the speakers while pushing the mic
+/// input with `push_input` from the moment playback starts, then call
+/// `compute`.
+#[wasm_bindgen]
+pub struct LatencyCalibrator {
+    sample_rate: f32,
+    /// Recorded microphone input, aligned with pattern sample 0
+    recorded: Vec<f32>,
+    /// Maximum samples to record (pattern length plus maximum latency)
+    capacity: usize,
+}
+
+#[wasm_bindgen]
+impl LatencyCalibrator {
+    #[wasm_bindgen(constructor)]
+    pub fn new(sample_rate: f32) -> LatencyCalibrator {
+        let sample_rate = clamp_sample_rate(sample_rate);
+        let capacity = pattern_length(sample_rate) + max_latency_samples(sample_rate);
+        LatencyCalibrator {
+            sample_rate,
+            recorded: Vec::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Render the calibration click pattern for playback
+    #[wasm_bindgen]
+    pub fn render_pattern(&self) -> Vec<f32> {
+        let click = render_click(self.sample_rate, CLICK_FREQ, 1.0);
+        let mut pattern = vec![0.0; pattern_length(self.sample_rate)];
+        for onset in click_onsets(self.sample_rate) {
+            let end = (onset + click.len()).min(pattern.len());
+            pattern[onset..end].copy_from_slice(&click[..end - onset]);
+        }
+        pattern
+    }
+
+    /// Append microphone input. Returns true once enough input has been recorded.
+    #[wasm_bindgen]
+    pub fn push_input(&mut self, samples: &[f32]) -> bool {
+        let remaining = self.capacity - self.recorded.len();
+        let take = samples.len().min(remaining);
+        self.recorded.extend_from_slice(&samples[..take]);
+        self.recorded.len() >= self.capacity
+    }
+
+    /// Discard recorded input, ready for another attempt
+    #[wasm_bindgen]
+    pub fn reset(&mut self) {
+        self.recorded.clear();
+    }
+
+    /// Estimate round-trip latency from the recorded input
+    #[wasm_bindgen]
+    pub fn compute(&self) -> LatencyResult {
+        let onsets = click_onsets(self.sample_rate);
+        let max_latency = max_latency_samples(self.sample_rate);
+
+        // Everything before the first click can reach the mic is background noise
+        let noise_end = onsets[0].min(self.recorded.len());
+        let noise_peak = self.recorded[..noise_end]
+            .iter()
+            .fold(0.0f32, |acc, &x| acc.max(x.abs()));
+
+        let mut lags = Vec::with_capacity(onsets.len());
+        for &onset in &onsets {
+            if onset >= self.recorded.len() {
+                break;
+            }
+            let window_end = (onset + max_latency).min(self.recorded.len());
+            let window = &self.recorded[onset..window_end];
+            if let Some(arrival) = find_arrival(window, noise_peak) {
+                lags.push(arrival as f32);
+            }
+        }
+
+        let detected = lags.len();
+        if detected < MIN_DETECTED_CLICKS {
+            return LatencyResult {
+                latency_samples: 0,
+                latency_ms: 0.0,
+                confidence: 0.0,
+                detected_clicks: detected as u32,
+                spread_ms: 0.0,
+            };
+        }
+
+        let latency = median(&mut lags);
+        let mut deviations: Vec<f32> = lags.iter().map(|&lag| (lag - latency).abs()).collect();
+        let spread_ms = median(&mut deviations) * 1000.0 / self.sample_rate;
+
+        // Full confidence needs every click found with sub-millisecond agreement
+        let found_ratio = detected as f32 / onsets.len() as f32;
+        let consistency = 1.0 / (1.0 + spread_ms);
+
+        LatencyResult {
+            latency_samples: latency.round() as u32,
+            latency_ms: latency * 1000.0 / self.sample_rate,
+            confidence: (found_ratio * consistency).clamp(0.0, 1.0),
+            detected_clicks: detected as u32,
+            spread_ms,
+        }
+    }
+}
+
+fn pattern_length(sample_rate: f32) -> usize {
+    let last = CLICK_TIMES[CLICK_TIMES.len() - 1];
+    ((last + 0.5) * sample_rate) as usize
+}
+
+fn max_latency_samples(sample_rate: f32) -> usize {
+    (MAX_LATENCY_SECONDS * sample_rate) as usize
+}
+
+fn click_onsets(sample_rate: f32) -> Vec<usize> {
+    CLICK_TIMES
+        .iter()
+        .map(|&t| (t * sample_rate).round() as usize)
+        .collect()
+}
+
+/// Index of the first sample in `window` that clearly rises above the noise.
+fn find_arrival(window: &[f32], noise_peak: f32) -> Option<usize> {
+    let peak = window.iter().fold(0.0f32, |acc, &x| acc.max(x.abs()));
+    // Require the click to stand well clear of background noise
+    if peak < 0.005 || peak < noise_peak * 3.0 {
+        return None;
+    }
+    let threshold = (peak * 0.25).max(noise_peak * 1.5);
+    window.iter().position(|&x| x.abs() >= threshold)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_signals::noise;
+
+    #[test]
+    fn test_detects_round_trip_latency() {
+        let mut calibrator = LatencyCalibrator::new(48000.0);
+        let pattern = calibrator.render_pattern();
+        let delay = 1234;
+
+        let mut input = vec![0.0f32; pattern.len() + 48000];
+        for (i, &x) in pattern.iter().enumerate() {
+            input[i + delay] += x * 0.3;
+        }
+        for (x, n) in input.iter_mut().zip(noise(pattern.len() + 48000, 0.002, 7)) {
+            *x += n;
+        }
+
+        for chunk in input.chunks(128) {
+            if calibrator.push_input(chunk) {
+                break;
+            }
+        }
+
+        let result = calibrator.compute();
+        assert_eq!(result.detected_clicks(), CLICK_TIMES.len() as u32);
+        assert!((result.latency_samples() as i32 - delay as i32).abs() <= 2);
+        assert!(result.confidence() > 0.8);
+        assert!((result.compensate(result.latency_ms()) - 0.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_silence_has_no_confidence() {
+        let mut calibrator = LatencyCalibrator::new(44100.0);
+        calibrator.push_input(&vec![0.0; 300_000]);
+        let result = calibrator.compute();
+        assert_eq!(result.detected_clicks(), 0);
+        assert_eq!(result.confidence(), 0.0);
+    }
+}
@@ -0,0 +1,286 @@
+use wasm_bindgen::prelude::*;
+use crate::clamp_sample_rate;
+use crate::health::sanitize_sample;
+
+// ============================================================================
+// Multi-channel Input
+// ============================================================================
+
+/// How a `ChannelSelector` turns several input channels into one
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChannelMode {
+    /// Follow the loudest live channel (e.g. a USB interface with the mic on
+    /// input 2), switching only when another channel is clearly louder
+    Auto,
+    /// Average all channels
+    Downmix,
+    /// Always use the channel chosen with `set_channel`
+    Fixed,
+}
+
+/// Arrangement of the samples in a multi-channel buffer
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChannelLayout {
+    /// Frame by frame: `L R L R ...`
+    Interleaved,
+    /// Channel by channel, each one buffer long: `L L ... R R ...`
+    Planar,
+}
+
+/// Smoothed RMS below which a channel counts as silent (-60 dBFS)
+const LIVE_LEVEL: f32 = 0.001;
+/// In auto mode, another channel must be this much louder (+6 dB) to take over
+const SWITCH_RATIO: f32 = 2.0;
+/// Time constant of the per-channel level smoothing, in seconds
+const LEVEL_TIME_CONSTANT: f32 = 0.3;
+
+/// Reduces interleaved or planar multi-channel input to the mono signal the
+/// detectors expect, and reports the level of every channel
+#[wasm_bindgen]
+pub struct ChannelSelector {
+    sample_rate: f32,
+    channels: usize,
+    mode: ChannelMode,
+    fixed_channel: usize,
+    selected: usize,
+    /// Smoothed RMS per channel
+    levels: Vec<f32>,
+    /// Peak per channel over the last buffer
+    peaks: Vec<f32>,
+}
+
+#[wasm_bindgen]
+impl ChannelSelector {
+    #[wasm_bindgen(constructor)]
+    pub fn new(sample_rate: f32) -> ChannelSelector {
+        ChannelSelector {
+            sample_rate: clamp_sample_rate(sample_rate),
+            channels: 1,
+            mode: ChannelMode::Auto,
+            fixed_channel: 0,
+            selected: 0,
+            levels: vec![0.0],
+            peaks: vec![0.0],
+        }
+    }
+
+    #[wasm_bindgen]
+    pub fn set_mode(&mut self, mode: ChannelMode) {
+        self.mode = mode;
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn mode(&self) -> ChannelMode { self.mode }
+
+    /// Use channel `index` (0-based) and switch to `ChannelMode::Fixed`
+    #[wasm_bindgen]
+    pub fn set_channel(&mut self, index: usize) {
+        self.fixed_channel = index;
+        self.mode = ChannelMode::Fixed;
+    }
+
+    /// Channel count of the last buffer
+    #[wasm_bindgen(getter)]
+    pub fn channels(&self) -> usize { self.channels }
+
+    /// Channel feeding the output, or -1 when downmixing
+    #[wasm_bindgen(getter)]
+    pub fn selected_channel(&self) -> i32 {
+        match self.mode {
+            ChannelMode::Downmix if self.channels > 1 => -1,
+            _ => self.selected as i32,
+        }
+    }
+
+    /// Smoothed RMS level of each channel
+    #[wasm_bindgen]
+    pub fn channel_levels(&self) -> Vec<f32> {
+        self.levels.clone()
+    }
+
+    /// Copy the smoothed levels into `out` without allocating, for callers
+    /// polling every render quantum. Returns the number of levels written.
+    #[wasm_bindgen]
+    pub fn channel_levels_into(&self, out: &mut [f32]) -> usize {
+        let count = self.levels.len().min(out.len());
+        out[..count].copy_from_slice(&self.levels[..count]);
+        count
+    }
+
+    /// Peak of each channel over the last buffer
+    #[wasm_bindgen]
+    pub fn channel_peaks(&self) -> Vec<f32> {
+        self.peaks.clone()
+    }
+
+    /// Number of channels carrying signal above -60 dBFS
+    #[wasm_bindgen(getter)]
+    pub fn live_channels(&self) -> usize {
+        self.levels.iter().filter(|&&level| level >= LIVE_LEVEL).count()
+    }
+
+    /// Forget levels and the auto-selected channel
+    #[wasm_bindgen]
+    pub fn reset(&mut self) {
+        self.levels.fill(0.0);
+        self.peaks.fill(0.0);
+        self.selected = 0;
+    }
+
+    /// Reduce `data` (`channels` channels in `layout`) to mono in `out`.
+    /// Returns the number of frames written, at most `out.len()`.
+    #[wasm_bindgen]
+    pub fn mix_into(&mut self, data: &[f32], channels: usize, layout: ChannelLayout, out: &mut [f32]) -> usize {
+        let channels = channels.max(1);
+        if channels != self.channels {
+            self.channels = channels;
+            self.levels = vec![0.0; channels];
+            self.peaks = vec![0.0; channels];
+            self.selected = 0;
+        }
+
+        // Planar channels are each a full buffer long, even if `out` is shorter
+        let stride = data.len() / channels;
+        let frames = stride.min(out.len());
+        let index = |channel: usize, frame: usize| match layout {
+            ChannelLayout::Interleaved => frame * channels + channel,
+            ChannelLayout::Planar => channel * stride + frame,
+        };
+
+        self.measure(frames, |channel, frame| data[index(channel, frame)]);
+        self.choose();
+
+        let out = &mut out[..frames];
+        if self.mode == ChannelMode::Downmix && channels > 1 {
+            let scale = 1.0 / channels as f32;
+            for (frame, slot) in out.iter_mut().enumerate() {
+                *slot = (0..channels).map(|channel| data[index(channel, frame)]).sum::<f32>() * scale;
+            }
+        } else {
+            for (frame, slot) in out.iter_mut().enumerate() {
+                *slot = data[index(self.selected, frame)];
+            }
+        }
+        frames
+    }
+
+    /// Like `mix_into`, returning a new mono buffer
+    #[wasm_bindgen]
+    pub fn mix(&mut self, data: &[f32], channels: usize, layout: ChannelLayout) -> Vec<f32> {
+        let mut out = vec![0.0; data.len() / channels.max(1)];
+        self.mix_into(data, channels, layout, &mut out);
+        out
+    }
+}
+
+impl ChannelSelector {
+    fn measure(&mut self, frames: usize, sample: impl Fn(usize, usize) -> f32) {
+        if frames == 0 {
+            return;
+        }
+        let alpha = 1.0 - (-(frames as f32) / (self.sample_rate * LEVEL_TIME_CONSTANT)).exp();
+        for channel in 0..self.channels {
+            let mut peak = 0.0f32;
+            let mut sum_squares = 0.0f32;
+            for frame in 0..frames {
+                let value = sanitize_sample(sample(channel, frame));
+                peak = peak.max(value.abs());
+                sum_squares += value * value;
+            }
+            let rms = (sum_squares / frames as f32).sqrt();
+            self.levels[channel] += alpha * (rms - self.levels[channel]);
+            self.peaks[channel] = peak;
+        }
+    }
+
+    fn choose(&mut self) {
+        match self.mode {
+            ChannelMode::Fixed => self.selected = self.fixed_channel.min(self.channels - 1),
+            ChannelMode::Downmix => {}
+            ChannelMode::Auto => {
+                let (loudest, &level) = self
+                    .levels
+                    .iter()
+                    .enumerate()
+                    .max_by(|a, b| a.1.total_cmp(b.1))
+                    .unwrap_or((0, &0.0));
+                if level >= LIVE_LEVEL && level > self.levels[self.selected] * SWITCH_RATIO {
+                    self.selected = loudest;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_signals::sine;
+
+    const SAMPLE_RATE: f32 = 48000.0;
+
+    fn interleave(channels: &[Vec<f32>]) -> Vec<f32> {
+        (0..channels[0].len()).flat_map(|i| channels.iter().map(move |c| c[i])).collect()
+    }
+
+    #[test]
+    fn test_auto_follows_live_channel() {
+        let hiss = sine(7000.0, 0.0002, 128, SAMPLE_RATE);
+        let mic = sine(440.0, 0.3, 128, SAMPLE_RATE);
+        let interleaved = interleave(&[hiss.clone(), mic.clone()]);
+        let planar: Vec<f32> = hiss.iter().chain(&mic).copied().collect();
+
+        for (data, layout) in [(interleaved, ChannelLayout::Interleaved), (planar, ChannelLayout::Planar)] {
+            let mut selector = ChannelSelector::new(SAMPLE_RATE);
+            let mut out = vec![0.0; 128];
+            // Half a second of 128-sample render quanta
+            for _ in 0..200 {
+                assert_eq!(selector.mix_into(&data, 2, layout, &mut out), 128);
+            }
+            assert_eq!(selector.selected_channel(), 1);
+            assert_eq!(out, mic);
+            assert_eq!(selector.live_channels(), 1);
+            let levels = selector.channel_levels();
+            assert!(levels[1] > 0.1 && levels[0] < LIVE_LEVEL, "{levels:?}");
+            let mut polled = [0.0; 3];
+            assert_eq!(selector.channel_levels_into(&mut polled), 2);
+            assert_eq!(polled[..2], levels[..]);
+            assert!((selector.channel_peaks()[1] - 0.3).abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn test_auto_does_not_flip_between_similar_channels() {
+        let mut selector = ChannelSelector::new(SAMPLE_RATE);
+        let left = sine(440.0, 0.3, 512, SAMPLE_RATE);
+        for amplitude in [0.25, 0.35, 0.4, 0.3] {
+            let data = interleave(&[left.clone(), sine(440.0, amplitude, 512, SAMPLE_RATE)]);
+            selector.mix(&data, 2, ChannelLayout::Interleaved);
+            assert_eq!(selector.selected_channel(), 0);
+        }
+    }
+
+    #[test]
+    fn test_downmix_and_fixed_modes() {
+        let data = interleave(&[vec![0.2; 64], vec![0.6; 64]]);
+        let mut selector = ChannelSelector::new(SAMPLE_RATE);
+
+        selector.set_mode(ChannelMode::Downmix);
+        let mono = selector.mix(&data, 2, ChannelLayout::Interleaved);
+        assert_eq!(selector.selected_channel(), -1);
+        assert!(mono.iter().all(|&s| (s - 0.4).abs() < 1e-6));
+
+        selector.set_channel(0);
+        assert_eq!(selector.mix(&data, 2, ChannelLayout::Interleaved), vec![0.2; 64]);
+        // Out-of-range channels fall back to the last one
+        selector.set_channel(5);
+        assert_eq!(selector.mix(&data, 2, ChannelLayout::Interleaved), vec![0.6; 64]);
+
+        // Mono input passes straight through
+        let mono = sine(440.0, 0.5, 64, SAMPLE_RATE);
+        assert_eq!(selector.mix(&mono, 1, ChannelLayout::Planar), mono);
+        assert_eq!(selector.channels(), 1);
+    }
+}
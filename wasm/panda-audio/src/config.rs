@@ -0,0 +1,245 @@
+use wasm_bindgen::prelude::*;
+use js_sys::{Object, Reflect};
+use crate::SmoothingMode;
+use crate::harmonics::DEFAULT_SUBHARMONIC_RATIO;
+
+// ============================================================================
+// Detector Configuration
+// ============================================================================
+
+/// All tunable `PitchDetector` settings in one object.
+///
+/// Build one in JS (or from a plain object with `DetectorConfig.from_js`) and
+/// pass it to `PitchDetector.apply_config` at any time; no re-creation needed.
+#[wasm_bindgen]
+#[derive(Clone, Debug, PartialEq)]
+pub struct DetectorConfig {
+    /// Minimum frequency to detect (Hz)
+    pub min_freq: f32,
+    /// Maximum frequency to detect (Hz)
+    pub max_freq: f32,
+    /// RMS volume below which frames are treated as silence
+    pub volume_threshold: f32,
+    /// NSDF peak required to accept a pitch
+    pub confidence_threshold: f32,
+    /// NSDF level a coarse (downsampled) peak must exceed to be considered
+    pub coarse_threshold: f32,
+    /// Tolerance for "in tune" detection (cents)
+    pub tune_tolerance: i32,
+    /// Whether octave/fifth errors are corrected
+    pub harmonic_correction: bool,
+    /// Sub-harmonic energy ratio above which a lower octave/twelfth is chosen
+    pub subharmonic_ratio: f32,
+    /// Pitch smoothing strategy
+    pub smoothing: SmoothingMode,
+    /// Weight of the newest frame for exponential and note-aware smoothing
+    pub smoothing_alpha: f32,
+    /// Median filter length in frames
+    pub median_window: usize,
+    /// One-euro minimum cutoff (Hz)
+    pub one_euro_min_cutoff: f32,
+    /// One-euro speed coefficient
+    pub one_euro_beta: f32,
+    /// Jump (cents) treated as a new note by note-aware smoothing
+    pub note_change_cents: f32,
+}
+
+impl Default for DetectorConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub(crate) fn read_f32(value: &JsValue, key: &str) -> Option<f32> {
+    Reflect::get(value, &JsValue::from_str(key)).ok()?.as_f64().map(|v| v as f32)
+}
+
+pub(crate) fn read_bool(value: &JsValue, key: &str) -> Option<bool> {
+    Reflect::get(value, &JsValue::from_str(key)).ok()?.as_bool()
+}
+
+pub(crate) fn write(object: &Object, key: &str, value: JsValue) {
+    // Setting a property on a fresh plain object cannot fail
+    let _ = Reflect::set(object, &JsValue::from_str(key), &value);
+}
+
+/// Parse a smoothing mode name as used in JS settings
+pub(crate) fn parse_smoothing_mode(name: &str) -> Option<SmoothingMode> {
+    match name {
+        "none" => Some(SmoothingMode::None),
+        "exponential" => Some(SmoothingMode::Exponential),
+        "median" => Some(SmoothingMode::Median),
+        "oneEuro" | "one_euro" => Some(SmoothingMode::OneEuro),
+        "noteAware" | "note_aware" => Some(SmoothingMode::NoteAware),
+        _ => None,
+    }
+}
+
+pub(crate) fn smoothing_mode_name(mode: SmoothingMode) -> &'static str {
+    match mode {
+        SmoothingMode::None => "none",
+        SmoothingMode::Exponential => "exponential",
+        SmoothingMode::Median => "median",
+        SmoothingMode::OneEuro => "oneEuro",
+        SmoothingMode::NoteAware => "noteAware",
+    }
+}
+
+#[wasm_bindgen]
+impl DetectorConfig {
+    /// Default violin configuration (matches a fresh `PitchDetector`)
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> DetectorConfig {
+        DetectorConfig {
+            min_freq: 180.0,
+            max_freq: 1400.0,
+            volume_threshold: 0.01,
+            confidence_threshold: 0.75,
+            coarse_threshold: 0.6,
+            tune_tolerance: 10,
+            harmonic_correction: true,
+            subharmonic_ratio: DEFAULT_SUBHARMONIC_RATIO,
+            smoothing: SmoothingMode::Exponential,
+            smoothing_alpha: 0.7,
+            median_window: 5,
+            one_euro_min_cutoff: 1.0,
+            one_euro_beta: 0.3,
+            note_change_cents: 50.0,
+        }
+    }
+
+    /// Build a config from a plain JS object with camelCase keys
+    /// (e.g. `{ minFreq: 120, smoothing: "oneEuro" }`). Missing or invalid
+    /// keys keep their defaults.
+    #[wasm_bindgen]
+    pub fn from_js(value: &JsValue) -> DetectorConfig {
+        let mut config = DetectorConfig::new();
+        if !value.is_object() {
+            return config;
+        }
+        if let Some(v) = read_f32(value, "minFreq") { config.min_freq = v; }
+        if let Some(v) = read_f32(value, "maxFreq") { config.max_freq = v; }
+        if let Some(v) = read_f32(value, "volumeThreshold") { config.volume_threshold = v; }
+        if let Some(v) = read_f32(value, "confidenceThreshold") { config.confidence_threshold = v; }
+        if let Some(v) = read_f32(value, "coarseThreshold") { config.coarse_threshold = v; }
+        if let Some(v) = read_f32(value, "tuneTolerance") { config.tune_tolerance = v.round() as i32; }
+        if let Some(v) = read_bool(value, "harmonicCorrection") { config.harmonic_correction = v; }
+        if let Some(v) = read_f32(value, "subharmonicRatio") { config.subharmonic_ratio = v; }
+        if let Some(v) = read_f32(value, "smoothingAlpha") { config.smoothing_alpha = v; }
+        if let Some(v) = read_f32(value, "medianWindow") { config.median_window = v.max(0.0) as usize; }
+        if let Some(v) = read_f32(value, "oneEuroMinCutoff") { config.one_euro_min_cutoff = v; }
+        if let Some(v) = read_f32(value, "oneEuroBeta") { config.one_euro_beta = v; }
+        if let Some(v) = read_f32(value, "noteChangeCents") { config.note_change_cents = v; }
+        let smoothing = Reflect::get(value, &JsValue::from_str("smoothing")).ok();
+        if let Some(mode) = smoothing.and_then(|v| v.as_string()).and_then(|s| parse_smoothing_mode(&s)) {
+            config.smoothing = mode;
+        }
+        config.validated()
+    }
+
+    /// Serialise to a plain JS object with camelCase keys (inverse of `from_js`)
+    #[wasm_bindgen]
+    pub fn to_js(&self) -> JsValue {
+        let object = Object::new();
+        write(&object, "minFreq", self.min_freq.into());
+        write(&object, "maxFreq", self.max_freq.into());
+        write(&object, "volumeThreshold", self.volume_threshold.into());
+        write(&object, "confidenceThreshold", self.confidence_threshold.into());
+        write(&object, "coarseThreshold", self.coarse_threshold.into());
+        write(&object, "tuneTolerance", self.tune_tolerance.into());
+        write(&object, "harmonicCorrection", self.harmonic_correction.into());
+        write(&object, "subharmonicRatio", self.subharmonic_ratio.into());
+        write(&object, "smoothing", smoothing_mode_name(self.smoothing).into());
+        write(&object, "smoothingAlpha", self.smoothing_alpha.into());
+        write(&object, "medianWindow", (self.median_window as u32).into());
+        write(&object, "oneEuroMinCutoff", self.one_euro_min_cutoff.into());
+        write(&object, "oneEuroBeta", self.one_euro_beta.into());
+        write(&object, "noteChangeCents", self.note_change_cents.into());
+        object.into()
+    }
+
+    /// Copy with every value clamped to its supported range. NaN or infinite
+    /// values are replaced with their defaults first.
+    #[wasm_bindgen]
+    pub fn validated(&self) -> DetectorConfig {
+        let defaults = DetectorConfig::new();
+        // clamp() passes NaN through, and a NaN bound makes it panic
+        let finite = |value: f32, default: f32| if value.is_finite() { value } else { default };
+        let min_freq = finite(self.min_freq, defaults.min_freq).clamp(20.0, 4000.0);
+        DetectorConfig {
+            min_freq,
+            // max/min rather than clamp(): a variable bound keeps clamp()'s
+            // float-formatting panic message in the wasm
+            max_freq: finite(self.max_freq, defaults.max_freq).max(min_freq * 1.5).min(8000.0),
+            volume_threshold: finite(self.volume_threshold, defaults.volume_threshold).clamp(0.001, 0.5),
+            confidence_threshold: finite(self.confidence_threshold, defaults.confidence_threshold).clamp(0.1, 0.99),
+            coarse_threshold: finite(self.coarse_threshold, defaults.coarse_threshold).clamp(0.1, 0.99),
+            tune_tolerance: self.tune_tolerance.clamp(1, 50),
+            harmonic_correction: self.harmonic_correction,
+            subharmonic_ratio: finite(self.subharmonic_ratio, defaults.subharmonic_ratio).clamp(0.005, 0.5),
+            smoothing: self.smoothing,
+            smoothing_alpha: finite(self.smoothing_alpha, defaults.smoothing_alpha).clamp(0.05, 1.0),
+            median_window: self.median_window.clamp(3, 15) | 1,
+            one_euro_min_cutoff: finite(self.one_euro_min_cutoff, defaults.one_euro_min_cutoff).clamp(0.01, 20.0),
+            one_euro_beta: finite(self.one_euro_beta, defaults.one_euro_beta).clamp(0.0, 10.0),
+            note_change_cents: finite(self.note_change_cents, defaults.note_change_cents).clamp(10.0, 200.0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validation_clamps_values() {
+        let mut config = DetectorConfig::new();
+        config.min_freq = 5.0;
+        config.max_freq = 10.0;
+        config.median_window = 4;
+        config.tune_tolerance = 500;
+        let valid = config.validated();
+        assert_eq!(valid.min_freq, 20.0);
+        assert_eq!(valid.max_freq, 30.0);
+        assert_eq!(valid.median_window, 5);
+        assert_eq!(valid.tune_tolerance, 50);
+        assert_eq!(DetectorConfig::new().validated(), DetectorConfig::new());
+    }
+
+    #[test]
+    fn test_validation_replaces_non_finite_values() {
+        let fields: [fn(&mut DetectorConfig) -> &mut f32; 10] = [
+            |c| &mut c.min_freq,
+            |c| &mut c.max_freq,
+            |c| &mut c.volume_threshold,
+            |c| &mut c.confidence_threshold,
+            |c| &mut c.coarse_threshold,
+            |c| &mut c.subharmonic_ratio,
+            |c| &mut c.smoothing_alpha,
+            |c| &mut c.one_euro_min_cutoff,
+            |c| &mut c.one_euro_beta,
+            |c| &mut c.note_change_cents,
+        ];
+        for (i, field) in fields.iter().enumerate() {
+            for value in [f32::NAN, f32::INFINITY, f32::NEG_INFINITY] {
+                let mut config = DetectorConfig::new();
+                *field(&mut config) = value;
+                assert_eq!(config.validated(), DetectorConfig::new(), "field {i} = {value}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_smoothing_mode_names_round_trip() {
+        for mode in [
+            SmoothingMode::None,
+            SmoothingMode::Exponential,
+            SmoothingMode::Median,
+            SmoothingMode::OneEuro,
+            SmoothingMode::NoteAware,
+        ] {
+            assert_eq!(parse_smoothing_mode(smoothing_mode_name(mode)), Some(mode));
+        }
+        assert_eq!(parse_smoothing_mode("wobbly"), None);
+    }
+}
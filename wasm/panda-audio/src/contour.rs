@@ -0,0 +1,237 @@
+use wasm_bindgen::prelude::*;
+use crate::PitchResult;
+
+// ============================================================================
+// Streaming Pitch-Contour Recorder
+// ============================================================================
+
+/// Values per frame in `export_packed`:
+/// `[time_ms, frequency, cents, volume, voicing_probability, flags]`
+/// where `flags` bit 0 = in tune, bit 1 = octave/fifth corrected.
+const PACKED_STRIDE: usize = 6;
+
+/// Values per point in `export_downsampled`:
+/// `[time_ms, frequency, volume, voiced_ratio]`
+const DOWNSAMPLED_STRIDE: usize = 4;
+
+const FLAG_IN_TUNE: u8 = 1;
+const FLAG_CORRECTED: u8 = 2;
+
+/// One recorded frame (19 bytes of payload, 20 in memory)
+#[derive(Clone, Copy, Debug)]
+struct ContourFrame {
+    /// Milliseconds since the first frame
+    offset_ms: f32,
+    frequency: f32,
+    cents: i16,
+    volume: f32,
+    voicing_probability: f32,
+    flags: u8,
+}
+
+/// Accumulates timestamped pitch frames in WASM memory
+#[wasm_bindgen]
+pub struct ContourRecorder {
+    frames: Vec<ContourFrame>,
+    /// Timestamp of the first frame (ms); offsets are stored relative to it
+    start_ms: f64,
+    /// Frames beyond this are dropped
+    max_frames: usize,
+    /// Whether frames were dropped because `max_frames` was reached
+    truncated: bool,
+}
+
+#[wasm_bindgen]
+impl ContourRecorder {
+    /// Create a recorder that keeps at most `max_frames` frames
+    /// (e.g. one hour at 23 frames/s is about 83,000 frames, under 2 MB)
+    #[wasm_bindgen(constructor)]
+    pub fn new(max_frames: usize) -> ContourRecorder {
+        ContourRecorder {
+            frames: Vec::new(),
+            start_ms: 0.0,
+            max_frames,
+            truncated: false,
+        }
+    }
+
+    /// Record a detection result at `timestamp_ms`.
+    /// Returns false if the recorder is full.
+    #[wasm_bindgen]
+    pub fn push(&mut self, timestamp_ms: f64, result: &PitchResult) -> bool {
+        if self.frames.len() >= self.max_frames {
+            self.truncated = true;
+            return false;
+        }
+        if self.frames.is_empty() {
+            self.start_ms = timestamp_ms;
+        }
+
+        let mut flags = 0;
+        if result.in_tune {
+            flags |= FLAG_IN_TUNE;
+        }
+        if result.corrected {
+            flags |= FLAG_CORRECTED;
+        }
+
+        self.frames.push(ContourFrame {
+            offset_ms: (timestamp_ms - self.start_ms) as f32,
+            frequency: result.frequency,
+            cents: result.cents.clamp(i16::MIN as i32, i16::MAX as i32) as i16,
+            volume: result.volume,
+            voicing_probability: result.voicing_probability,
+            flags,
+        });
+        true
+    }
+
+    /// Discard all frames
+    #[wasm_bindgen]
+    pub fn clear(&mut self) {
+        self.frames.clear();
+        self.start_ms = 0.0;
+        self.truncated = false;
+    }
+
+    /// All frames as a flat array with `PACKED_STRIDE` values per frame.
+    /// Times are milliseconds relative to `start_ms`.
+    #[wasm_bindgen]
+    pub fn export_packed(&self) -> Vec<f32> {
+        let mut packed = Vec::with_capacity(self.frames.len() * PACKED_STRIDE);
+        for frame in &self.frames {
+            packed.extend_from_slice(&[
+                frame.offset_ms,
+                frame.frequency,
+                frame.cents as f32,
+                frame.volume,
+                frame.voicing_probability,
+                frame.flags as f32,
+            ]);
+        }
+        packed
+    }
+
+    /// Contour reduced to `points` equal-time buckets with
+    /// `DOWNSAMPLED_STRIDE` values each. A bucket's frequency is the median of
+    /// its voiced frames (0 if none) and its volume is the loudest frame.
+    /// `points` is capped at the number of frames.
+    #[wasm_bindgen]
+    pub fn export_downsampled(&self, points: usize) -> Vec<f32> {
+        let points = points.min(self.frames.len());
+        let mut series = Vec::with_capacity(points * DOWNSAMPLED_STRIDE);
+        if points == 0 {
+            return series;
+        }
+
+        let duration = self.duration_ms().max(f32::EPSILON);
+        let bucket_ms = duration / points as f32;
+        let mut voiced: Vec<f32> = Vec::new();
+        let mut idx = 0;
+
+        for p in 0..points {
+            let end = if p + 1 == points { f32::INFINITY } else { (p + 1) as f32 * bucket_ms };
+            let mut count = 0;
+            let mut loudest = 0.0f32;
+            voiced.clear();
+            while idx < self.frames.len() && self.frames[idx].offset_ms < end {
+                let frame = &self.frames[idx];
+                count += 1;
+                loudest = loudest.max(frame.volume);
+                if frame.frequency > 0.0 {
+                    voiced.push(frame.frequency);
+                }
+                idx += 1;
+            }
+
+            let frequency = if voiced.is_empty() {
+                0.0
+            } else {
+                voiced.sort_by(|a, b| a.total_cmp(b));
+                voiced[voiced.len() / 2]
+            };
+            let voiced_ratio = if count > 0 { voiced.len() as f32 / count as f32 } else { 0.0 };
+            series.extend_from_slice(&[(p as f32 + 0.5) * bucket_ms, frequency, loudest, voiced_ratio]);
+        }
+        series
+    }
+
+    /// Number of recorded frames
+    #[wasm_bindgen(getter)]
+    pub fn len(&self) -> usize { self.frames.len() }
+
+    #[wasm_bindgen]
+    pub fn is_empty(&self) -> bool { self.frames.is_empty() }
+
+    /// Timestamp of the first frame (ms)
+    #[wasm_bindgen(getter)]
+    pub fn start_ms(&self) -> f64 { self.start_ms }
+
+    /// Time between the first and last frames (ms)
+    #[wasm_bindgen(getter)]
+    pub fn duration_ms(&self) -> f32 {
+        self.frames.last().map_or(0.0, |frame| frame.offset_ms)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn truncated(&self) -> bool { self.truncated }
+
+    #[wasm_bindgen(getter)]
+    pub fn packed_stride(&self) -> usize { PACKED_STRIDE }
+
+    #[wasm_bindgen(getter)]
+    pub fn downsampled_stride(&self) -> usize { DOWNSAMPLED_STRIDE }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::empty_pitch_result;
+
+    fn voiced(frequency: f32) -> PitchResult {
+        let mut result = empty_pitch_result(0.2, 0.95, 0.9);
+        result.frequency = frequency;
+        result.in_tune = true;
+        result
+    }
+
+    #[test]
+    fn test_packed_export() {
+        let mut recorder = ContourRecorder::new(10);
+        recorder.push(1000.0, &voiced(440.0));
+        recorder.push(1042.5, &empty_pitch_result(0.001, 0.0, 0.0));
+
+        let packed = recorder.export_packed();
+        assert_eq!(packed.len(), 2 * PACKED_STRIDE);
+        assert_eq!(&packed[..6], &[0.0, 440.0, 0.0, 0.2, 0.9, 1.0]);
+        assert_eq!(packed[6], 42.5);
+        assert_eq!(recorder.start_ms(), 1000.0);
+    }
+
+    #[test]
+    fn test_downsampled_series_and_capacity() {
+        let mut recorder = ContourRecorder::new(100);
+        for i in 0..100 {
+            let frequency = if i < 50 { 440.0 } else { 0.0 };
+            recorder.push(i as f64 * 10.0, &voiced(frequency));
+        }
+        assert!(!recorder.push(1000.0, &voiced(440.0)));
+        assert!(recorder.truncated());
+
+        let series = recorder.export_downsampled(2);
+        assert_eq!(series.len(), 2 * DOWNSAMPLED_STRIDE);
+        assert_eq!(series[1], 440.0);
+        assert_eq!(series[5], 0.0);
+        assert_eq!(series[7], 0.0);
+    }
+
+    #[test]
+    fn test_downsampled_points_capped_at_frame_count() {
+        let mut recorder = ContourRecorder::new(10);
+        for i in 0..3 {
+            recorder.push(i as f64 * 10.0, &voiced(440.0));
+        }
+        assert_eq!(recorder.export_downsampled(usize::MAX).len(), 3 * DOWNSAMPLED_STRIDE);
+        assert!(ContourRecorder::new(10).export_downsampled(usize::MAX).is_empty());
+    }
+}
@@ -0,0 +1,442 @@
+use wasm_bindgen::prelude::*;
+use std::f32::consts::PI;
+use crate::fft::SpectrumAnalyzer;
+use crate::harmonics::goertzel_power;
+use crate::health::{sanitize_sample, InputScan};
+use crate::{amplitude_to_db, clamp_sample_rate, SILENCE_DB};
+
+// ============================================================================
+// Microphone Input Diagnostics (sound check)
+// ============================================================================
+
+/// Outcome of one sound-check measurement
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verdict {
+    Pass,
+    Warn,
+    Fail,
+}
+
+/// Length of the level windows (seconds)
+const WINDOW_SECONDS: f32 = 0.02;
+/// Windows within this many dB of the noise floor count as background
+const QUIET_RANGE_DB: f32 = 25.0;
+/// Hum partials must stand this far above neighbouring frequencies (dB)
+const HUM_PROMINENCE_DB: f32 = 12.0;
+/// Distance of the reference frequencies either side of a hum partial (Hz)
+const HUM_REFERENCE_OFFSET_HZ: f32 = 7.0;
+/// Longest stretch of input used for the hum measurement (seconds)
+const HUM_MAX_SECONDS: f32 = 4.0;
+/// Width of the bands the averaged spectrum is grouped into (Hz)
+const BANDWIDTH_BAND_HZ: f32 = 250.0;
+/// Every microphone passes this range; its median band level is the reference
+const BANDWIDTH_REFERENCE_HZ: (f32, f32) = (200.0, 4000.0);
+/// Bands this far below the reference level no longer count as content (dB)
+const BANDWIDTH_RANGE_DB: f32 = 30.0;
+const BANDWIDTH_FFT_SIZE: usize = 4096;
+const BANDWIDTH_MAX_FRAMES: usize = 64;
+
+/// (pass below, fail at or above) limits per measurement
+const NOISE_FLOOR_DB: (f32, f32) = (-60.0, -45.0);
+const HUM_DB: (f32, f32) = (-60.0, -45.0);
+const CLIPPING_PERCENT: (f32, f32) = (0.1, 1.0);
+const DC_OFFSET: (f32, f32) = (0.01, 0.05);
+const FLOOR_VARIATION_DB: (f32, f32) = (6.0, 12.0);
+/// (pass at or above, fail below) for bandwidth (Hz)
+const BANDWIDTH_HZ: (f32, f32) = (8000.0, 4000.0);
+
+/// Verdict for a measurement where smaller is better
+fn verdict_below(value: f32, (pass, fail): (f32, f32)) -> Verdict {
+    if value < pass {
+        Verdict::Pass
+    } else if value < fail {
+        Verdict::Warn
+    } else {
+        Verdict::Fail
+    }
+}
+
+/// Value at fraction `p` (0.0 to 1.0) of the sorted `values`
+fn percentile(sorted: &[f32], p: f32) -> f32 {
+    if sorted.is_empty() {
+        return SILENCE_DB;
+    }
+    sorted[((sorted.len() - 1) as f32 * p).round() as usize]
+}
+
+/// Sound-check results. Every measurement has its own verdict; `overall` is
+/// the worst of them.
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct MicReport {
+    duration: f32,
+    /// Background level: 10th percentile of 20 ms window levels (dBFS)
+    noise_floor_db: f32,
+    /// Mains hum frequency (50 or 60 Hz), or 0 when none was found
+    hum_hz: f32,
+    /// Level of the strongest hum partial (dBFS), or -120 without hum
+    hum_db: f32,
+    clipping_percent: f32,
+    /// Mean sample value (full scale = 1.0)
+    dc_offset: f32,
+    /// Spread of the background level over time (dB); automatic gain
+    /// control makes it pump with the playing
+    floor_variation_db: f32,
+    /// Upper edge of the highest 250 Hz band within 30 dB of the median
+    /// 200-4000 Hz band level (Hz)
+    bandwidth_hz: f32,
+    noise_verdict: Verdict,
+    hum_verdict: Verdict,
+    clipping_verdict: Verdict,
+    dc_verdict: Verdict,
+    agc_verdict: Verdict,
+    bandwidth_verdict: Verdict,
+}
+
+#[wasm_bindgen]
+impl MicReport {
+    #[wasm_bindgen(getter)]
+    pub fn duration(&self) -> f32 { self.duration }
+
+    #[wasm_bindgen(getter)]
+    pub fn noise_floor_db(&self) -> f32 { self.noise_floor_db }
+
+    #[wasm_bindgen(getter)]
+    pub fn hum_hz(&self) -> f32 { self.hum_hz }
+
+    #[wasm_bindgen(getter)]
+    pub fn hum_db(&self) -> f32 { self.hum_db }
+
+    #[wasm_bindgen(getter)]
+    pub fn clipping_percent(&self) -> f32 { self.clipping_percent }
+
+    #[wasm_bindgen(getter)]
+    pub fn dc_offset(&self) -> f32 { self.dc_offset }
+
+    #[wasm_bindgen(getter)]
+    pub fn floor_variation_db(&self) -> f32 { self.floor_variation_db }
+
+    /// Whether the background level moves like automatic gain control
+    #[wasm_bindgen(getter)]
+    pub fn agc_suspected(&self) -> bool { self.agc_verdict != Verdict::Pass }
+
+    #[wasm_bindgen(getter)]
+    pub fn bandwidth_hz(&self) -> f32 { self.bandwidth_hz }
+
+    #[wasm_bindgen(getter)]
+    pub fn noise_verdict(&self) -> Verdict { self.noise_verdict }
+
+    #[wasm_bindgen(getter)]
+    pub fn hum_verdict(&self) -> Verdict { self.hum_verdict }
+
+    #[wasm_bindgen(getter)]
+    pub fn clipping_verdict(&self) -> Verdict { self.clipping_verdict }
+
+    #[wasm_bindgen(getter)]
+    pub fn dc_verdict(&self) -> Verdict { self.dc_verdict }
+
+    #[wasm_bindgen(getter)]
+    pub fn agc_verdict(&self) -> Verdict { self.agc_verdict }
+
+    #[wasm_bindgen(getter)]
+    pub fn bandwidth_verdict(&self) -> Verdict { self.bandwidth_verdict }
+
+    /// Worst verdict of all measurements
+    #[wasm_bindgen(getter)]
+    pub fn overall(&self) -> Verdict {
+        [
+            self.noise_verdict,
+            self.hum_verdict,
+            self.clipping_verdict,
+            self.dc_verdict,
+            self.agc_verdict,
+            self.bandwidth_verdict,
+        ]
+        .into_iter()
+        .max()
+        .unwrap_or(Verdict::Pass)
+    }
+}
+
+/// Collects a few seconds of microphone input for `diagnose_input`.
+///
+/// Usage: ask the player to stay quiet for a moment and then play a few
+/// notes, push the input with `push_input` until it returns true, then call
+/// `compute`.
+#[wasm_bindgen]
+pub struct MicDiagnostics {
+    sample_rate: f32,
+    recorded: Vec<f32>,
+    capacity: usize,
+}
+
+#[wasm_bindgen]
+impl MicDiagnostics {
+    /// Record `seconds` (clamped to 1-10) of input at `sample_rate`
+    #[wasm_bindgen(constructor)]
+    pub fn new(sample_rate: f32, seconds: f32) -> MicDiagnostics {
+        let sample_rate = clamp_sample_rate(sample_rate);
+        let seconds = if seconds.is_nan() { 1.0 } else { seconds.clamp(1.0, 10.0) };
+        let capacity = (seconds * sample_rate) as usize;
+        MicDiagnostics { sample_rate, recorded: Vec::with_capacity(capacity), capacity }
+    }
+
+    /// Append microphone input. Returns true once enough input has been recorded.
+    #[wasm_bindgen]
+    pub fn push_input(&mut self, samples: &[f32]) -> bool {
+        let take = samples.len().min(self.capacity - self.recorded.len());
+        self.recorded.extend_from_slice(&samples[..take]);
+        self.recorded.len() >= self.capacity
+    }
+
+    /// Fraction (0.0 to 1.0) of the required input recorded so far
+    #[wasm_bindgen(getter)]
+    pub fn progress(&self) -> f32 {
+        self.recorded.len() as f32 / self.capacity.max(1) as f32
+    }
+
+    /// Discard recorded input, ready for another attempt
+    #[wasm_bindgen]
+    pub fn reset(&mut self) {
+        self.recorded.clear();
+    }
+
+    /// Analyse the input recorded so far
+    #[wasm_bindgen]
+    pub fn compute(&self) -> MicReport {
+        diagnose_input(&self.recorded, self.sample_rate)
+    }
+}
+
+/// Sound-check measurements for a stretch of microphone input
+#[wasm_bindgen]
+pub fn diagnose_input(samples: &[f32], sample_rate: f32) -> MicReport {
+    let sample_rate = clamp_sample_rate(sample_rate);
+    let clipping_percent = InputScan::of(samples).clipping_percent();
+
+    let dc_offset = if samples.is_empty() {
+        0.0
+    } else {
+        samples.iter().map(|&s| sanitize_sample(s)).sum::<f32>() / samples.len() as f32
+    };
+    let centred: Vec<f32> = samples.iter().map(|&s| sanitize_sample(s) - dc_offset).collect();
+
+    let (noise_floor_db, floor_variation_db) = background_level(&centred, sample_rate);
+    let (hum_hz, hum_db) = detect_hum(&centred, sample_rate);
+    let bandwidth_hz = effective_bandwidth(&centred, sample_rate);
+
+    let bandwidth_verdict = if bandwidth_hz >= BANDWIDTH_HZ.0 {
+        Verdict::Pass
+    } else if bandwidth_hz >= BANDWIDTH_HZ.1 {
+        Verdict::Warn
+    } else {
+        Verdict::Fail
+    };
+
+    MicReport {
+        duration: samples.len() as f32 / sample_rate,
+        noise_floor_db,
+        hum_hz,
+        hum_db,
+        clipping_percent,
+        dc_offset,
+        floor_variation_db,
+        bandwidth_hz,
+        noise_verdict: verdict_below(noise_floor_db, NOISE_FLOOR_DB),
+        hum_verdict: verdict_below(hum_db, HUM_DB),
+        clipping_verdict: verdict_below(clipping_percent, CLIPPING_PERCENT),
+        dc_verdict: verdict_below(dc_offset.abs(), DC_OFFSET),
+        agc_verdict: verdict_below(floor_variation_db, FLOOR_VARIATION_DB),
+        bandwidth_verdict,
+    }
+}
+
+/// Noise floor (dBFS) and the spread of background window levels (dB)
+fn background_level(samples: &[f32], sample_rate: f32) -> (f32, f32) {
+    let window = ((WINDOW_SECONDS * sample_rate) as usize).max(1);
+    let mut levels: Vec<f32> = samples
+        .chunks_exact(window)
+        .map(|chunk| amplitude_to_db(crate::compute_rms(chunk)))
+        .collect();
+    levels.sort_by(|a, b| a.total_cmp(b));
+    let floor = percentile(&levels, 0.1);
+
+    // With AGC the background rises and falls with the playing, so windows
+    // near the floor spread over many dB instead of a couple
+    let end = levels.partition_point(|&level| level <= floor + QUIET_RANGE_DB);
+    let start = levels.partition_point(|&level| level < floor);
+    let quiet = &levels[start..end];
+    let variation = percentile(quiet, 0.9) - percentile(quiet, 0.1);
+    (floor, variation)
+}
+
+/// Amplitude (full scale = 1.0) of the `frequency` component of Hann-windowed
+/// samples whose window weights sum to `window_sum`
+fn tone_amplitude(windowed: &[f32], window_sum: f32, frequency: f32, sample_rate: f32) -> f32 {
+    let power = goertzel_power(windowed, frequency, sample_rate);
+    2.0 * power.sqrt() * windowed.len() as f32 / window_sum.max(1e-9)
+}
+
+/// Strongest mains hum family (50 or 60 Hz) and its level in dBFS
+fn detect_hum(samples: &[f32], sample_rate: f32) -> (f32, f32) {
+    let len = samples.len().min((HUM_MAX_SECONDS * sample_rate) as usize);
+    if len < 2 {
+        return (0.0, SILENCE_DB);
+    }
+    let window = |i: usize| 0.5 - 0.5 * (2.0 * PI * i as f32 / (len - 1) as f32).cos();
+    let windowed: Vec<f32> = samples[..len].iter().enumerate().map(|(i, &x)| x * window(i)).collect();
+    let window_sum: f32 = (0..len).map(window).sum();
+    let amplitude = |frequency| tone_amplitude(&windowed, window_sum, frequency, sample_rate);
+
+    let mut best = (0.0, SILENCE_DB);
+    for mains in [50.0f32, 60.0] {
+        for harmonic in 1..=3 {
+            let frequency = mains * harmonic as f32;
+            let level = amplitude_to_db(amplitude(frequency));
+            let reference = amplitude_to_db(
+                amplitude(frequency - HUM_REFERENCE_OFFSET_HZ).max(amplitude(frequency + HUM_REFERENCE_OFFSET_HZ)),
+            );
+            if level - reference >= HUM_PROMINENCE_DB && level > best.1 {
+                best = (mains, level);
+            }
+        }
+    }
+    best
+}
+
+/// Upper edge of the highest band whose average power is within
+/// `BANDWIDTH_RANGE_DB` of the median band in `BANDWIDTH_REFERENCE_HZ` (0 for
+/// silence). The median ignores the few bands holding the played notes.
+fn effective_bandwidth(samples: &[f32], sample_rate: f32) -> f32 {
+    let mut analyzer = SpectrumAnalyzer::new(BANDWIDTH_FFT_SIZE);
+    let fft_size = analyzer.fft_size();
+    let frames = (samples.len() / fft_size).clamp(1, BANDWIDTH_MAX_FRAMES);
+    let hop = samples.len() / frames;
+
+    let mut power = vec![0.0f32; fft_size / 2 + 1];
+    let mut mags = Vec::new();
+    for f in 0..frames {
+        analyzer.magnitudes(samples, f * hop + hop / 2, &mut mags);
+        for (sum, &mag) in power.iter_mut().zip(&mags) {
+            *sum += mag * mag;
+        }
+    }
+
+    let bin_hz = sample_rate / fft_size as f32;
+    let bins_per_band = ((BANDWIDTH_BAND_HZ / bin_hz).round() as usize).max(1);
+    let bands: Vec<f32> = power
+        .chunks(bins_per_band)
+        .map(|band| band.iter().sum::<f32>() / band.len() as f32)
+        .collect();
+    let band_hz = bins_per_band as f32 * bin_hz;
+
+    let (low, high) = BANDWIDTH_REFERENCE_HZ;
+    let mut reference: Vec<f32> = bands
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| (low..high).contains(&(*i as f32 * band_hz)))
+        .map(|(_, &p)| p)
+        .collect();
+    reference.sort_by(|a, b| a.total_cmp(b));
+    let median = reference.get(reference.len() / 2).copied().unwrap_or(0.0);
+    if median <= 0.0 {
+        return 0.0;
+    }
+    let threshold = median * 10f32.powf(-BANDWIDTH_RANGE_DB / 10.0);
+    bands
+        .iter()
+        .rposition(|&p| p >= threshold)
+        .map_or(0.0, |band| ((band + 1) as f32 * band_hz).min(sample_rate / 2.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::test_signals::Noise;
+
+    const SAMPLE_RATE: f32 = 48000.0;
+
+    /// One second of background noise followed by two seconds of A4
+    fn sound_check(noise_level: f32, hum: Option<(f32, f32)>) -> Vec<f32> {
+        let mut noise = Noise::new(7);
+        (0..(3.0 * SAMPLE_RATE) as usize)
+            .map(|i| {
+                let t = i as f32 / SAMPLE_RATE;
+                let tone = if t >= 1.0 { 0.3 * (2.0 * PI * 440.0 * t).sin() } else { 0.0 };
+                let hum = hum.map_or(0.0, |(hz, amp)| amp * (2.0 * PI * hz * t).sin());
+                tone + hum + noise_level * noise.next()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_clean_input_passes() {
+        let report = diagnose_input(&sound_check(0.0005, None), SAMPLE_RATE);
+        assert!(report.noise_floor_db() < -60.0, "{report:?}");
+        assert_eq!(report.hum_hz(), 0.0, "{report:?}");
+        assert!(report.bandwidth_hz() > 20000.0, "{report:?}");
+        assert_eq!(report.overall(), Verdict::Pass, "{report:?}");
+    }
+
+    #[test]
+    fn test_problems_are_reported() {
+        // Loud 60 Hz hum, DC offset and an overdriven note
+        let input: Vec<f32> = sound_check(0.0005, Some((60.0, 0.02)))
+            .iter()
+            .map(|s| (s * 4.0 + 0.08).clamp(-1.0, 1.0))
+            .collect();
+        let report = diagnose_input(&input, SAMPLE_RATE);
+        assert_eq!(report.hum_hz(), 60.0, "{report:?}");
+        assert_eq!(report.hum_verdict(), Verdict::Fail, "{report:?}");
+        assert_eq!(report.dc_verdict(), Verdict::Fail, "{report:?}");
+        assert_eq!(report.clipping_verdict(), Verdict::Fail, "{report:?}");
+        assert_eq!(report.overall(), Verdict::Fail);
+
+        // Background that swells from -70 to -40 dBFS like AGC in silence
+        let mut noise = Noise::new(3);
+        let len = (3.0 * SAMPLE_RATE) as usize;
+        let pumping: Vec<f32> = (0..len)
+            .map(|i| 10f32.powf((-70.0 + 30.0 * i as f32 / len as f32) / 20.0) * 1.7 * noise.next())
+            .collect();
+        let report = diagnose_input(&pumping, SAMPLE_RATE);
+        assert!(report.agc_suspected(), "{report:?}");
+
+        // Noise low-passed at about 3 kHz, like a narrowband headset mic
+        let mut state = [0.0f32; 6];
+        let alpha = 1.0 - (-2.0 * PI * 3000.0 / SAMPLE_RATE).exp();
+        let narrow: Vec<f32> = (0..len)
+            .map(|_| {
+                let mut x = 0.1 * noise.next();
+                for s in state.iter_mut() {
+                    *s += alpha * (x - *s);
+                    x = *s;
+                }
+                x
+            })
+            .collect();
+        let report = diagnose_input(&narrow, SAMPLE_RATE);
+        assert!(report.bandwidth_hz() < 8000.0, "{report:?}");
+        assert_ne!(report.bandwidth_verdict(), Verdict::Pass);
+    }
+
+    #[test]
+    fn test_recorder_collects_requested_length() {
+        let mut diagnostics = MicDiagnostics::new(SAMPLE_RATE, 2.0);
+        let input = sound_check(0.0005, Some((50.0, 0.003)));
+        let mut done = false;
+        for chunk in input.chunks(128) {
+            done = diagnostics.push_input(chunk);
+            if done {
+                break;
+            }
+        }
+        assert!(done);
+        assert_eq!(diagnostics.progress(), 1.0);
+        let report = diagnostics.compute();
+        assert_eq!(report.duration(), 2.0);
+        assert_eq!(report.hum_hz(), 50.0, "{report:?}");
+        assert_eq!(report.hum_verdict(), Verdict::Warn, "{report:?}");
+    }
+}
@@ -0,0 +1,487 @@
+use wasm_bindgen::prelude::*;
+use crate::analysis::{analyze_prepared_with, DEFAULT_FRAME_SIZE, DEFAULT_HOP_SIZE};
+use crate::preprocess::{preprocess_recording, PreprocessConfig};
+use crate::{clamp_sample_rate, frequency_to_midi, midi_to_frequency, midi_to_note_name, DetectorConfig};
+
+// ============================================================================
+// Ear Training
+// ============================================================================
+
+/// Kind of ear-training question
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EarQuestionKind {
+    /// Name the interval between two notes
+    Interval,
+    /// Name the scale degree of a note heard after a tonic chord
+    ScaleDegree,
+    /// Name the quality of a chord
+    Chord,
+}
+
+/// Question difficulty (shared with the other practice generators)
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+const INTERVAL_NAMES: [&str; 13] = ["P1", "m2", "M2", "m3", "M3", "P4", "TT", "P5", "m6", "M6", "m7", "M7", "P8"];
+const DEGREE_NAMES: [&str; 12] = ["1", "b2", "2", "b3", "3", "4", "#4", "5", "b6", "6", "b7", "7"];
+
+/// Chord qualities as (name, semitones above the root)
+const CHORDS: [(&str, &[i32]); 7] = [
+    ("major", &[0, 4, 7]),
+    ("minor", &[0, 3, 7]),
+    ("diminished", &[0, 3, 6]),
+    ("augmented", &[0, 4, 8]),
+    ("dominant7", &[0, 4, 7, 10]),
+    ("major7", &[0, 4, 7, 11]),
+    ("minor7", &[0, 3, 7, 10]),
+];
+
+/// Semitone choices per difficulty
+fn interval_pool(difficulty: Difficulty) -> &'static [i32] {
+    match difficulty {
+        Difficulty::Easy => &[4, 5, 7, 12],
+        Difficulty::Medium => &[2, 3, 4, 5, 7, 8, 9, 12],
+        Difficulty::Hard => &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12],
+    }
+}
+
+/// Scale degrees (semitones above the tonic) per difficulty
+fn degree_pool(difficulty: Difficulty) -> &'static [i32] {
+    match difficulty {
+        Difficulty::Easy => &[0, 4, 7],
+        Difficulty::Medium => &[0, 2, 4, 5, 7, 9, 11],
+        Difficulty::Hard => &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11],
+    }
+}
+
+/// Number of entries of `CHORDS` used per difficulty
+fn chord_pool(difficulty: Difficulty) -> usize {
+    match difficulty {
+        Difficulty::Easy => 2,
+        Difficulty::Medium => 4,
+        Difficulty::Hard => CHORDS.len(),
+    }
+}
+
+/// One generated question
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct EarQuestion {
+    kind: EarQuestionKind,
+    /// Notes heard before the question (tonic chord for scale degrees)
+    context: Vec<i32>,
+    /// Question notes as MIDI, lowest/first first
+    notes: Vec<i32>,
+    /// Whether the notes sound together rather than one after another
+    harmonic: bool,
+    answer: String,
+    choices: Vec<String>,
+}
+
+#[wasm_bindgen]
+impl EarQuestion {
+    #[wasm_bindgen(getter)]
+    pub fn kind(&self) -> EarQuestionKind { self.kind }
+
+    #[wasm_bindgen(getter)]
+    pub fn context(&self) -> Vec<i32> { self.context.clone() }
+
+    #[wasm_bindgen(getter)]
+    pub fn notes(&self) -> Vec<i32> { self.notes.clone() }
+
+    /// Question notes as names (e.g., "A4")
+    #[wasm_bindgen(getter)]
+    pub fn note_names(&self) -> Vec<String> {
+        self.notes.iter().map(|&m| midi_to_note_name(m)).collect()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn harmonic(&self) -> bool { self.harmonic }
+
+    /// Correct label (e.g., "P5", "b3" or "minor")
+    #[wasm_bindgen(getter)]
+    pub fn answer(&self) -> String { self.answer.clone() }
+
+    /// Labels offered at this difficulty
+    #[wasm_bindgen(getter)]
+    pub fn choices(&self) -> Vec<String> { self.choices.clone() }
+
+    /// Whether `label` is the correct answer
+    #[wasm_bindgen]
+    pub fn check_label(&self, label: &str) -> bool {
+        label.trim().eq_ignore_ascii_case(&self.answer)
+    }
+}
+
+/// Result of checking a sung or played answer
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct EarAnswer {
+    correct: bool,
+    /// Question notes reproduced in order
+    matched: usize,
+    expected: usize,
+    /// Notes heard that are not part of the question
+    extra: usize,
+    /// Notes heard in the recording (MIDI)
+    detected: Vec<i32>,
+    /// Mean absolute cents error of the matched notes
+    mean_abs_cents: f32,
+}
+
+#[wasm_bindgen]
+impl EarAnswer {
+    #[wasm_bindgen(getter)]
+    pub fn correct(&self) -> bool { self.correct }
+
+    #[wasm_bindgen(getter)]
+    pub fn matched(&self) -> usize { self.matched }
+
+    #[wasm_bindgen(getter)]
+    pub fn expected(&self) -> usize { self.expected }
+
+    #[wasm_bindgen(getter)]
+    pub fn extra(&self) -> usize { self.extra }
+
+    #[wasm_bindgen(getter)]
+    pub fn detected(&self) -> Vec<i32> { self.detected.clone() }
+
+    #[wasm_bindgen(getter)]
+    pub fn mean_abs_cents(&self) -> f32 { self.mean_abs_cents }
+}
+
+/// Shortest note (seconds) counted when checking a recorded answer
+const MIN_ANSWER_NOTE_SECONDS: f32 = 0.12;
+
+/// Generates, renders and checks ear-training questions
+#[wasm_bindgen]
+pub struct EarTrainer {
+    sample_rate: f32,
+    difficulty: Difficulty,
+    /// Lowest and highest MIDI notes a question may use
+    low_midi: i32,
+    high_midi: i32,
+    /// Length of each rendered note (seconds)
+    note_seconds: f32,
+    /// Whether answers an octave away count (for singers)
+    octave_equivalence: bool,
+    rng: XorShift,
+}
+
+#[wasm_bindgen]
+impl EarTrainer {
+    /// Create a trainer over the violin range (G3 to E6). The same `seed`
+    /// always produces the same question sequence.
+    #[wasm_bindgen(constructor)]
+    pub fn new(sample_rate: f32, seed: u32) -> EarTrainer {
+        EarTrainer {
+            sample_rate: clamp_sample_rate(sample_rate),
+            difficulty: Difficulty::Easy,
+            low_midi: 55,
+            high_midi: 88,
+            note_seconds: 0.8,
+            octave_equivalence: false,
+            rng: XorShift::new(seed),
+        }
+    }
+
+    #[wasm_bindgen]
+    pub fn set_difficulty(&mut self, difficulty: Difficulty) {
+        self.difficulty = difficulty;
+    }
+
+    /// Limit questions to MIDI notes `low..=high` (at least an octave wide)
+    #[wasm_bindgen]
+    pub fn set_range(&mut self, low: i32, high: i32) {
+        self.low_midi = low.clamp(21, 108);
+        self.high_midi = high.clamp(self.low_midi + 12, 120);
+    }
+
+    #[wasm_bindgen]
+    pub fn set_note_seconds(&mut self, seconds: f32) {
+        self.note_seconds = seconds.clamp(0.2, 3.0);
+    }
+
+    /// Accept sung answers in any octave
+    #[wasm_bindgen]
+    pub fn set_octave_equivalence(&mut self, enabled: bool) {
+        self.octave_equivalence = enabled;
+    }
+
+    /// Generate the next question of `kind`
+    #[wasm_bindgen]
+    pub fn next_question(&mut self, kind: EarQuestionKind) -> EarQuestion {
+        match kind {
+            EarQuestionKind::Interval => {
+                let pool = interval_pool(self.difficulty);
+                let semitones = pool[self.rng.next_index(pool.len())];
+                let root = self.random_root(semitones);
+                EarQuestion {
+                    kind,
+                    context: Vec::new(),
+                    notes: vec![root, root + semitones],
+                    harmonic: self.difficulty == Difficulty::Hard,
+                    answer: INTERVAL_NAMES[semitones as usize].to_string(),
+                    choices: pool.iter().map(|&s| INTERVAL_NAMES[s as usize].to_string()).collect(),
+                }
+            }
+            EarQuestionKind::ScaleDegree => {
+                let pool = degree_pool(self.difficulty);
+                let degree = pool[self.rng.next_index(pool.len())];
+                let tonic = self.random_root(11);
+                EarQuestion {
+                    kind,
+                    context: vec![tonic, tonic + 4, tonic + 7],
+                    notes: vec![tonic + degree],
+                    harmonic: false,
+                    answer: DEGREE_NAMES[degree as usize].to_string(),
+                    choices: pool.iter().map(|&d| DEGREE_NAMES[d as usize].to_string()).collect(),
+                }
+            }
+            EarQuestionKind::Chord => {
+                let count = chord_pool(self.difficulty);
+                let (name, shape) = CHORDS[self.rng.next_index(count)];
+                let root = self.random_root(*shape.last().unwrap_or(&0));
+                EarQuestion {
+                    kind,
+                    context: Vec::new(),
+                    notes: shape.iter().map(|&s| root + s).collect(),
+                    harmonic: self.difficulty != Difficulty::Easy,
+                    answer: name.to_string(),
+                    choices: CHORDS[..count].iter().map(|(n, _)| n.to_string()).collect(),
+                }
+            }
+        }
+    }
+
+    /// Synthesise a question: context chord (if any), then the notes either
+    /// one after another or together
+    #[wasm_bindgen]
+    pub fn render(&self, question: &EarQuestion) -> Vec<f32> {
+        let note_len = (self.note_seconds * self.sample_rate) as usize;
+        let gap_len = (0.1 * self.sample_rate) as usize;
+        let mut out = Vec::new();
+
+        if !question.context.is_empty() {
+            mix_bowed_notes(&mut out, &question.context, note_len, self.sample_rate);
+            out.resize(out.len() + gap_len * 2, 0.0);
+        }
+
+        if question.harmonic {
+            mix_bowed_notes(&mut out, &question.notes, note_len * 2, self.sample_rate);
+        } else {
+            for &note in &question.notes {
+                mix_bowed_notes(&mut out, &[note], note_len, self.sample_rate);
+                out.resize(out.len() + gap_len, 0.0);
+            }
+        }
+        out
+    }
+
+    /// Check a recording of the question's notes sung or played in order.
+    /// The answer is correct only if the notes heard are exactly the question's
+    /// notes; anything else heard in between is counted in `extra`. Pitches are detected over the trainer's range (see `set_range`), an
+    /// octave wider each way with octave equivalence on.
+    #[wasm_bindgen]
+    pub fn check_recording(&self, question: &EarQuestion, samples: &[f32]) -> EarAnswer {
+        let config = self.answer_detector_config();
+        // The analysis frame must hold two periods of the lowest note
+        let frame_size = ((2.0 * self.sample_rate / config.min_freq).ceil() as usize)
+            .next_power_of_two()
+            .max(DEFAULT_FRAME_SIZE);
+        let filtered = preprocess_recording(samples, self.sample_rate, &PreprocessConfig::new());
+        let report = analyze_prepared_with(&filtered, self.sample_rate, frame_size, DEFAULT_HOP_SIZE, &config);
+        let mut detected: Vec<(i32, f32)> = Vec::new();
+        for note in report.notes() {
+            if note.duration() < MIN_ANSWER_NOTE_SECONDS {
+                continue;
+            }
+            let midi = frequency_to_midi(note.frequency());
+            let rounded = midi.round() as i32;
+            if detected.last().is_some_and(|&(m, _)| m == rounded) {
+                continue;
+            }
+            detected.push((rounded, (midi - rounded as f32) * 100.0));
+        }
+
+        let mut matched = 0;
+        let mut cents_sum = 0.0;
+        for &(midi, cents) in &detected {
+            if matched < question.notes.len() && self.same_note(question.notes[matched], midi) {
+                cents_sum += cents.abs();
+                matched += 1;
+            }
+        }
+
+        let extra = detected.len() - matched;
+        EarAnswer {
+            correct: matched == question.notes.len() && extra == 0,
+            matched,
+            expected: question.notes.len(),
+            extra,
+            detected: detected.iter().map(|&(m, _)| m).collect(),
+            mean_abs_cents: if matched > 0 { cents_sum / matched as f32 } else { 0.0 },
+        }
+    }
+
+    /// Live check: whether `frequency` (e.g., from `PitchDetector.detect`) is
+    /// question note `index`
+    #[wasm_bindgen]
+    pub fn matches_note(&self, question: &EarQuestion, index: usize, frequency: f32) -> bool {
+        let Some(&target) = question.notes.get(index) else {
+            return false;
+        };
+        frequency > 0.0 && self.same_note(target, frequency_to_midi(frequency).round() as i32)
+    }
+}
+
+impl EarTrainer {
+    /// Detector settings covering every note an answer may use: the question
+    /// range plus a semitone of slack, and an octave more each way for singers
+    fn answer_detector_config(&self) -> DetectorConfig {
+        let margin = if self.octave_equivalence { 13 } else { 1 };
+        let mut config = DetectorConfig::new();
+        config.min_freq = midi_to_frequency((self.low_midi - margin) as f32);
+        config.max_freq = midi_to_frequency((self.high_midi + margin) as f32);
+        config.validated()
+    }
+
+    /// Root such that `root..=root + span` fits in the range
+    fn random_root(&mut self, span: i32) -> i32 {
+        let top = (self.high_midi - span).max(self.low_midi);
+        self.low_midi + self.rng.next_index((top - self.low_midi + 1) as usize) as i32
+    }
+
+    fn same_note(&self, expected: i32, heard: i32) -> bool {
+        if self.octave_equivalence {
+            (expected - heard).rem_euclid(12) == 0
+        } else {
+            expected == heard
+        }
+    }
+}
+
+/// Small deterministic xorshift generator shared by the practice generators
+#[derive(Clone, Debug)]
+pub(crate) struct XorShift(u32);
+
+impl XorShift {
+    /// The same non-zero `seed` always gives the same sequence
+    pub(crate) fn new(seed: u32) -> XorShift {
+        XorShift(if seed == 0 { 0x9E37_79B9 } else { seed })
+    }
+
+    pub(crate) fn next_u32(&mut self) -> u32 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.0 = x;
+        x
+    }
+
+    pub(crate) fn next_index(&mut self, len: usize) -> usize {
+        (self.next_u32() as usize) % len.max(1)
+    }
+}
+
+/// Append `len` samples of `notes` sounding together with a bowed-string timbre
+pub(crate) fn mix_bowed_notes(out: &mut Vec<f32>, notes: &[i32], len: usize, sample_rate: f32) {
+    let start = out.len();
+    out.resize(start + len, 0.0);
+    let attack = (0.02 * sample_rate).max(1.0);
+    let release = (0.08 * sample_rate).max(1.0);
+    let gain = 0.3 / notes.len().max(1) as f32;
+
+    for &note in notes {
+        let omega = 2.0 * std::f32::consts::PI * midi_to_frequency(note as f32) / sample_rate;
+        for (i, sample) in out[start..].iter_mut().enumerate() {
+            let t = i as f32;
+            let envelope = (t / attack).min(1.0).min((len - i) as f32 / release);
+            let phase = omega * t;
+            let tone = phase.sin() + 0.5 * (2.0 * phase).sin() + 0.25 * (3.0 * phase).sin();
+            *sample += gain * envelope * tone / 1.75;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_questions_respect_range_and_difficulty() {
+        let mut trainer = EarTrainer::new(48000.0, 7);
+        trainer.set_range(60, 76);
+        for _ in 0..50 {
+            let q = trainer.next_question(EarQuestionKind::Interval);
+            assert!(q.notes.iter().all(|&n| (60..=76).contains(&n)));
+            assert!(q.choices.contains(&q.answer));
+            assert!(["M3", "P4", "P5", "P8"].contains(&q.answer.as_str()));
+        }
+        trainer.set_difficulty(Difficulty::Hard);
+        let chord = trainer.next_question(EarQuestionKind::Chord);
+        assert!(chord.harmonic && chord.notes.len() >= 3);
+        assert!(chord.check_label(&chord.answer.to_uppercase()));
+    }
+
+    #[test]
+    fn test_rendered_question_checks_as_correct() {
+        let mut trainer = EarTrainer::new(48000.0, 3);
+        let question = trainer.next_question(EarQuestionKind::Interval);
+        let audio = trainer.render(&question);
+        let answer = trainer.check_recording(&question, &audio);
+        assert!(answer.correct(), "{:?} vs {:?}", question.notes, answer.detected);
+        assert!(answer.mean_abs_cents() < 10.0);
+
+        let wrong = EarQuestion { notes: vec![question.notes[0], question.notes[0] + 1], ..question.clone() };
+        assert!(!trainer.check_recording(&wrong, &audio).correct());
+    }
+
+    #[test]
+    fn test_chromatic_run_is_not_a_major_third() {
+        let mut trainer = EarTrainer::new(48000.0, 3);
+        let question = EarQuestion { notes: vec![60, 64], ..trainer.next_question(EarQuestionKind::Interval) };
+        let run = EarQuestion { notes: vec![60, 61, 62, 63, 64], ..question.clone() };
+        let answer = trainer.check_recording(&question, &trainer.render(&run));
+        assert_eq!(answer.detected, run.notes);
+        assert_eq!((answer.matched(), answer.extra()), (2, 3));
+        assert!(!answer.correct());
+    }
+
+    #[test]
+    fn test_octave_equivalence_for_singers() {
+        let mut trainer = EarTrainer::new(48000.0, 11);
+        let question = trainer.next_question(EarQuestionKind::ScaleDegree);
+        let target = question.notes[0];
+        let octave_down = midi_to_frequency((target - 12) as f32);
+        assert!(!trainer.matches_note(&question, 0, octave_down));
+        trainer.set_octave_equivalence(true);
+        assert!(trainer.matches_note(&question, 0, octave_down));
+    }
+
+    #[test]
+    fn test_recordings_are_checked_over_the_trainer_range() {
+        // Cello/low-voice range, below the violin detector's 180 Hz floor
+        let mut trainer = EarTrainer::new(48000.0, 5);
+        trainer.set_range(40, 60);
+        let question = trainer.next_question(EarQuestionKind::Interval);
+        assert!(question.notes[0] < 54, "{:?}", question.notes);
+        let answer = trainer.check_recording(&question, &trainer.render(&question));
+        assert!(answer.correct(), "{:?} vs {:?}", question.notes, answer.detected);
+
+        // A singer answering a violin-range question an octave down
+        let mut trainer = EarTrainer::new(48000.0, 9);
+        trainer.set_octave_equivalence(true);
+        let question = EarQuestion { notes: vec![57, 62], ..trainer.next_question(EarQuestionKind::Interval) };
+        let sung = EarQuestion { notes: vec![45, 50], ..question.clone() };
+        let answer = trainer.check_recording(&question, &trainer.render(&sung));
+        assert!(answer.correct(), "{:?}", answer.detected);
+    }
+}
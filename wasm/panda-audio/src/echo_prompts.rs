@@ -0,0 +1,369 @@
+use wasm_bindgen::prelude::*;
+use js_sys::{Array, Object};
+use crate::config::write;
+use crate::ear_training::{mix_bowed_notes, XorShift};
+use crate::{clamp_sample_rate, midi_to_note_name, Difficulty, EchoBuffer};
+
+// ============================================================================
+// Echo Game Prompts
+// ============================================================================
+
+/// What the student has to echo
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EchoPromptKind {
+    /// A rhythm on one note
+    Rhythm,
+    /// A short melody from the notes the student knows
+    Melody,
+}
+
+/// Rhythm cells as note lengths in beats; negative lengths are rests
+const EASY_CELLS: &[&[f32]] = &[&[1.0], &[2.0], &[1.0, 1.0]];
+const MEDIUM_CELLS: &[&[f32]] = &[&[1.0], &[2.0], &[0.5, 0.5], &[-1.0], &[1.0, 1.0]];
+const HARD_CELLS: &[&[f32]] = &[
+    &[1.0],
+    &[0.5, 0.5],
+    &[1.5, 0.5],
+    &[0.25, 0.25, 0.25, 0.25],
+    &[-0.5, 0.5],
+    &[-1.0],
+    &[0.5, 1.0, 0.5],
+];
+
+/// Default known notes: first-position D major on the D and A strings
+const DEFAULT_KNOWN_NOTES: [i32; 8] = [62, 64, 66, 67, 69, 71, 73, 74];
+/// Rhythm prompts use the known note nearest to this (A4, the open A string)
+const RHYTHM_PITCH: i32 = 69;
+/// Fraction of each note's length that sounds, leaving a gap before the next
+/// so every note has a clear onset
+const NOTE_SOUNDING_FRACTION: f32 = 0.85;
+
+/// Per-difficulty prompt shape
+struct Level {
+    tempo_bpm: f32,
+    beats: f32,
+    cells: &'static [&'static [f32]],
+    /// Notes in a steady-pulse melody; 0 means melodies follow a random rhythm
+    melody_notes: usize,
+    /// Largest melodic step, in positions of the known-note list
+    max_step: usize,
+}
+
+fn level(difficulty: Difficulty) -> Level {
+    match difficulty {
+        Difficulty::Easy => Level { tempo_bpm: 72.0, beats: 4.0, cells: EASY_CELLS, melody_notes: 3, max_step: 1 },
+        Difficulty::Medium => Level { tempo_bpm: 84.0, beats: 4.0, cells: MEDIUM_CELLS, melody_notes: 4, max_step: 2 },
+        Difficulty::Hard => Level { tempo_bpm: 96.0, beats: 8.0, cells: HARD_CELLS, melody_notes: 0, max_step: 4 },
+    }
+}
+
+/// One note or rest of a prompt
+#[derive(Clone, Debug, PartialEq)]
+struct EchoEvent {
+    /// MIDI note, or `None` for a rest
+    midi: Option<i32>,
+    start_beats: f32,
+    beats: f32,
+}
+
+/// A generated prompt: the events to play plus everything needed to grade
+/// the echo (note list, onset times and a reference envelope)
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct EchoPrompt {
+    kind: EchoPromptKind,
+    difficulty: Difficulty,
+    tempo_bpm: f32,
+    events: Vec<EchoEvent>,
+}
+
+impl EchoPrompt {
+    fn beats_to_ms(&self, beats: f32) -> f32 {
+        beats * 60_000.0 / self.tempo_bpm
+    }
+
+    fn sounding(&self) -> impl Iterator<Item = (i32, &EchoEvent)> {
+        self.events.iter().filter_map(|e| e.midi.map(|m| (m, e)))
+    }
+}
+
+#[wasm_bindgen]
+impl EchoPrompt {
+    #[wasm_bindgen(getter)]
+    pub fn kind(&self) -> EchoPromptKind { self.kind }
+
+    #[wasm_bindgen(getter)]
+    pub fn difficulty(&self) -> Difficulty { self.difficulty }
+
+    #[wasm_bindgen(getter)]
+    pub fn tempo_bpm(&self) -> f32 { self.tempo_bpm }
+
+    /// Sounding notes as MIDI, in order (rests excluded)
+    #[wasm_bindgen(getter)]
+    pub fn notes(&self) -> Vec<i32> {
+        self.sounding().map(|(m, _)| m).collect()
+    }
+
+    /// Sounding notes as names (e.g., "A4")
+    #[wasm_bindgen(getter)]
+    pub fn note_names(&self) -> Vec<String> {
+        self.sounding().map(|(m, _)| midi_to_note_name(m)).collect()
+    }
+
+    /// Onset of each sounding note (ms from the start of the prompt)
+    #[wasm_bindgen(getter)]
+    pub fn starts_ms(&self) -> Vec<f32> {
+        self.sounding().map(|(_, e)| self.beats_to_ms(e.start_beats)).collect()
+    }
+
+    /// Written length of each sounding note (ms)
+    #[wasm_bindgen(getter)]
+    pub fn durations_ms(&self) -> Vec<f32> {
+        self.sounding().map(|(_, e)| self.beats_to_ms(e.beats)).collect()
+    }
+
+    /// Length of the whole prompt, trailing rests included (ms)
+    #[wasm_bindgen(getter)]
+    pub fn duration_ms(&self) -> f32 {
+        self.beats_to_ms(self.events.last().map_or(0.0, |e| e.start_beats + e.beats))
+    }
+
+    /// Events as `[{ start, duration, type: "note" | "rest", midi?, note? }]`
+    /// with times in ms, the shape the echo game draws from
+    #[wasm_bindgen]
+    pub fn to_js(&self) -> JsValue {
+        let events = Array::new();
+        for event in &self.events {
+            let object = Object::new();
+            write(&object, "start", self.beats_to_ms(event.start_beats).into());
+            write(&object, "duration", self.beats_to_ms(event.beats).into());
+            match event.midi {
+                Some(midi) => {
+                    write(&object, "type", "note".into());
+                    write(&object, "midi", midi.into());
+                    write(&object, "note", midi_to_note_name(midi).into());
+                }
+                None => write(&object, "type", "rest".into()),
+            }
+            events.push(&object);
+        }
+        events.into()
+    }
+}
+
+/// Generates and renders echo-game prompts
+#[wasm_bindgen]
+pub struct EchoPromptGenerator {
+    sample_rate: f32,
+    difficulty: Difficulty,
+    /// Notes the student can play (MIDI, ascending)
+    known_notes: Vec<i32>,
+    rng: XorShift,
+}
+
+#[wasm_bindgen]
+impl EchoPromptGenerator {
+    /// The same `seed` always produces the same prompt sequence
+    #[wasm_bindgen(constructor)]
+    pub fn new(sample_rate: f32, seed: u32) -> EchoPromptGenerator {
+        EchoPromptGenerator {
+            sample_rate: clamp_sample_rate(sample_rate),
+            difficulty: Difficulty::Easy,
+            known_notes: DEFAULT_KNOWN_NOTES.to_vec(),
+            rng: XorShift::new(seed),
+        }
+    }
+
+    #[wasm_bindgen]
+    pub fn set_difficulty(&mut self, difficulty: Difficulty) {
+        self.difficulty = difficulty;
+    }
+
+    /// Restrict melodies to these MIDI notes. Out-of-range notes are
+    /// ignored; an empty list keeps the current notes.
+    #[wasm_bindgen]
+    pub fn set_known_notes(&mut self, notes: &[i32]) {
+        let mut notes: Vec<i32> = notes.iter().copied().filter(|n| (21..=108).contains(n)).collect();
+        notes.sort_unstable();
+        notes.dedup();
+        if !notes.is_empty() {
+            self.known_notes = notes;
+        }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn known_notes(&self) -> Vec<i32> {
+        self.known_notes.clone()
+    }
+
+    /// Generate the next prompt of `kind`
+    #[wasm_bindgen]
+    pub fn next_prompt(&mut self, kind: EchoPromptKind) -> EchoPrompt {
+        let level = level(self.difficulty);
+        let mut events = match kind {
+            EchoPromptKind::Rhythm => self.rhythm(&level),
+            EchoPromptKind::Melody if level.melody_notes == 0 => self.rhythm(&level),
+            EchoPromptKind::Melody => (0..level.melody_notes)
+                .map(|i| EchoEvent { midi: Some(0), start_beats: i as f32, beats: 1.0 })
+                .collect(),
+        };
+
+        let rhythm_pitch = *self
+            .known_notes
+            .iter()
+            .min_by_key(|&&n| (n - RHYTHM_PITCH).abs())
+            .unwrap_or(&RHYTHM_PITCH);
+        let mut position = self.rng.next_index(self.known_notes.len());
+        for event in events.iter_mut().filter(|e| e.midi.is_some()) {
+            event.midi = Some(match kind {
+                EchoPromptKind::Rhythm => rhythm_pitch,
+                EchoPromptKind::Melody => {
+                    let note = self.known_notes[position];
+                    position = self.step(position, level.max_step);
+                    note
+                }
+            });
+        }
+
+        EchoPrompt { kind, difficulty: self.difficulty, tempo_bpm: level.tempo_bpm, events }
+    }
+
+    /// Synthesise a prompt with a bowed-string timbre
+    #[wasm_bindgen]
+    pub fn render(&self, prompt: &EchoPrompt) -> Vec<f32> {
+        let samples_per_beat = 60.0 / prompt.tempo_bpm * self.sample_rate;
+        let total = (prompt.duration_ms() / 1000.0 * self.sample_rate) as usize;
+        let mut out = Vec::with_capacity(total);
+        for event in &prompt.events {
+            let start = (event.start_beats * samples_per_beat) as usize;
+            out.resize(start, 0.0);
+            if let Some(midi) = event.midi {
+                let len = (event.beats * NOTE_SOUNDING_FRACTION * samples_per_beat) as usize;
+                mix_bowed_notes(&mut out, &[midi], len, self.sample_rate);
+            }
+        }
+        out.resize(total, 0.0);
+        out
+    }
+
+    /// Envelope of the rendered prompt in `bins` slices, computed exactly
+    /// like `EchoBuffer::extract_envelope` so it compares with the student's
+    #[wasm_bindgen]
+    pub fn reference_envelope(&self, prompt: &EchoPrompt, bins: usize) -> Vec<f32> {
+        let audio = self.render(prompt);
+        let mut buffer = EchoBuffer::new(audio.len());
+        buffer.set_recording(true);
+        buffer.push_chunk(&audio);
+        buffer.extract_envelope(bins)
+    }
+
+    /// Fill `level.beats` with random rhythm cells; the first event is a note
+    fn rhythm(&mut self, level: &Level) -> Vec<EchoEvent> {
+        let mut events = Vec::new();
+        let mut beat = 0.0;
+        while beat < level.beats {
+            let remaining = level.beats - beat;
+            let fitting: Vec<&[f32]> = level
+                .cells
+                .iter()
+                .copied()
+                .filter(|cell| cell.iter().map(|b| b.abs()).sum::<f32>() <= remaining)
+                .filter(|cell| !events.is_empty() || cell[0] > 0.0)
+                .collect();
+            let cell = fitting[self.rng.next_index(fitting.len())];
+            for &length in cell {
+                let beats = length.abs();
+                events.push(EchoEvent { midi: (length > 0.0).then_some(0), start_beats: beat, beats });
+                beat += beats;
+            }
+        }
+        events
+    }
+
+    /// Move up or down the known-note list by 1..=`max_step` positions
+    fn step(&mut self, position: usize, max_step: usize) -> usize {
+        let last = self.known_notes.len() - 1;
+        if last == 0 {
+            return 0;
+        }
+        let size = 1 + self.rng.next_index(max_step.min(last));
+        let up = self.rng.next_u32() & 1 == 0;
+        if (up && position + size <= last) || position < size {
+            (position + size).min(last)
+        } else {
+            position - size
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prompts_fill_the_bar_within_known_notes() {
+        let mut generator = EchoPromptGenerator::new(48000.0, 11);
+        generator.set_known_notes(&[69, 71, 73, 74, 76]);
+        for difficulty in [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard] {
+            generator.set_difficulty(difficulty);
+            let level = level(difficulty);
+            for _ in 0..30 {
+                let rhythm = generator.next_prompt(EchoPromptKind::Rhythm);
+                let total: f32 = rhythm.events.iter().map(|e| e.beats).sum();
+                assert_eq!(total, level.beats);
+                assert!(rhythm.events[0].midi.is_some());
+                assert!(rhythm.notes().iter().all(|&n| n == 69));
+                assert_eq!(rhythm.duration_ms(), level.beats * 60_000.0 / level.tempo_bpm);
+
+                let melody = generator.next_prompt(EchoPromptKind::Melody);
+                assert!(melody.notes().iter().all(|n| [69, 71, 73, 74, 76].contains(n)));
+                assert!(melody.notes().windows(2).all(|w| w[0] != w[1]));
+            }
+        }
+        generator.set_difficulty(Difficulty::Easy);
+        let easy = generator.next_prompt(EchoPromptKind::Melody);
+        assert_eq!(easy.notes().len(), 3);
+        assert_eq!(easy.starts_ms(), vec![0.0, 60_000.0 / 72.0, 120_000.0 / 72.0]);
+    }
+
+    #[test]
+    fn test_render_and_reference_match_the_note_list() {
+        let sample_rate = 48000.0;
+        let mut generator = EchoPromptGenerator::new(sample_rate, 5);
+        generator.set_difficulty(Difficulty::Medium);
+        let prompt = generator.next_prompt(EchoPromptKind::Melody);
+        let audio = generator.render(&prompt);
+        assert_eq!(audio.len(), (prompt.duration_ms() / 1000.0 * sample_rate) as usize);
+
+        // The rendered audio analyses back to the prompt's notes
+        let heard: Vec<i32> = crate::analyze_recording(&audio, sample_rate)
+            .notes()
+            .iter()
+            .filter(|n| n.duration() > 0.15)
+            .map(|n| crate::frequency_to_midi(n.frequency()).round() as i32)
+            .collect();
+        assert_eq!(heard, prompt.notes());
+
+        // Loud where notes sound, silent between them
+        let bins = 400;
+        let envelope = generator.reference_envelope(&prompt, bins);
+        assert_eq!(envelope.len(), bins);
+        let bin_of = |ms: f32| (ms / prompt.duration_ms() * bins as f32) as usize;
+        for (start, duration) in prompt.starts_ms().iter().zip(prompt.durations_ms()) {
+            assert!(envelope[bin_of(start + duration * 0.5)] > 0.5);
+            assert!(envelope[bin_of(start + duration * 0.95)] < 0.05);
+        }
+    }
+
+    #[test]
+    fn test_same_seed_same_prompts() {
+        let mut a = EchoPromptGenerator::new(44100.0, 99);
+        let mut b = EchoPromptGenerator::new(44100.0, 99);
+        a.set_difficulty(Difficulty::Hard);
+        b.set_difficulty(Difficulty::Hard);
+        for kind in [EchoPromptKind::Rhythm, EchoPromptKind::Melody, EchoPromptKind::Melody] {
+            assert_eq!(a.next_prompt(kind).events, b.next_prompt(kind).events);
+        }
+    }
+}
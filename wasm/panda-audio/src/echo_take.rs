@@ -0,0 +1,191 @@
+use wasm_bindgen::prelude::*;
+use js_sys::Object;
+use crate::config::write;
+use crate::health::{sanitize_sample, InputHealth, InputScan};
+
+// ============================================================================
+// Echo Take Metadata
+// ============================================================================
+
+/// Integrity metadata for one `EchoBuffer` recording: when it started, at
+/// what rate, how much was kept and how loud it was
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EchoTakeMetadata {
+    start_timestamp_ms: f64,
+    sample_rate: f32,
+    sample_count: usize,
+    capacity: usize,
+    dropped_samples: usize,
+    peak: f32,
+    rms: f32,
+    clipping_percent: f32,
+    input_health: InputHealth,
+}
+
+#[wasm_bindgen]
+impl EchoTakeMetadata {
+    /// Caller-supplied time the recording started (e.g. `currentTime * 1000`)
+    #[wasm_bindgen(getter)]
+    pub fn start_timestamp_ms(&self) -> f64 { self.start_timestamp_ms }
+
+    #[wasm_bindgen(getter)]
+    pub fn sample_rate(&self) -> f32 { self.sample_rate }
+
+    /// Samples actually stored in the buffer
+    #[wasm_bindgen(getter)]
+    pub fn sample_count(&self) -> usize { self.sample_count }
+
+    #[wasm_bindgen(getter)]
+    pub fn capacity(&self) -> usize { self.capacity }
+
+    /// Length of the stored audio
+    #[wasm_bindgen(getter)]
+    pub fn duration_ms(&self) -> f64 {
+        self.sample_count as f64 * 1000.0 / self.sample_rate as f64
+    }
+
+    /// Samples pushed after the buffer was full and thrown away
+    #[wasm_bindgen(getter)]
+    pub fn dropped_samples(&self) -> usize { self.dropped_samples }
+
+    /// Whether the buffer filled up, so the take may be cut short
+    #[wasm_bindgen(getter)]
+    pub fn hit_capacity(&self) -> bool {
+        self.capacity > 0 && self.sample_count >= self.capacity
+    }
+
+    /// Largest absolute sample (0-1)
+    #[wasm_bindgen(getter)]
+    pub fn peak(&self) -> f32 { self.peak }
+
+    /// RMS level over the whole take
+    #[wasm_bindgen(getter)]
+    pub fn rms(&self) -> f32 { self.rms }
+
+    /// Percentage (0-100) of samples at full scale
+    #[wasm_bindgen(getter)]
+    pub fn clipping_percent(&self) -> f32 { self.clipping_percent }
+
+    #[wasm_bindgen(getter)]
+    pub fn input_health(&self) -> InputHealth { self.input_health }
+
+    /// Plain JS object with the same fields, for storage alongside the take
+    #[wasm_bindgen]
+    pub fn to_js(&self) -> JsValue {
+        let object = Object::new();
+        write(&object, "startTimestampMs", self.start_timestamp_ms.into());
+        write(&object, "sampleRate", self.sample_rate.into());
+        write(&object, "sampleCount", (self.sample_count as f64).into());
+        write(&object, "capacity", (self.capacity as f64).into());
+        write(&object, "durationMs", self.duration_ms().into());
+        write(&object, "droppedSamples", (self.dropped_samples as f64).into());
+        write(&object, "hitCapacity", self.hit_capacity().into());
+        write(&object, "peak", self.peak.into());
+        write(&object, "rms", self.rms.into());
+        write(&object, "clippingPercent", self.clipping_percent.into());
+        let health = match self.input_health {
+            InputHealth::Good => "good",
+            InputHealth::Clipping => "clipping",
+            InputHealth::Invalid => "invalid",
+        };
+        write(&object, "inputHealth", health.into());
+        object.into()
+    }
+}
+
+/// Running statistics of the take being recorded, updated per chunk so
+/// reading the metadata never rescans the buffer
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct TakeStats {
+    start_timestamp_ms: f64,
+    dropped: usize,
+    peak: f32,
+    sum_squares: f64,
+    scan: InputScan,
+}
+
+impl TakeStats {
+    pub(crate) fn start(timestamp_ms: f64) -> TakeStats {
+        TakeStats { start_timestamp_ms: timestamp_ms, ..TakeStats::default() }
+    }
+
+    /// Account for one pushed chunk: `stored` went into the buffer and
+    /// `dropped` further samples did not fit
+    pub(crate) fn record(&mut self, stored: &[f32], dropped: usize) {
+        self.dropped += dropped;
+        self.scan.merge(&InputScan::of(stored));
+        for &sample in stored {
+            let sample = sanitize_sample(sample);
+            self.peak = self.peak.max(sample.abs());
+            self.sum_squares += (sample * sample) as f64;
+        }
+    }
+
+    pub(crate) fn metadata(&self, sample_rate: f32, sample_count: usize, capacity: usize) -> EchoTakeMetadata {
+        let rms = if sample_count == 0 { 0.0 } else { (self.sum_squares / sample_count as f64).sqrt() as f32 };
+        EchoTakeMetadata {
+            start_timestamp_ms: self.start_timestamp_ms,
+            sample_rate,
+            sample_count,
+            capacity,
+            dropped_samples: self.dropped,
+            peak: self.peak,
+            rms,
+            clipping_percent: self.scan.clipping_percent(),
+            input_health: self.scan.health(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_signals::sine;
+    use crate::{EchoBuffer, InputHealth};
+
+    #[test]
+    fn test_metadata_describes_take() {
+        let mut echo = EchoBuffer::new(48000);
+        echo.set_sample_rate(48000.0);
+        echo.start_recording(1250.5);
+        let audio = sine(440.0, 0.5, 24000, 48000.0);
+        for chunk in audio.chunks(2048) {
+            assert!(!echo.push_chunk(chunk));
+        }
+
+        let meta = echo.metadata();
+        assert_eq!(meta.start_timestamp_ms(), 1250.5);
+        assert_eq!(meta.sample_rate(), 48000.0);
+        assert_eq!(meta.sample_count(), 24000);
+        assert_eq!(meta.duration_ms(), 500.0);
+        assert!(!meta.hit_capacity());
+        assert_eq!(meta.dropped_samples(), 0);
+        assert!((meta.peak() - 0.5).abs() < 1e-3, "{}", meta.peak());
+        assert!((meta.rms() - 0.5 / 2f32.sqrt()).abs() < 1e-3, "{}", meta.rms());
+        assert_eq!(meta.input_health(), InputHealth::Good);
+
+        // A new take starts from scratch
+        echo.start_recording(9000.0);
+        let meta = echo.metadata();
+        assert_eq!((meta.start_timestamp_ms(), meta.sample_count(), meta.peak()), (9000.0, 0, 0.0));
+    }
+
+    #[test]
+    fn test_metadata_reports_overflow_and_bad_input() {
+        let mut echo = EchoBuffer::new(3000);
+        echo.start_recording(0.0);
+        let mut audio = sine(440.0, 0.5, 2048, 48000.0);
+        audio[100] = f32::NAN;
+        assert!(!echo.push_chunk(&audio));
+        assert!(echo.push_chunk(&audio));
+        // Still armed but full: everything further is dropped
+        assert!(!echo.push_chunk(&audio));
+
+        let meta = echo.metadata();
+        assert_eq!(meta.sample_count(), 3000);
+        assert!(meta.hit_capacity());
+        assert_eq!(meta.dropped_samples(), 3 * 2048 - 3000);
+        assert_eq!(meta.input_health(), InputHealth::Invalid);
+        assert!(meta.peak() <= 0.5 + 1e-3);
+    }
+}
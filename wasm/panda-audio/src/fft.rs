@@ -0,0 +1,129 @@
+use std::f32::consts::PI;
+
+// ============================================================================
+// Radix-2 FFT and Spectrum Helpers
+// ============================================================================
+
+/// In-place iterative radix-2 FFT. Both slices must share a power-of-two length.
+pub(crate) fn fft_in_place(re: &mut [f32], im: &mut [f32]) {
+    let n = re.len();
+    debug_assert_eq!(n, im.len());
+    debug_assert!(n.is_power_of_two());
+    if n < 2 {
+        return;
+    }
+
+    // Bit-reversal permutation
+    let bits = n.trailing_zeros();
+    for i in 0..n {
+        let j = i.reverse_bits() >> (usize::BITS - bits);
+        if j > i {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+
+    // Butterflies
+    let mut len = 2;
+    while len <= n {
+        let angle = -2.0 * PI / len as f32;
+        let (w_im, w_re) = angle.sin_cos();
+        for start in (0..n).step_by(len) {
+            let mut cur_re = 1.0f32;
+            let mut cur_im = 0.0f32;
+            for k in 0..len / 2 {
+                let a = start + k;
+                let b = a + len / 2;
+                let t_re = re[b] * cur_re - im[b] * cur_im;
+                let t_im = re[b] * cur_im + im[b] * cur_re;
+                re[b] = re[a] - t_re;
+                im[b] = im[a] - t_im;
+                re[a] += t_re;
+                im[a] += t_im;
+                let next_re = cur_re * w_re - cur_im * w_im;
+                cur_im = cur_re * w_im + cur_im * w_re;
+                cur_re = next_re;
+            }
+        }
+        len <<= 1;
+    }
+}
+
+/// Hann window of the given length
+pub(crate) fn hann_window(len: usize) -> Vec<f32> {
+    if len < 2 {
+        return vec![1.0; len];
+    }
+    (0..len)
+        .map(|i| 0.5 - 0.5 * (2.0 * PI * i as f32 / (len - 1) as f32).cos())
+        .collect()
+}
+
+/// Reusable windowed magnitude-spectrum calculator
+pub(crate) struct SpectrumAnalyzer {
+    fft_size: usize,
+    window: Vec<f32>,
+    re: Vec<f32>,
+    im: Vec<f32>,
+}
+
+impl SpectrumAnalyzer {
+    /// `fft_size` is rounded up to a power of two
+    pub(crate) fn new(fft_size: usize) -> SpectrumAnalyzer {
+        let fft_size = fft_size.max(2).next_power_of_two();
+        SpectrumAnalyzer {
+            fft_size,
+            window: hann_window(fft_size),
+            re: vec![0.0; fft_size],
+            im: vec![0.0; fft_size],
+        }
+    }
+
+    pub(crate) fn fft_size(&self) -> usize {
+        self.fft_size
+    }
+
+    /// Magnitude spectrum (`fft_size / 2 + 1` bins) of the frame centred on
+    /// `center`; samples outside `samples` are treated as silence.
+    pub(crate) fn magnitudes(&mut self, samples: &[f32], center: usize, out: &mut Vec<f32>) {
+        let half = self.fft_size / 2;
+        for i in 0..self.fft_size {
+            let idx = (center + i).checked_sub(half);
+            let sample = idx.and_then(|idx| samples.get(idx)).copied().unwrap_or(0.0);
+            self.re[i] = sample * self.window[i];
+            self.im[i] = 0.0;
+        }
+        fft_in_place(&mut self.re, &mut self.im);
+
+        out.clear();
+        out.extend((0..=half).map(|k| (self.re[k] * self.re[k] + self.im[k] * self.im[k]).sqrt()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fft_sine_peak_bin() {
+        let n = 1024;
+        let sample_rate = 48000.0;
+        // Exactly bin 32
+        let frequency = 32.0 * sample_rate / n as f32;
+        let samples: Vec<f32> = (0..n)
+            .map(|i| (2.0 * PI * frequency * i as f32 / sample_rate).sin())
+            .collect();
+
+        let mut analyzer = SpectrumAnalyzer::new(n);
+        let mut mags = Vec::new();
+        analyzer.magnitudes(&samples, n / 2, &mut mags);
+        let peak = mags
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .map(|(i, _)| i)
+            .unwrap();
+        assert_eq!(mags.len(), n / 2 + 1);
+        assert_eq!(peak, 32);
+    }
+}
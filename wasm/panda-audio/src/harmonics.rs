@@ -0,0 +1,237 @@
+use std::collections::VecDeque;
+use crate::fft::hann_window;
+
+// ============================================================================
+// Harmonic-Aware Octave & Fifth Error Correction
+// ============================================================================
+
+/// Number of recent pitches kept for the continuity check
+const HISTORY_LEN: usize = 5;
+/// Unvoiced frames after which the history is forgotten
+const HISTORY_TIMEOUT_FRAMES: u32 = 4;
+/// Tolerance when matching a jump to an interval ratio (cents)
+const RATIO_TOLERANCE_CENTS: f32 = 35.0;
+/// Default sub-harmonic energy, relative to the detected pitch's own lowest
+/// partials, above which the true fundamental is taken to be lower
+pub(crate) const DEFAULT_SUBHARMONIC_RATIO: f32 = 0.03;
+/// Energy of the detected fundamental's odd partials, relative to its second
+/// partial, below which the true fundamental is taken to be an octave higher
+const MISSING_ODD_RATIO: f32 = 0.05;
+/// Energy of a history candidate's distinctive partials, relative to the
+/// detected pitch's lowest partials, above which it vetoes a jump
+const HISTORY_SUPPORT_RATIO: f32 = 0.005;
+
+/// Interval ratios treated as suspicious jumps: octave, fifth and twelfth
+const JUMP_RATIOS: [f32; 6] = [2.0, 0.5, 1.5, 2.0 / 3.0, 3.0, 1.0 / 3.0];
+
+/// Normalized power of `buffer` at `frequency` (Goertzel algorithm)
+pub(crate) fn goertzel_power(buffer: &[f32], frequency: f32, sample_rate: f32) -> f32 {
+    if buffer.is_empty() || frequency <= 0.0 || frequency >= sample_rate / 2.0 {
+        return 0.0;
+    }
+    let omega = 2.0 * std::f32::consts::PI * frequency / sample_rate;
+    let coeff = 2.0 * omega.cos();
+    let (mut s1, mut s2) = (0.0f32, 0.0f32);
+    for &x in buffer {
+        let s0 = x + coeff * s1 - s2;
+        s2 = s1;
+        s1 = s0;
+    }
+    let power = s1 * s1 + s2 * s2 - coeff * s1 * s2;
+    let n = buffer.len() as f32;
+    (power / (n * n)).max(0.0)
+}
+
+fn cents_between(a: f32, b: f32) -> f32 {
+    1200.0 * (a / b).log2()
+}
+
+/// Keeps a short pitch history and vetoes octave and fifth errors using the
+/// harmonic structure of the current buffer.
+pub(crate) struct HarmonicCorrector {
+    /// Sub-harmonic energy ratio that triggers a downward correction
+    /// (lower for instruments with weak fundamentals)
+    pub(crate) subharmonic_ratio: f32,
+    history: VecDeque<f32>,
+    unvoiced_frames: u32,
+    /// Hann window matching the last buffer length (limits leakage between partials)
+    window: Vec<f32>,
+    /// Reusable windowed copy of the current buffer
+    windowed: Vec<f32>,
+}
+
+impl HarmonicCorrector {
+    pub(crate) fn new() -> HarmonicCorrector {
+        HarmonicCorrector {
+            subharmonic_ratio: DEFAULT_SUBHARMONIC_RATIO,
+            history: VecDeque::with_capacity(HISTORY_LEN),
+            unvoiced_frames: 0,
+            window: Vec::new(),
+            windowed: Vec::new(),
+        }
+    }
+
+    /// Forget the pitch history
+    pub(crate) fn reset(&mut self) {
+        self.history.clear();
+        self.unvoiced_frames = 0;
+    }
+
+    /// Record a frame in which no pitch was detected
+    pub(crate) fn mark_unvoiced(&mut self) {
+        self.unvoiced_frames += 1;
+        if self.unvoiced_frames > HISTORY_TIMEOUT_FRAMES {
+            self.history.clear();
+        }
+    }
+
+    /// Return the corrected frequency for a detected `frequency`, restricted
+    /// to `min_freq..=max_freq`, and record it in the history.
+    pub(crate) fn correct(
+        &mut self,
+        buffer: &[f32],
+        frequency: f32,
+        sample_rate: f32,
+        min_freq: f32,
+        max_freq: f32,
+    ) -> f32 {
+        if self.window.len() != buffer.len() {
+            self.window = hann_window(buffer.len());
+        }
+        self.windowed.clear();
+        self.windowed.extend(buffer.iter().zip(&self.window).map(|(x, w)| x * w));
+        let windowed = &self.windowed;
+
+        let power = |f: f32| goertzel_power(windowed, f, sample_rate);
+        let in_range = |f: f32| f >= min_freq && f <= max_freq;
+        let mut corrected = frequency;
+
+        // 1. Structure check: energy below the detected pitch means we locked
+        //    onto a harmonic (usually the 2nd on the G string)
+        let own = power(frequency) + power(2.0 * frequency);
+        for divisor in [2.0f32, 3.0] {
+            let candidate = frequency / divisor;
+            if !in_range(candidate) {
+                continue;
+            }
+            // Partials of the candidate that are not partials of `frequency`
+            let sub: f32 = (1..=(2 * divisor as usize))
+                .filter(|h| h % divisor as usize != 0)
+                .map(|h| power(candidate * h as f32))
+                .sum();
+            if sub > own * self.subharmonic_ratio {
+                corrected = candidate;
+                break;
+            }
+        }
+
+        // 2. Missing odd partials mean we locked onto a sub-harmonic
+        if corrected == frequency && in_range(2.0 * frequency) {
+            let odd = power(frequency) + power(3.0 * frequency);
+            if odd < power(2.0 * frequency) * MISSING_ODD_RATIO {
+                corrected = 2.0 * frequency;
+            }
+        }
+
+        // 3. Continuity: a sudden octave/fifth/twelfth jump away from the
+        //    recent pitch is vetoed if the old pitch's own partials (those it
+        //    does not share with the new pitch) are still sounding
+        if let Some(reference) = self.reference() {
+            let ratio = corrected / reference;
+            let jump = JUMP_RATIOS
+                .iter()
+                .find(|&&r| cents_between(ratio, r).abs() < RATIO_TOLERANCE_CENTS);
+            if let Some(&r) = jump {
+                let candidate = corrected / r;
+                let distinctive: f32 = (1..=4)
+                    .map(|h| h as f32)
+                    .filter(|&h| ((h / r) - (h / r).round()).abs() > 0.1)
+                    .map(|h| power(candidate * h))
+                    .sum();
+                let own = power(corrected) + power(2.0 * corrected);
+                if in_range(candidate) && distinctive > own * HISTORY_SUPPORT_RATIO {
+                    corrected = candidate;
+                }
+            }
+        }
+
+        if self.history.len() == HISTORY_LEN {
+            self.history.pop_front();
+        }
+        self.history.push_back(corrected);
+        self.unvoiced_frames = 0;
+        corrected
+    }
+
+    /// Median of the recent pitches, once enough have been seen
+    fn reference(&self) -> Option<f32> {
+        if self.history.len() < 3 {
+            return None;
+        }
+        let mut sorted: Vec<f32> = self.history.iter().copied().collect();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        Some(sorted[sorted.len() / 2])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn partials(fundamental: f32, amplitudes: &[f32], len: usize, sample_rate: f32) -> Vec<f32> {
+        (0..len)
+            .map(|i| {
+                let t = i as f32 / sample_rate;
+                amplitudes
+                    .iter()
+                    .enumerate()
+                    .map(|(h, &a)| {
+                        let f = fundamental * (h + 1) as f32;
+                        a * (2.0 * std::f32::consts::PI * f * t).sin()
+                    })
+                    .sum()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_subharmonic_energy_corrects_octave_down() {
+        // G3 with a weak fundamental and dominant second harmonic
+        let buffer = partials(196.0, &[0.08, 0.5, 0.2, 0.1], 2048, 48000.0);
+        let mut corrector = HarmonicCorrector::new();
+        let corrected = corrector.correct(&buffer, 392.0, 48000.0, 180.0, 1400.0);
+        assert!((corrected - 196.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_clean_tone_is_not_corrected() {
+        let buffer = partials(392.0, &[0.5, 0.2, 0.1], 2048, 48000.0);
+        let mut corrector = HarmonicCorrector::new();
+        let corrected = corrector.correct(&buffer, 392.0, 48000.0, 180.0, 1400.0);
+        assert_eq!(corrected, 392.0);
+    }
+
+    #[test]
+    fn test_genuine_octave_leap_is_allowed() {
+        let mut corrector = HarmonicCorrector::new();
+        let low = partials(220.0, &[0.5, 0.2, 0.1], 2048, 48000.0);
+        for _ in 0..HISTORY_LEN {
+            corrector.correct(&low, 220.0, 48000.0, 180.0, 1400.0);
+        }
+        let high = partials(440.0, &[0.5, 0.2, 0.1], 2048, 48000.0);
+        assert_eq!(corrector.correct(&high, 440.0, 48000.0, 180.0, 1400.0), 440.0);
+    }
+
+    #[test]
+    fn test_history_vetoes_octave_jump() {
+        let mut corrector = HarmonicCorrector::new();
+        let g3 = partials(196.0, &[0.3, 0.5, 0.2], 2048, 48000.0);
+        for _ in 0..HISTORY_LEN {
+            corrector.correct(&g3, 196.0, 48000.0, 180.0, 1400.0);
+        }
+        // Fundamental nearly gone: too weak for the structure check alone
+        let faded = partials(196.0, &[0.0, 0.5, 0.06], 2048, 48000.0);
+        let corrected = corrector.correct(&faded, 392.0, 48000.0, 180.0, 1400.0);
+        assert!((corrected - 196.0).abs() < 1.0);
+    }
+}
@@ -0,0 +1,126 @@
+use wasm_bindgen::prelude::*;
+
+// ============================================================================
+// Input Health (non-finite samples and clipping)
+// ============================================================================
+
+/// State of the microphone signal behind a detection result
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InputHealth {
+    /// Nothing wrong with the input
+    Good,
+    /// Too many samples at full scale: the player is too close or too loud
+    Clipping,
+    /// The input contained NaN or infinite samples (a glitching device);
+    /// they were replaced with silence before detection
+    Invalid,
+}
+
+/// Samples at or above this magnitude count as clipped
+const CLIP_LEVEL: f32 = 0.99;
+/// Percentage of clipped samples above which the input is reported as clipping
+const CLIPPING_WARN_PERCENT: f32 = 1.0;
+
+/// A sample made safe for arithmetic: non-finite values become silence and
+/// anything beyond full scale is clipped to it
+#[inline]
+pub(crate) fn sanitize_sample(sample: f32) -> f32 {
+    if sample.is_finite() { sample.clamp(-1.0, 1.0) } else { 0.0 }
+}
+
+/// Counts of problem samples in one buffer
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct InputScan {
+    len: usize,
+    clipped: usize,
+    out_of_range: usize,
+    non_finite: usize,
+}
+
+impl InputScan {
+    pub(crate) fn of(buffer: &[f32]) -> InputScan {
+        let mut scan = InputScan { len: buffer.len(), ..InputScan::default() };
+        for &sample in buffer {
+            if !sample.is_finite() {
+                scan.non_finite += 1;
+            } else if sample.abs() >= CLIP_LEVEL {
+                scan.clipped += 1;
+                if sample.abs() > 1.0 {
+                    scan.out_of_range += 1;
+                }
+            }
+        }
+        scan
+    }
+
+    /// Fold the counts of a later buffer into this one
+    pub(crate) fn merge(&mut self, other: &InputScan) {
+        self.len += other.len;
+        self.clipped += other.clipped;
+        self.out_of_range += other.out_of_range;
+        self.non_finite += other.non_finite;
+    }
+
+    /// Whether the buffer must be copied through `sanitize_sample` before use
+    pub(crate) fn needs_sanitizing(&self) -> bool {
+        self.non_finite > 0 || self.out_of_range > 0
+    }
+
+    /// Percentage (0-100) of finite samples at full scale
+    pub(crate) fn clipping_percent(&self) -> f32 {
+        let finite = self.len - self.non_finite;
+        if finite == 0 {
+            return 0.0;
+        }
+        100.0 * self.clipped as f32 / finite as f32
+    }
+
+    pub(crate) fn health(&self) -> InputHealth {
+        if self.non_finite > 0 {
+            InputHealth::Invalid
+        } else if self.clipping_percent() > CLIPPING_WARN_PERCENT {
+            InputHealth::Clipping
+        } else {
+            InputHealth::Good
+        }
+    }
+}
+
+/// Copy `buffer` into `out` with every sample passed through `sanitize_sample`
+pub(crate) fn sanitize_into(buffer: &[f32], out: &mut Vec<f32>) {
+    out.clear();
+    out.extend(buffer.iter().map(|&s| sanitize_sample(s)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_classifies_input() {
+        let clean: Vec<f32> = (0..1000).map(|i| 0.5 * (i as f32 * 0.05).sin()).collect();
+        let scan = InputScan::of(&clean);
+        assert_eq!(scan.health(), InputHealth::Good);
+        assert!(!scan.needs_sanitizing());
+        assert_eq!(scan.clipping_percent(), 0.0);
+
+        // A sine driven 3x past full scale and hard-limited
+        let clipped: Vec<f32> = clean.iter().map(|s| (s * 6.0).clamp(-1.0, 1.0)).collect();
+        let scan = InputScan::of(&clipped);
+        assert_eq!(scan.health(), InputHealth::Clipping);
+        assert!(scan.clipping_percent() > 50.0, "{}", scan.clipping_percent());
+
+        let mut glitched = clean.clone();
+        glitched[10] = f32::NAN;
+        glitched[20] = f32::INFINITY;
+        glitched[30] = 4.0;
+        let scan = InputScan::of(&glitched);
+        assert_eq!(scan.health(), InputHealth::Invalid);
+        assert!(scan.needs_sanitizing());
+
+        let mut out = Vec::new();
+        sanitize_into(&glitched, &mut out);
+        assert_eq!((out[10], out[20], out[30], out[40]), (0.0, 0.0, 1.0, clean[40]));
+    }
+}
@@ -0,0 +1,232 @@
+use wasm_bindgen::prelude::*;
+use crate::{frequency_to_midi, midi_to_frequency, midi_to_note_name, DetectorConfig};
+
+// ============================================================================
+// Instrument Presets
+// ============================================================================
+
+/// Supported string instruments (fractional violins share violin tuning)
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Instrument {
+    Violin,
+    ViolinThreeQuarter,
+    ViolinHalf,
+    ViolinQuarter,
+    ViolinEighth,
+    ViolinSixteenth,
+    Viola,
+    Cello,
+}
+
+/// Open strings as MIDI notes, lowest first
+const VIOLIN_STRINGS: [i32; 4] = [55, 62, 69, 76]; // G3 D4 A4 E5
+const VIOLA_STRINGS: [i32; 4] = [48, 55, 62, 69]; // C3 G3 D4 A4
+const CELLO_STRINGS: [i32; 4] = [36, 43, 50, 57]; // C2 G2 D3 A3
+
+/// Detection settings and tuner targets for one instrument
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct InstrumentPreset {
+    instrument: Instrument,
+    /// Lowest frequency to detect (Hz), a little below the lowest open string
+    min_freq: f32,
+    /// Highest frequency to detect (Hz)
+    max_freq: f32,
+    /// Open strings as MIDI notes, lowest first
+    open_strings: [i32; 4],
+    /// Tolerance for "in tune" detection (cents)
+    tune_tolerance: i32,
+    /// Sub-harmonic energy ratio for octave correction. Smaller instruments
+    /// radiate a weaker fundamental, so they need a more sensitive check.
+    subharmonic_ratio: f32,
+    /// RMS volume threshold (small instruments are quieter)
+    volume_threshold: f32,
+    /// Smallest analysis buffer that holds enough periods of the lowest string
+    min_buffer_size: usize,
+}
+
+#[wasm_bindgen]
+impl InstrumentPreset {
+    #[wasm_bindgen(constructor)]
+    pub fn new(instrument: Instrument) -> InstrumentPreset {
+        let violin = |tune_tolerance, subharmonic_ratio, volume_threshold| InstrumentPreset {
+            instrument,
+            min_freq: 180.0,
+            max_freq: 1400.0,
+            open_strings: VIOLIN_STRINGS,
+            tune_tolerance,
+            subharmonic_ratio,
+            volume_threshold,
+            min_buffer_size: 2048,
+        };
+
+        match instrument {
+            Instrument::Violin => violin(10, 0.03, 0.01),
+            Instrument::ViolinThreeQuarter => violin(12, 0.025, 0.009),
+            Instrument::ViolinHalf => violin(12, 0.02, 0.008),
+            Instrument::ViolinQuarter => violin(15, 0.015, 0.007),
+            Instrument::ViolinEighth | Instrument::ViolinSixteenth => violin(15, 0.01, 0.006),
+            Instrument::Viola => InstrumentPreset {
+                instrument,
+                min_freq: 120.0,
+                max_freq: 1200.0,
+                open_strings: VIOLA_STRINGS,
+                tune_tolerance: 10,
+                subharmonic_ratio: 0.025,
+                volume_threshold: 0.01,
+                min_buffer_size: 2048,
+            },
+            Instrument::Cello => InstrumentPreset {
+                instrument,
+                min_freq: 60.0,
+                max_freq: 1000.0,
+                open_strings: CELLO_STRINGS,
+                tune_tolerance: 10,
+                subharmonic_ratio: 0.03,
+                volume_threshold: 0.01,
+                min_buffer_size: 4096,
+            },
+        }
+    }
+
+    /// Look up a preset by name: "violin", "violin-3/4", "violin-1/2",
+    /// "violin-1/4", "violin-1/8", "violin-1/16", "viola" or "cello"
+    #[wasm_bindgen]
+    pub fn from_name(name: &str) -> Option<InstrumentPreset> {
+        let instrument = match name.trim().to_ascii_lowercase().as_str() {
+            "violin" | "violin-4/4" => Instrument::Violin,
+            "violin-3/4" => Instrument::ViolinThreeQuarter,
+            "violin-1/2" => Instrument::ViolinHalf,
+            "violin-1/4" => Instrument::ViolinQuarter,
+            "violin-1/8" => Instrument::ViolinEighth,
+            "violin-1/16" => Instrument::ViolinSixteenth,
+            "viola" => Instrument::Viola,
+            "cello" => Instrument::Cello,
+            _ => return None,
+        };
+        Some(InstrumentPreset::new(instrument))
+    }
+
+    /// Detector configuration for this instrument
+    #[wasm_bindgen]
+    pub fn detector_config(&self) -> DetectorConfig {
+        let mut config = DetectorConfig::new();
+        self.apply_to(&mut config);
+        config
+    }
+
+    /// Open string frequencies (Hz), lowest first
+    #[wasm_bindgen]
+    pub fn open_string_frequencies(&self) -> Vec<f32> {
+        self.open_strings.iter().map(|&m| midi_to_frequency(m as f32)).collect()
+    }
+
+    /// Open string note names (e.g., "G3"), lowest first
+    #[wasm_bindgen]
+    pub fn open_string_notes(&self) -> Vec<String> {
+        self.open_strings.iter().map(|&m| midi_to_note_name(m)).collect()
+    }
+
+    /// Index of the open string closest to `frequency` (tuner target)
+    #[wasm_bindgen]
+    pub fn nearest_string(&self, frequency: f32) -> usize {
+        let midi = frequency_to_midi(frequency.max(1.0));
+        self.open_strings
+            .iter()
+            .enumerate()
+            .min_by(|a, b| {
+                let da = (*a.1 as f32 - midi).abs();
+                let db = (*b.1 as f32 - midi).abs();
+                da.total_cmp(&db)
+            })
+            .map_or(0, |(i, _)| i)
+    }
+
+    /// Cents from open string `index` to `frequency` (positive = sharp)
+    #[wasm_bindgen]
+    pub fn string_cents(&self, index: usize, frequency: f32) -> f32 {
+        let Some(&target) = self.open_strings.get(index) else {
+            return 0.0;
+        };
+        if frequency <= 0.0 {
+            return 0.0;
+        }
+        (frequency_to_midi(frequency) - target as f32) * 100.0
+    }
+
+    // Getters
+    #[wasm_bindgen(getter)]
+    pub fn instrument(&self) -> Instrument { self.instrument }
+
+    #[wasm_bindgen(getter)]
+    pub fn min_freq(&self) -> f32 { self.min_freq }
+
+    #[wasm_bindgen(getter)]
+    pub fn max_freq(&self) -> f32 { self.max_freq }
+
+    #[wasm_bindgen(getter)]
+    pub fn tune_tolerance(&self) -> i32 { self.tune_tolerance }
+
+    #[wasm_bindgen(getter)]
+    pub fn min_buffer_size(&self) -> usize { self.min_buffer_size }
+}
+
+impl InstrumentPreset {
+    /// Overwrite the instrument-specific fields of `config` (range, tune
+    /// tolerance, octave check and volume threshold), keeping the rest
+    pub(crate) fn apply_to(&self, config: &mut DetectorConfig) {
+        config.min_freq = self.min_freq;
+        config.max_freq = self.max_freq;
+        config.tune_tolerance = self.tune_tolerance;
+        config.subharmonic_ratio = self.subharmonic_ratio;
+        config.volume_threshold = self.volume_threshold;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PitchDetector;
+
+    fn bowed(frequency: f32, len: usize, sample_rate: f32) -> Vec<f32> {
+        (0..len)
+            .map(|i| {
+                let phase = 2.0 * std::f32::consts::PI * frequency * i as f32 / sample_rate;
+                0.3 * phase.sin() + 0.2 * (2.0 * phase).sin() + 0.1 * (3.0 * phase).sin()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_open_strings() {
+        let viola = InstrumentPreset::from_name("viola").unwrap();
+        assert_eq!(viola.open_string_notes(), vec!["C3", "G3", "D4", "A4"]);
+        let cello = InstrumentPreset::new(Instrument::Cello);
+        assert!((cello.open_string_frequencies()[0] - 65.41).abs() < 0.01);
+        assert!(InstrumentPreset::from_name("banjo").is_none());
+    }
+
+    #[test]
+    fn test_tuner_targets() {
+        let violin = InstrumentPreset::new(Instrument::Violin);
+        assert_eq!(violin.nearest_string(445.0), 2);
+        assert!((violin.string_cents(2, 445.0) - 19.56).abs() < 0.1);
+        assert_eq!(violin.nearest_string(190.0), 0);
+    }
+
+    #[test]
+    fn test_presets_detect_lowest_string() {
+        for (instrument, note, frequency) in [
+            (Instrument::Viola, "C3", 130.81),
+            (Instrument::Cello, "C2", 65.41),
+            (Instrument::ViolinQuarter, "G3", 196.0),
+        ] {
+            let preset = InstrumentPreset::new(instrument);
+            let size = preset.min_buffer_size();
+            let mut detector = PitchDetector::with_config(48000.0, size, &preset.detector_config());
+            let result = detector.detect(&bowed(frequency, size, 48000.0));
+            assert_eq!(result.note(), note, "{instrument:?}");
+        }
+    }
+}
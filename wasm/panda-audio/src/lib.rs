@@ -8,9 +8,111 @@
 //! - RMS volume calculation
 //! - Note classification with cents deviation
 //! - Optimized for 48kHz sample rate
+//! - Sample-accurate metronome click generation
+//! - Round-trip audio latency calibration
+//! - Offline analysis of complete recordings
+//! - Spectrogram and chroma features for visualisation
+//! - Harmonic-aware octave and fifth error correction
+//! - Selectable pitch smoothing (median, one-euro, note-aware)
+//! - Runtime-reconfigurable detector settings
+//! - Instrument presets for violin (all sizes), viola and cello
+//! - Calibrated voicing probability per frame
+//! - Compact in-memory pitch-contour recording
+//! - Allocation-free binary result ring for worklet output
+//! - Ear-training question generation, synthesis and answer checking
+//! - Key estimation and scale checking (major and minor forms)
+//! - Pizzicato/arco and staccato/legato articulation classification
+//! - NaN/Inf input sanitising with clipping and input-health reporting
+//! - Microphone sound-check diagnostics with pass/warn/fail verdicts
+//! - Configurable input pre-processing (DC blocker, high-pass, hum notch, pre-emphasis)
+//! - Echo-game rhythm and melody prompts by difficulty, with grading references
+//! - Per-take echo recording metadata (start time, sample rate, levels, overflow)
+//! - Interleaved/planar multi-channel input with live-channel selection or downmix
+//!
+//! Whole-recording analysis and the game modules are behind the default
+//! `offline` feature. The app's bindings are built without it, which keeps
+//! the worklet's .wasm small:
+//!
+//! ```text
+//! RUSTFLAGS="-C target-feature=-reference-types" \
+//!     cargo build --release --target wasm32-unknown-unknown -p panda-audio --no-default-features
+//! wasm-bindgen --target web --no-typescript --out-dir ../src/wasm target/wasm32-unknown-unknown/release/panda_audio.wasm
+//! wasm-opt -Oz --enable-bulk-memory --enable-nontrapping-float-to-int --enable-sign-ext \
+//!     ../src/wasm/panda_audio_bg.wasm -o ../src/wasm/panda_audio_bg.wasm
+//! ```
+//!
+//! Reference types are off because wasm-opt 116 cannot parse the
+//! `table.fill` that wasm-bindgen emits for its externref table.
 
 use wasm_bindgen::prelude::*;
 
+mod metronome;
+mod calibration;
+#[cfg(feature = "offline")]
+mod analysis;
+mod fft;
+#[cfg(feature = "offline")]
+mod spectrogram;
+mod harmonics;
+mod smoothing;
+mod config;
+mod instruments;
+mod voicing;
+mod contour;
+mod protocol;
+#[cfg(feature = "offline")]
+mod ear_training;
+#[cfg(feature = "offline")]
+mod scales;
+#[cfg(feature = "offline")]
+mod articulation;
+mod health;
+#[cfg(feature = "offline")]
+mod diagnostics;
+mod preprocess;
+#[cfg(feature = "offline")]
+mod echo_prompts;
+mod echo_take;
+mod channels;
+#[cfg(test)]
+pub(crate) mod test_signals;
+
+pub use metronome::{ClickKind, Metronome};
+pub use calibration::{LatencyCalibrator, LatencyResult};
+#[cfg(feature = "offline")]
+pub use analysis::{
+    analyze_recording, analyze_recording_with, analyze_recording_with_preprocessing, AnalysisReport, NoteEvent,
+};
+#[cfg(feature = "offline")]
+pub use spectrogram::{compute_chroma, compute_spectrogram, Chromagram, Spectrogram};
+
+pub use smoothing::SmoothingMode;
+pub use config::DetectorConfig;
+pub use instruments::{Instrument, InstrumentPreset};
+pub use contour::ContourRecorder;
+pub use protocol::{ring_byte_length, ring_protocol_version, FrameRecord, ResultRingReader, ResultRingWriter};
+#[cfg(feature = "offline")]
+pub use ear_training::{Difficulty, EarAnswer, EarQuestion, EarQuestionKind, EarTrainer};
+#[cfg(feature = "offline")]
+pub use scales::{check_scale, estimate_key, estimate_key_from_chroma, identify_scale, KeyEstimate, ScaleCheck, ScaleType};
+#[cfg(feature = "offline")]
+pub use articulation::{classify_articulation, Articulation, ArticulatedNote, ArticulationReport};
+pub use health::InputHealth;
+#[cfg(feature = "offline")]
+pub use diagnostics::{diagnose_input, MicDiagnostics, MicReport, Verdict};
+pub use preprocess::{preprocess_recording, PreprocessConfig};
+#[cfg(feature = "offline")]
+pub use echo_prompts::{EchoPrompt, EchoPromptGenerator, EchoPromptKind};
+pub use echo_take::EchoTakeMetadata;
+pub use channels::{ChannelLayout, ChannelMode, ChannelSelector};
+
+use harmonics::HarmonicCorrector;
+use health::{sanitize_into, sanitize_sample, InputScan};
+use preprocess::Preprocessor;
+use echo_take::TakeStats;
+use smoothing::PitchSmoother;
+use voicing::VoicingModel;
+
 // Initialize panic hook for better error messages
 #[wasm_bindgen(start)]
 pub fn init() {
@@ -32,10 +134,21 @@ pub struct PitchResult {
     cents: i32,
     /// RMS volume level (0.0 to 1.0)
     volume: f32,
-    /// Confidence of detection (0.0 to 1.0)
+    /// Confidence of detection: the raw NSDF peak (0.0 to 1.0)
     confidence: f32,
+    /// Probability that a note is sounding (0.0 to 1.0); 0.5 is the
+    /// recommended threshold regardless of buffer size
+    voicing_probability: f32,
     /// Whether the pitch is considered "in tune"
     in_tune: bool,
+    /// Frequency reported by autocorrelation before harmonic correction (Hz)
+    raw_frequency: f32,
+    /// Whether an octave or fifth correction was applied
+    corrected: bool,
+    /// Percentage of samples in the buffer at full scale (0 to 100)
+    clipping_percent: f32,
+    /// Whether the input was clean, clipping or contained invalid samples
+    input_health: InputHealth,
 }
 
 #[wasm_bindgen]
@@ -65,28 +178,101 @@ impl PitchResult {
         self.confidence
     }
 
+    #[wasm_bindgen(getter)]
+    pub fn voicing_probability(&self) -> f32 {
+        self.voicing_probability
+    }
+
     #[wasm_bindgen(getter)]
     pub fn in_tune(&self) -> bool {
         self.in_tune
     }
+
+    #[wasm_bindgen(getter)]
+    pub fn raw_frequency(&self) -> f32 {
+        self.raw_frequency
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn corrected(&self) -> bool {
+        self.corrected
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn clipping_percent(&self) -> f32 {
+        self.clipping_percent
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn input_health(&self) -> InputHealth {
+        self.input_health
+    }
+}
+
+/// Lowest and highest sample rates accepted by the public API
+const MIN_SAMPLE_RATE: f32 = 1000.0;
+const MAX_SAMPLE_RATE: f32 = 384_000.0;
+/// Used when a caller passes a NaN sample rate
+const FALLBACK_SAMPLE_RATE: f32 = 48000.0;
+
+/// Clamp a caller-supplied sample rate into the supported range, so lag,
+/// window and buffer sizes derived from it stay finite and allocatable
+pub(crate) fn clamp_sample_rate(sample_rate: f32) -> f32 {
+    if sample_rate.is_nan() {
+        FALLBACK_SAMPLE_RATE
+    } else {
+        sample_rate.clamp(MIN_SAMPLE_RATE, MAX_SAMPLE_RATE)
+    }
 }
 
 fn compute_rms(buffer: &[f32]) -> f32 {
     if buffer.is_empty() {
         return 0.0;
     }
-    let sum: f32 = buffer.iter().map(|&x| x * x).sum();
+    let sum: f32 = buffer.iter().map(|&x| sanitize_sample(x).powi(2)).sum();
     (sum / buffer.len() as f32).sqrt()
 }
 
-fn empty_pitch_result(volume: f32, confidence: f32) -> PitchResult {
+/// Floor for levels in dBFS, reported for digital silence
+#[cfg(feature = "offline")]
+pub(crate) const SILENCE_DB: f32 = -120.0;
+
+/// Level of `amplitude` (full scale = 1.0) in dBFS, floored at `SILENCE_DB`
+#[cfg(feature = "offline")]
+pub(crate) fn amplitude_to_db(amplitude: f32) -> f32 {
+    if amplitude > 0.0 { (20.0 * amplitude.log10()).max(SILENCE_DB) } else { SILENCE_DB }
+}
+
+/// Fractional MIDI note number for a frequency (A4 = 440 Hz = 69)
+pub(crate) fn frequency_to_midi(frequency: f32) -> f32 {
+    69.0 + 12.0 * (frequency / 440.0).log2()
+}
+
+/// Frequency in Hz of a (possibly fractional) MIDI note number
+pub(crate) fn midi_to_frequency(midi: f32) -> f32 {
+    440.0 * 2.0f32.powf((midi - 69.0) / 12.0)
+}
+
+/// Note name for a MIDI note number (e.g., 69 -> "A4")
+pub(crate) fn midi_to_note_name(midi: i32) -> String {
+    let note_idx = midi.rem_euclid(12) as usize;
+    let octave = midi.div_euclid(12) - 1;
+    format!("{}{}", NOTE_NAMES[note_idx], octave)
+}
+
+fn empty_pitch_result(volume: f32, confidence: f32, voicing_probability: f32) -> PitchResult {
     PitchResult {
         frequency: 0.0,
         note: String::new(),
         cents: 0,
         volume,
         confidence,
+        voicing_probability,
         in_tune: false,
+        raw_frequency: 0.0,
+        corrected: false,
+        clipping_percent: 0.0,
+        input_health: InputHealth::Good,
     }
 }
 
@@ -111,6 +297,9 @@ fn compute_nsdf_value(samples: &[f32], tau: usize, limit: usize) -> Option<f32>
     }
 }
 
+/// Decimation factor of the coarse pitch search (and the voicing spectrum)
+const DOWNSAMPLE_STRIDE: usize = 4;
+
 /// Pitch detector using autocorrelation algorithm
 #[wasm_bindgen]
 pub struct PitchDetector {
@@ -121,13 +310,28 @@ pub struct PitchDetector {
     max_freq: f32,
     /// Threshold for volume detection
     volume_threshold: f32,
+    /// NSDF peak required to accept a pitch
+    confidence_threshold: f32,
+    /// NSDF level for the coarse (downsampled) peak search
+    coarse_threshold: f32,
     /// Tolerance for "in tune" detection (cents)
     tune_tolerance: i32,
-    /// Previous valid frequency for smoothing
-    prev_frequency: f32,
+    /// Pitch smoothing state and parameters
+    smoother: PitchSmoother,
+    /// Whether octave/fifth errors are corrected
+    harmonic_correction: bool,
+    /// Pitch history for octave/fifth error correction
+    corrector: HarmonicCorrector,
+    /// Voicing probability model
+    voicing: VoicingModel,
     /// Reusable buffers to avoid allocations
     downsampled: Vec<f32>,
     nsdf: Vec<f32>,
+    /// Copy of the input with invalid samples replaced, when needed
+    sanitized: Vec<f32>,
+    /// Input filtering ahead of detection
+    preprocessor: Preprocessor,
+    filtered: Vec<f32>,
 }
 
 #[wasm_bindgen]
@@ -139,9 +343,10 @@ impl PitchDetector {
     /// * `buffer_size` - FFT buffer size (typically 2048 or 4096)
     #[wasm_bindgen(constructor)]
     pub fn new(sample_rate: f32, buffer_size: usize) -> PitchDetector {
+        let sample_rate = clamp_sample_rate(sample_rate);
         // Prepare reusable buffers
         // Downsample factor 4 means buffer is 1/4 size
-        let downsampled_size = buffer_size / 4 + 1;
+        let downsampled_size = buffer_size / DOWNSAMPLE_STRIDE + 1;
         // Max lag is roughly buffer_size / 2 for safe detection
         let nsdf_size = buffer_size; 
 
@@ -150,45 +355,170 @@ impl PitchDetector {
             min_freq: 180.0,
             max_freq: 1400.0,
             volume_threshold: 0.01,
+            confidence_threshold: 0.75,
+            coarse_threshold: 0.6,
             tune_tolerance: 10,
-            prev_frequency: 0.0,
+            smoother: PitchSmoother::new(),
+            harmonic_correction: true,
+            corrector: HarmonicCorrector::new(),
+            voicing: VoicingModel::new(buffer_size / DOWNSAMPLE_STRIDE),
             downsampled: vec![0.0; downsampled_size],
             nsdf: vec![0.0; nsdf_size],
+            sanitized: Vec::with_capacity(buffer_size),
+            preprocessor: Preprocessor::new(&PreprocessConfig::new(), sample_rate),
+            filtered: Vec::with_capacity(buffer_size),
+        }
+    }
+
+    /// Create a pitch detector with the given configuration
+    #[wasm_bindgen]
+    pub fn with_config(sample_rate: f32, buffer_size: usize, config: &DetectorConfig) -> PitchDetector {
+        let mut detector = PitchDetector::new(sample_rate, buffer_size);
+        detector.apply_config(config);
+        detector
+    }
+
+    /// Apply a configuration at runtime. Buffers of any size may be passed to
+    /// `detect` afterwards; internal scratch space grows as needed.
+    #[wasm_bindgen]
+    pub fn apply_config(&mut self, config: &DetectorConfig) {
+        let config = config.validated();
+        self.min_freq = config.min_freq;
+        self.max_freq = config.max_freq;
+        self.volume_threshold = config.volume_threshold;
+        self.confidence_threshold = config.confidence_threshold;
+        self.coarse_threshold = config.coarse_threshold;
+        self.tune_tolerance = config.tune_tolerance;
+        self.harmonic_correction = config.harmonic_correction;
+        self.corrector.subharmonic_ratio = config.subharmonic_ratio;
+        self.smoother.mode = config.smoothing;
+        self.smoother.alpha = config.smoothing_alpha;
+        self.smoother.median_window = config.median_window;
+        self.smoother.min_cutoff = config.one_euro_min_cutoff;
+        self.smoother.beta = config.one_euro_beta;
+        self.smoother.note_change_cents = config.note_change_cents;
+        self.reset();
+    }
+
+    /// Current configuration
+    #[wasm_bindgen]
+    pub fn config(&self) -> DetectorConfig {
+        DetectorConfig {
+            min_freq: self.min_freq,
+            max_freq: self.max_freq,
+            volume_threshold: self.volume_threshold,
+            confidence_threshold: self.confidence_threshold,
+            coarse_threshold: self.coarse_threshold,
+            tune_tolerance: self.tune_tolerance,
+            harmonic_correction: self.harmonic_correction,
+            subharmonic_ratio: self.corrector.subharmonic_ratio,
+            smoothing: self.smoother.mode,
+            smoothing_alpha: self.smoother.alpha,
+            median_window: self.smoother.median_window,
+            one_euro_min_cutoff: self.smoother.min_cutoff,
+            one_euro_beta: self.smoother.beta,
+            note_change_cents: self.smoother.note_change_cents,
         }
     }
 
+    /// Apply the range, tune tolerance, octave check and volume threshold of
+    /// an instrument preset. Smoothing and the other settings are kept.
+    #[wasm_bindgen]
+    pub fn set_instrument(&mut self, instrument: Instrument) {
+        let mut config = self.config();
+        InstrumentPreset::new(instrument).apply_to(&mut config);
+        self.apply_config(&config);
+    }
+
+    /// Forget smoothing, pitch history and filter state (call between exercises)
+    #[wasm_bindgen]
+    pub fn reset(&mut self) {
+        self.smoother.reset();
+        self.corrector.reset();
+        self.preprocessor.reset();
+    }
+
+    /// Replace the input pre-processing chain. Filters keep state between
+    /// calls to `detect`, so buffers must be consecutive pieces of one stream.
+    #[wasm_bindgen]
+    pub fn set_preprocessing(&mut self, config: &PreprocessConfig) {
+        self.preprocessor = Preprocessor::new(config, self.sample_rate);
+    }
+
+    /// Current input pre-processing settings
+    #[wasm_bindgen]
+    pub fn preprocessing(&self) -> PreprocessConfig {
+        self.preprocessor.config().clone()
+    }
+
     /// Analyze audio buffer and detect pitch
     ///
     /// # Arguments
     /// * `buffer` - Audio samples as f32 array
     ///
     /// # Returns
-    /// PitchResult with detected frequency, note, cents, etc.
+    /// PitchResult with detected frequency, note, cents, etc. NaN/infinite
+    /// samples are treated as silence and reported through `input_health`;
+    /// the pre-processing chain runs before detection.
     #[wasm_bindgen]
     pub fn detect(&mut self, buffer: &[f32]) -> PitchResult {
+        let scan = InputScan::of(buffer);
+        let mut sanitized = std::mem::take(&mut self.sanitized);
+        let mut filtered = std::mem::take(&mut self.filtered);
+        let mut samples = buffer;
+        if scan.needs_sanitizing() {
+            sanitize_into(buffer, &mut sanitized);
+            samples = &sanitized;
+        }
+        if self.preprocessor.is_active() {
+            self.preprocessor.process(samples, &mut filtered);
+            samples = &filtered;
+        }
+        let mut result = self.detect_clean(samples);
+        self.sanitized = sanitized;
+        self.filtered = filtered;
+        result.clipping_percent = scan.clipping_percent();
+        result.input_health = scan.health();
+        result
+    }
+
+    /// Detection on a buffer of finite samples within full scale
+    fn detect_clean(&mut self, buffer: &[f32]) -> PitchResult {
         // Calculate RMS volume (O(N))
         let volume = compute_rms(buffer);
 
         // If volume is too low, return no pitch
         if volume < self.volume_threshold {
-            return empty_pitch_result(volume, 0.0);
+            return self.unvoiced_result(volume, 0.0, 0.0);
         }
 
         // Perform optimized autocorrelation pitch detection
         let (frequency, confidence) = self.autocorrelate(buffer);
+        // The voicing spectrum comes from the decimated copy autocorrelate made
+        let voicing_probability = self.voicing.probability(
+            &self.downsampled[..buffer.len() / DOWNSAMPLE_STRIDE],
+            self.sample_rate / DOWNSAMPLE_STRIDE as f32,
+            volume,
+            confidence,
+        );
 
         // If no valid pitch found
-        if frequency < self.min_freq || frequency > self.max_freq || confidence < 0.75 { // Slightly lower threshold for downsampled
-            return empty_pitch_result(volume, confidence);
+        if frequency < self.min_freq || frequency > self.max_freq || confidence < self.confidence_threshold {
+            return self.unvoiced_result(volume, confidence, voicing_probability);
         }
 
-        // Apply smoothing
-        let smoothed_freq = if self.prev_frequency > 0.0 {
-            frequency * 0.7 + self.prev_frequency * 0.3
+        // Veto octave and fifth errors
+        let raw_frequency = frequency;
+        let frequency = if self.harmonic_correction {
+            self.corrector.correct(buffer, frequency, self.sample_rate, self.min_freq, self.max_freq)
         } else {
             frequency
         };
-        self.prev_frequency = smoothed_freq;
+        let corrected = frequency != raw_frequency;
+
+        // Apply smoothing
+        let frame_seconds = buffer.len() as f32 / self.sample_rate;
+        let smoothed_freq = self.smoother.process(frequency, frame_seconds);
 
         // Convert frequency to note name and cents
         let (note, cents) = self.frequency_to_note(smoothed_freq);
@@ -200,16 +530,28 @@ impl PitchDetector {
             cents,
             volume,
             confidence,
+            voicing_probability,
             in_tune,
+            raw_frequency,
+            corrected,
+            clipping_percent: 0.0,
+            input_health: InputHealth::Good,
         }
     }
 
+    /// Result for a frame without a usable pitch; smoothing restarts afterwards
+    fn unvoiced_result(&mut self, volume: f32, confidence: f32, voicing_probability: f32) -> PitchResult {
+        self.corrector.mark_unvoiced();
+        self.smoother.reset();
+        empty_pitch_result(volume, confidence, voicing_probability)
+    }
+
     /// Optimized Autocorrelation using Coarse-to-Fine Strategy
     /// 1. fast scan on downsampled data.
     /// 2. Precise refinement on original data.
     fn autocorrelate(&mut self, buffer: &[f32]) -> (f32, f32) {
         let n = buffer.len();
-        let stride = 4;
+        let stride = DOWNSAMPLE_STRIDE;
         
         // 1. Better Downsampling (Average 4 samples)
         let ds_len = n / stride;
@@ -251,7 +593,7 @@ impl PitchDetector {
         }
 
         // 3. Peak Picking (Coarse)
-        let threshold = 0.6; // Lower threshold for downsampled data
+        let threshold = self.coarse_threshold; // Lower threshold for downsampled data
         let mut best_lag_ds = 0;
         let mut best_val_ds = 0.0f32;
         let mut in_peak = false;
@@ -265,6 +607,14 @@ impl PitchDetector {
                  }
                  in_peak = true;
             } else if in_peak {
+                 // A region peaking on the shortest lag is the tail of the
+                 // zero-lag lobe (low notes), not a period: keep looking
+                 if best_lag_ds == ds_min_lag {
+                     best_lag_ds = 0;
+                     best_val_ds = 0.0;
+                     in_peak = false;
+                     continue;
+                 }
                  break; // Found the first strong peak
             }
         }
@@ -317,7 +667,7 @@ impl PitchDetector {
 
     /// Parabolic interpolation for sub-sample accuracy
     fn parabolic_interpolation(&self, data: &[f32], peak_idx: usize) -> f32 {
-        if peak_idx == 0 || peak_idx >= data.len() - 1 {
+        if peak_idx == 0 || peak_idx + 1 >= data.len() {
             return peak_idx as f32;
         }
 
@@ -351,7 +701,7 @@ impl PitchDetector {
 
         // Calculate note index (A is index 9)
         let note_idx = ((note_num + 9) % 12 + 12) % 12;
-        let octave = 4 + (note_num + 9) / 12;
+        let octave = 4 + (note_num + 9).div_euclid(12);
 
         let note_name = format!("{}{}", NOTE_NAMES[note_idx as usize], octave);
 
@@ -369,11 +719,52 @@ impl PitchDetector {
     pub fn set_tune_tolerance(&mut self, cents: i32) {
         self.tune_tolerance = cents.clamp(1, 50);
     }
+
+    /// Enable or disable octave/fifth error correction
+    #[wasm_bindgen]
+    pub fn set_harmonic_correction(&mut self, enabled: bool) {
+        self.harmonic_correction = enabled;
+        self.corrector.reset();
+    }
+
+    /// Select the pitch smoothing strategy
+    #[wasm_bindgen]
+    pub fn set_smoothing(&mut self, mode: SmoothingMode) {
+        self.smoother.mode = mode;
+        self.smoother.reset();
+    }
+
+    /// Set the weight of the newest frame for exponential and note-aware
+    /// smoothing (0.05 = very smooth, 1.0 = no smoothing)
+    #[wasm_bindgen]
+    pub fn set_smoothing_alpha(&mut self, alpha: f32) {
+        self.smoother.alpha = alpha.clamp(0.05, 1.0);
+    }
+
+    /// Set the median filter length in frames (rounded up to odd, 3-15)
+    #[wasm_bindgen]
+    pub fn set_median_window(&mut self, frames: usize) {
+        self.smoother.median_window = frames.clamp(3, 15) | 1;
+    }
+
+    /// Set one-euro parameters: minimum cutoff (Hz) and speed coefficient
+    #[wasm_bindgen]
+    pub fn set_one_euro(&mut self, min_cutoff: f32, beta: f32) {
+        self.smoother.min_cutoff = min_cutoff.clamp(0.01, 20.0);
+        self.smoother.beta = beta.clamp(0.0, 10.0);
+    }
+
+    /// Set the jump (cents) that note-aware smoothing treats as a new note
+    #[wasm_bindgen]
+    pub fn set_note_change_cents(&mut self, cents: f32) {
+        self.smoother.note_change_cents = cents.clamp(10.0, 200.0);
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_signals::{sine, Noise};
 
     #[test]
     fn test_frequency_to_note() {
@@ -385,6 +776,9 @@ mod tests {
 
         let (note, _) = detector.frequency_to_note(261.63);
         assert_eq!(note, "C4");
+
+        let (note, _) = detector.frequency_to_note(196.0);
+        assert_eq!(note, "G3");
     }
 
     #[test]
@@ -398,6 +792,147 @@ mod tests {
         assert!((compute_rms(&constant) - 0.5).abs() < 0.01);
     }
 
+    #[test]
+    fn test_voicing_probability_is_consistent() {
+        let sample_rate = 48000.0;
+        let mut rng = Noise::new(99);
+        for size in [1024, 2048, 4096] {
+            let tone = sine(330.0, 0.2, size, sample_rate);
+            let noise: Vec<f32> = (0..size).map(|_| rng.next() * 0.25).collect();
+
+            let mut detector = PitchDetector::new(sample_rate, size);
+            assert!(detector.detect(&tone).voicing_probability() > 0.9, "tone at {size}");
+            assert!(detector.detect(&noise).voicing_probability() < 0.2, "noise at {size}");
+            assert_eq!(detector.detect(&vec![0.0; size]).voicing_probability(), 0.0);
+        }
+    }
+
+    #[test]
+    fn test_glitched_and_clipped_input() {
+        let sample_rate = 48000.0;
+        let tone: Vec<f32> = (0..2048)
+            .map(|i| 0.4 * (2.0 * std::f32::consts::PI * 440.0 * i as f32 / sample_rate).sin())
+            .collect();
+        let mut detector = PitchDetector::new(sample_rate, 2048);
+        detector.set_smoothing(SmoothingMode::None);
+
+        let clean = detector.detect(&tone);
+        assert_eq!(clean.input_health(), InputHealth::Good);
+        assert_eq!(clean.clipping_percent(), 0.0);
+
+        // A few dropped-out samples still give A4 with finite values
+        let mut glitched = tone.clone();
+        for i in [100, 900, 1500] {
+            glitched[i] = f32::NAN;
+        }
+        glitched[1200] = f32::NEG_INFINITY;
+        let result = detector.detect(&glitched);
+        assert_eq!(result.input_health(), InputHealth::Invalid);
+        assert_eq!(result.note(), "A4");
+        assert!(result.volume().is_finite() && result.confidence().is_finite());
+
+        // Overdriven input is still detected but flagged
+        let overdriven: Vec<f32> = tone.iter().map(|s| (s * 5.0).clamp(-1.0, 1.0)).collect();
+        let result = detector.detect(&overdriven);
+        assert_eq!(result.input_health(), InputHealth::Clipping);
+        assert!(result.clipping_percent() > 50.0);
+        assert_eq!(result.note(), "A4");
+
+        // All-NaN buffers read as silence (apart from the filters' decay)
+        let result = detector.detect(&vec![f32::NAN; 2048]);
+        assert_eq!(result.frequency(), 0.0);
+        assert!(result.volume() < 0.01, "{}", result.volume());
+        assert_eq!(result.input_health(), InputHealth::Invalid);
+    }
+
+    #[test]
+    fn test_apply_config_and_reset() {
+        let sample_rate = 48000.0;
+        let tone = |frequency: f32| -> Vec<f32> {
+            (0..4096)
+                .map(|i| 0.3 * (2.0 * std::f32::consts::PI * frequency * i as f32 / sample_rate).sin())
+                .collect()
+        };
+
+        let mut config = DetectorConfig::new();
+        config.min_freq = 300.0;
+        config.smoothing = SmoothingMode::Exponential;
+        let mut detector = PitchDetector::new(sample_rate, 2048);
+        detector.apply_config(&config);
+        assert_eq!(detector.config(), config);
+
+        // A3 is now out of range, and a larger buffer works without re-creation
+        assert_eq!(detector.detect(&tone(220.0)).frequency(), 0.0);
+        assert_eq!(detector.detect(&tone(440.0)).note(), "A4");
+
+        // Without reset the next note is blended with the previous one
+        let blended = detector.detect(&tone(523.25)).frequency();
+        assert!(blended < 510.0);
+        detector.reset();
+        let fresh = detector.detect(&tone(523.25)).frequency();
+        assert!((fresh - 523.25).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_set_instrument_keeps_other_settings() {
+        let mut detector = PitchDetector::new(48000.0, 4096);
+        detector.set_smoothing(SmoothingMode::Median);
+        detector.set_median_window(7);
+        detector.set_one_euro(2.0, 0.5);
+        detector.set_note_change_cents(80.0);
+        detector.set_harmonic_correction(false);
+        let before = detector.config();
+
+        detector.set_instrument(Instrument::Cello);
+        let mut expected = before;
+        InstrumentPreset::new(Instrument::Cello).apply_to(&mut expected);
+        assert_eq!(detector.config(), expected.validated());
+        assert_eq!((detector.config().min_freq, detector.config().max_freq), (60.0, 1000.0));
+        assert_eq!(detector.config().smoothing, SmoothingMode::Median);
+        assert_eq!(detector.config().median_window, 7);
+    }
+
+    #[test]
+    fn test_g_string_octave_error_is_corrected() {
+        // G3 with a weak fundamental and a dominant second harmonic
+        let sample_rate = 48000.0;
+        let buffer: Vec<f32> = (0..2048)
+            .map(|i| {
+                let phase = 2.0 * std::f32::consts::PI * 196.0 * i as f32 / sample_rate;
+                0.03 * phase.sin() + 0.5 * (2.0 * phase).sin() + 0.1 * (3.0 * phase).sin()
+            })
+            .collect();
+
+        let mut detector = PitchDetector::new(sample_rate, 2048);
+        let result = detector.detect(&buffer);
+        assert_eq!(result.note(), "G3");
+        assert!(result.corrected());
+        assert_eq!(result.raw_frequency().round(), 392.0);
+
+        let mut uncorrected = PitchDetector::new(sample_rate, 2048);
+        uncorrected.set_smoothing(SmoothingMode::None);
+        uncorrected.set_harmonic_correction(false);
+        assert_eq!(uncorrected.detect(&buffer).note(), "G4");
+    }
+
+    #[test]
+    fn test_low_note_is_not_mistaken_for_zero_lag_lobe() {
+        // With a 60 Hz floor the shortest searched lag sits well inside the
+        // zero-lag lobe of a C2, which must not be reported as the period
+        let sample_rate = 48000.0;
+        let buffer: Vec<f32> = (0..4096)
+            .map(|i| 0.4 * (2.0 * std::f32::consts::PI * 65.41 * i as f32 / sample_rate).sin())
+            .collect();
+        let mut config = DetectorConfig::new();
+        config.min_freq = 60.0;
+        config.max_freq = 1000.0;
+        config.smoothing = SmoothingMode::None;
+        let mut detector = PitchDetector::with_config(sample_rate, 4096, &config);
+        let result = detector.detect(&buffer);
+        assert_eq!(result.note(), "C2");
+        assert!((result.frequency() - 65.41).abs() < 1.0, "{}", result.frequency());
+    }
+
 }
 
 /// Echo recording and evaluation buffer
@@ -413,6 +948,10 @@ pub struct EchoBuffer {
     size: usize,
     /// Indicates if we are actively recording
     is_recording: bool,
+    /// Rate the samples were captured at, reported in the take metadata
+    sample_rate: f32,
+    /// Start time, levels and overflow of the current take
+    stats: TakeStats,
 }
 
 #[wasm_bindgen]
@@ -425,6 +964,8 @@ impl EchoBuffer {
             capacity,
             size: 0,
             is_recording: false,
+            sample_rate: FALLBACK_SAMPLE_RATE,
+            stats: TakeStats::default(),
         }
     }
 
@@ -434,6 +975,7 @@ impl EchoBuffer {
         self.write_idx = 0;
         self.size = 0;
         self.is_recording = false;
+        self.stats = TakeStats::default();
         // Optimization: No need to zero the vector memory, we just overwrite it
     }
 
@@ -442,28 +984,46 @@ impl EchoBuffer {
         self.is_recording = state;
     }
 
+    /// Sample rate of the pushed audio (defaults to 48kHz)
+    #[wasm_bindgen]
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.sample_rate = clamp_sample_rate(sample_rate);
+    }
+
+    /// Reset and start a new take captured at `timestamp_ms`
+    /// (e.g. the worklet's `currentTime * 1000`)
+    #[wasm_bindgen]
+    pub fn start_recording(&mut self, timestamp_ms: f64) {
+        self.reset();
+        self.stats = TakeStats::start(timestamp_ms);
+        self.is_recording = true;
+    }
+
+    /// Start time, sample rate, length, levels and overflow of the current take
+    #[wasm_bindgen]
+    pub fn metadata(&self) -> EchoTakeMetadata {
+        self.stats.metadata(self.sample_rate, self.size, self.capacity)
+    }
+
     /// Push an array of audio samples into the linear buffer.
     /// Returns true if the buffer hit capacity during this write.
     #[wasm_bindgen]
     pub fn push_chunk(&mut self, samples: &[f32]) -> bool {
-        if !self.is_recording || self.size >= self.capacity {
+        if !self.is_recording {
             return false;
         }
-
-        let mut hit_capacity = false;
-        
-        for &sample in samples {
-            if self.size < self.capacity {
-                self.buffer[self.write_idx] = sample;
-                self.write_idx += 1;
-                self.size += 1;
-            } else {
-                hit_capacity = true;
-                break;
-            }
+        if self.size >= self.capacity {
+            self.stats.record(&[], samples.len());
+            return false;
         }
-        
-        hit_capacity
+
+        let stored = samples.len().min(self.capacity - self.size);
+        self.buffer[self.write_idx..self.write_idx + stored].copy_from_slice(&samples[..stored]);
+        self.write_idx += stored;
+        self.size += stored;
+        self.stats.record(&samples[..stored], samples.len() - stored);
+
+        stored < samples.len()
     }
 
     /// Simplified envelope extractor. 
@@ -480,7 +1040,7 @@ impl EchoBuffer {
             return envelope; // Too small
         }
 
-        for i in 0..target_bins {
+        for (i, slot) in envelope.iter_mut().enumerate() {
             let start = i * samples_per_bin;
             let end = (start + samples_per_bin).min(self.size);
             
@@ -488,7 +1048,7 @@ impl EchoBuffer {
             let rms = compute_rms(&self.buffer[start..end]);
             
             // Normalize slightly (magic constant for standard mic input)
-            envelope[i] = (rms * 10.0).clamp(0.0, 1.0);
+            *slot = (rms * 10.0).clamp(0.0, 1.0);
         }
 
         envelope
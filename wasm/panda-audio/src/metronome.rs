@@ -0,0 +1,351 @@
+use wasm_bindgen::prelude::*;
+use crate::clamp_sample_rate;
+
+// ============================================================================
+// Metronome Click Generator
+// ============================================================================
+
+/// Click tone frequencies (Hz) per click kind
+const ACCENT_FREQ: f32 = 1760.0;
+const BEAT_FREQ: f32 = 1320.0;
+const SUBDIVISION_FREQ: f32 = 990.0;
+const COUNT_IN_FREQ: f32 = 2093.0;
+
+/// Click length and decay time constant (seconds)
+const CLICK_SECONDS: f32 = 0.035;
+const CLICK_DECAY_SECONDS: f32 = 0.008;
+
+/// Supported tempo range (BPM)
+const MIN_BPM: f32 = 20.0;
+const MAX_BPM: f32 = 300.0;
+/// Tempo used in place of a non-finite BPM
+const DEFAULT_BPM: f32 = 120.0;
+
+/// Kind of click scheduled on a metronome tick
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClickKind {
+    /// Tick with no click (subdivisions during the count-in)
+    Silent,
+    /// First beat of a bar
+    Accent,
+    /// Any other beat of a bar
+    Beat,
+    /// Subdivision between beats
+    Subdivision,
+    /// Beat of a count-in bar
+    CountIn,
+}
+
+/// Sample-accurate metronome that renders clicks for any tempo and meter.
+///
+/// Sample position 0 is the first count-in beat (or bar 1 when there is no
+/// count-in). Rendering is stateless with respect to position, so blocks can
+/// be requested in any order and always line up on the same grid.
+#[wasm_bindgen]
+pub struct Metronome {
+    sample_rate: f32,
+    /// Tempo in beats per minute (the beat is the time signature's beat unit)
+    bpm: f32,
+    /// Time signature numerator
+    beats_per_bar: u32,
+    /// Time signature denominator
+    beat_unit: u32,
+    /// Clicks per beat (1 = beats only, 2 = eighths, 3 = triplets, ...)
+    subdivision: u32,
+    /// Number of count-in bars before bar 1
+    count_in_bars: u32,
+    /// Output gain (0.0 to 1.0)
+    volume: f32,
+    /// Pre-rendered click waveforms
+    accent_click: Vec<f32>,
+    beat_click: Vec<f32>,
+    subdivision_click: Vec<f32>,
+    count_in_click: Vec<f32>,
+}
+
+pub(crate) fn render_click(sample_rate: f32, frequency: f32, gain: f32) -> Vec<f32> {
+    let len = (sample_rate * CLICK_SECONDS).round().max(1.0) as usize;
+    let omega = 2.0 * std::f32::consts::PI * frequency / sample_rate;
+    let decay = 1.0 / (sample_rate * CLICK_DECAY_SECONDS);
+    (0..len)
+        .map(|i| {
+            let t = i as f32;
+            gain * (omega * t).sin() * (-t * decay).exp()
+        })
+        .collect()
+}
+
+/// Clamp a caller-supplied tempo into the supported range. A NaN tempo would
+/// make every tick position NaN and `render_into` would never finish.
+fn clamp_bpm(bpm: f32) -> f32 {
+    if bpm.is_finite() { bpm.clamp(MIN_BPM, MAX_BPM) } else { DEFAULT_BPM }
+}
+
+/// Parse a time signature such as `"3/4"` or `"6/8"` into (beats, unit).
+pub(crate) fn parse_time_signature(time: &str) -> Option<(u32, u32)> {
+    let (beats, unit) = time.trim().split_once('/')?;
+    let beats: u32 = beats.trim().parse().ok()?;
+    let unit: u32 = unit.trim().parse().ok()?;
+    if !(1..=16).contains(&beats) || !matches!(unit, 1 | 2 | 4 | 8 | 16) {
+        return None;
+    }
+    Some((beats, unit))
+}
+
+#[wasm_bindgen]
+impl Metronome {
+    /// Create a new metronome in 4/4 with no subdivision or count-in
+    ///
+    /// # Arguments
+    /// * `sample_rate` - Output sample rate (typically 48000)
+    /// * `bpm` - Tempo in beats per minute
+    #[wasm_bindgen(constructor)]
+    pub fn new(sample_rate: f32, bpm: f32) -> Metronome {
+        let sample_rate = clamp_sample_rate(sample_rate);
+        Metronome {
+            sample_rate,
+            bpm: clamp_bpm(bpm),
+            beats_per_bar: 4,
+            beat_unit: 4,
+            subdivision: 1,
+            count_in_bars: 0,
+            volume: 0.8,
+            accent_click: render_click(sample_rate, ACCENT_FREQ, 1.0),
+            beat_click: render_click(sample_rate, BEAT_FREQ, 0.7),
+            subdivision_click: render_click(sample_rate, SUBDIVISION_FREQ, 0.4),
+            count_in_click: render_click(sample_rate, COUNT_IN_FREQ, 0.8),
+        }
+    }
+
+    /// Set tempo in beats per minute (clamped to 20-300; NaN or infinite
+    /// values select 120)
+    #[wasm_bindgen]
+    pub fn set_tempo(&mut self, bpm: f32) {
+        self.bpm = clamp_bpm(bpm);
+    }
+
+    /// Set time signature from a song `time` field such as `"3/4"`.
+    /// Returns false (leaving the meter unchanged) if the string is invalid.
+    #[wasm_bindgen]
+    pub fn set_time_signature(&mut self, time: &str) -> bool {
+        match parse_time_signature(time) {
+            Some((beats, unit)) => {
+                self.beats_per_bar = beats;
+                self.beat_unit = unit;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Set number of clicks per beat (clamped to 1-8)
+    #[wasm_bindgen]
+    pub fn set_subdivision(&mut self, clicks_per_beat: u32) {
+        self.subdivision = clicks_per_beat.clamp(1, 8);
+    }
+
+    /// Set number of count-in bars played before bar 1 (clamped to 0-4)
+    #[wasm_bindgen]
+    pub fn set_count_in(&mut self, bars: u32) {
+        self.count_in_bars = bars.min(4);
+    }
+
+    /// Set output gain (clamped to 0.0-1.0)
+    #[wasm_bindgen]
+    pub fn set_volume(&mut self, volume: f32) {
+        self.volume = if volume.is_nan() { 0.0 } else { volume.clamp(0.0, 1.0) };
+    }
+
+    /// Number of samples per beat (fractional, never rounded)
+    #[wasm_bindgen]
+    pub fn samples_per_beat(&self) -> f64 {
+        self.sample_rate as f64 * 60.0 / self.bpm as f64
+    }
+
+    /// Number of samples in one bar
+    #[wasm_bindgen]
+    pub fn samples_per_bar(&self) -> f64 {
+        self.samples_per_beat() * self.beats_per_bar as f64
+    }
+
+    /// Sample position at which bar 1 starts (the length of the count-in)
+    #[wasm_bindgen]
+    pub fn count_in_samples(&self) -> usize {
+        (self.samples_per_bar() * self.count_in_bars as f64).round() as usize
+    }
+
+    /// Sample position of the onset of tick `index`
+    #[wasm_bindgen]
+    pub fn tick_position(&self, index: usize) -> usize {
+        (index as f64 * self.samples_per_tick()).round() as usize
+    }
+
+    /// Kind of click played on tick `index`
+    #[wasm_bindgen]
+    pub fn tick_kind(&self, index: usize) -> ClickKind {
+        let ticks_per_bar = (self.beats_per_bar * self.subdivision) as usize;
+        let bar = index / ticks_per_bar;
+        let within_bar = index % ticks_per_bar;
+        let on_beat = within_bar.is_multiple_of(self.subdivision as usize);
+
+        if bar < self.count_in_bars as usize {
+            if on_beat { ClickKind::CountIn } else { ClickKind::Silent }
+        } else if within_bar == 0 {
+            ClickKind::Accent
+        } else if on_beat {
+            ClickKind::Beat
+        } else {
+            ClickKind::Subdivision
+        }
+    }
+
+    /// Render `length` samples starting at absolute sample position `start`
+    #[wasm_bindgen]
+    pub fn render(&self, start: usize, length: usize) -> Vec<f32> {
+        let mut output = vec![0.0; length];
+        self.render_into(start, &mut output);
+        output
+    }
+
+    /// Mix clicks into `output`, whose first sample is at absolute position `start`
+    #[wasm_bindgen]
+    pub fn render_into(&self, start: usize, output: &mut [f32]) {
+        let end = start.saturating_add(output.len());
+        let spt = self.samples_per_tick();
+        let click_len = self.accent_click.len();
+
+        // First tick whose click can still be sounding at `start`
+        let first = (start.saturating_sub(click_len) as f64 / spt).floor() as usize;
+
+        let mut index = first;
+        loop {
+            let onset = self.tick_position(index);
+            if onset >= end {
+                break;
+            }
+            let click = match self.tick_kind(index) {
+                ClickKind::Silent => None,
+                ClickKind::Accent => Some(&self.accent_click),
+                ClickKind::Beat => Some(&self.beat_click),
+                ClickKind::Subdivision => Some(&self.subdivision_click),
+                ClickKind::CountIn => Some(&self.count_in_click),
+            };
+            if let Some(click) = click {
+                let from = start.max(onset);
+                let to = end.min(onset.saturating_add(click.len()));
+                for pos in from..to {
+                    output[pos - start] += click[pos - onset] * self.volume;
+                }
+            }
+            index += 1;
+        }
+    }
+
+    /// Render the count-in followed by `bars` bars as a single buffer
+    #[wasm_bindgen]
+    pub fn render_bars(&self, bars: u32) -> Vec<f32> {
+        let total_bars = (self.count_in_bars + bars) as f64;
+        let length = (self.samples_per_bar() * total_bars).round() as usize;
+        self.render(0, length)
+    }
+
+    // Getters
+    #[wasm_bindgen(getter)]
+    pub fn bpm(&self) -> f32 { self.bpm }
+
+    #[wasm_bindgen(getter)]
+    pub fn beats_per_bar(&self) -> u32 { self.beats_per_bar }
+
+    #[wasm_bindgen(getter)]
+    pub fn beat_unit(&self) -> u32 { self.beat_unit }
+
+    #[wasm_bindgen(getter)]
+    pub fn subdivision(&self) -> u32 { self.subdivision }
+
+    #[wasm_bindgen(getter)]
+    pub fn count_in_bars(&self) -> u32 { self.count_in_bars }
+}
+
+impl Metronome {
+    fn samples_per_tick(&self) -> f64 {
+        self.samples_per_beat() / self.subdivision as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_time_signature_parsing() {
+        assert_eq!(parse_time_signature("4/4"), Some((4, 4)));
+        assert_eq!(parse_time_signature(" 6/8 "), Some((6, 8)));
+        assert_eq!(parse_time_signature("3/5"), None);
+        assert_eq!(parse_time_signature("waltz"), None);
+    }
+
+    #[test]
+    fn test_click_positions_are_sample_accurate() {
+        let mut metronome = Metronome::new(48000.0, 90.0);
+        metronome.set_time_signature("3/4");
+        // 90 BPM at 48kHz is exactly 32000 samples per beat
+        assert_eq!(metronome.tick_position(3), 96000);
+        assert_eq!(metronome.tick_kind(0), ClickKind::Accent);
+        assert_eq!(metronome.tick_kind(1), ClickKind::Beat);
+        assert_eq!(metronome.tick_kind(3), ClickKind::Accent);
+
+        let audio = metronome.render(95990, 20);
+        assert!(audio[..10].iter().all(|&x| x == 0.0));
+        assert!(audio[10..].iter().any(|&x| x != 0.0));
+    }
+
+    #[test]
+    fn test_block_rendering_matches_whole_render() {
+        let mut metronome = Metronome::new(44100.0, 137.0);
+        metronome.set_subdivision(3);
+        metronome.set_count_in(1);
+        let whole = metronome.render_bars(2);
+
+        let mut blocks = Vec::new();
+        for start in (0..whole.len()).step_by(128) {
+            let len = 128.min(whole.len() - start);
+            blocks.extend(metronome.render(start, len));
+        }
+        assert_eq!(whole, blocks);
+    }
+
+    #[test]
+    fn test_count_in_has_no_subdivisions() {
+        let mut metronome = Metronome::new(48000.0, 120.0);
+        metronome.set_subdivision(2);
+        metronome.set_count_in(1);
+        assert_eq!(metronome.count_in_samples(), 96000);
+        assert_eq!(metronome.tick_kind(0), ClickKind::CountIn);
+        assert_eq!(metronome.tick_kind(1), ClickKind::Silent);
+        assert_eq!(metronome.tick_kind(8), ClickKind::Accent);
+        assert_eq!(metronome.tick_kind(9), ClickKind::Subdivision);
+        assert_eq!(metronome.tick_kind(10), ClickKind::Beat);
+    }
+
+    #[test]
+    fn test_non_finite_tempo_uses_default() {
+        // Non-finite tempos fall back to the default, so the click spacing stays finite
+        let mut metronome = Metronome::new(48000.0, f32::NAN);
+        assert_eq!(metronome.bpm(), DEFAULT_BPM);
+        let mut block = [0.0; 128];
+        metronome.render_into(0, &mut block);
+        assert!(block.iter().any(|&x| x != 0.0));
+
+        for bpm in [f32::INFINITY, f32::NEG_INFINITY, f32::NAN] {
+            metronome.set_tempo(bpm);
+            assert_eq!(metronome.bpm(), DEFAULT_BPM);
+        }
+        metronome.set_tempo(1000.0);
+        assert_eq!(metronome.bpm(), MAX_BPM);
+
+        metronome.set_volume(f32::NAN);
+        metronome.render_into(usize::MAX - 64, &mut block);
+        assert!(block.iter().all(|x| x.is_finite()));
+    }
+}
@@ -0,0 +1,391 @@
+use wasm_bindgen::prelude::*;
+use js_sys::Object;
+use std::f32::consts::PI;
+use crate::clamp_sample_rate;
+use crate::config::{read_bool, read_f32, write};
+use crate::health::sanitize_sample;
+
+// ============================================================================
+// Input Pre-processing (DC blocker, high-pass, hum notch, pre-emphasis)
+// ============================================================================
+
+/// Time constant of the DC blocker's running mean (seconds)
+const DC_TIME_CONSTANT_SECONDS: f32 = 0.05;
+/// Butterworth quality factor for the high-pass stage
+const HIGH_PASS_Q: f32 = std::f32::consts::FRAC_1_SQRT_2;
+/// Quality factor of the hum notches (about 2 Hz wide at 60 Hz)
+const NOTCH_Q: f32 = 30.0;
+/// Mains partials notched: the fundamental and its second harmonic
+const NOTCH_HARMONICS: usize = 2;
+
+/// Settings of the pre-processing chain run on the input before analysis.
+/// Stages run in field order and each can be enabled on its own.
+#[wasm_bindgen]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PreprocessConfig {
+    /// Subtract a running mean (removes DC offset)
+    pub dc_blocker: bool,
+    /// Second-order Butterworth high-pass (removes handling noise and rumble)
+    pub high_pass: bool,
+    /// High-pass cutoff (Hz)
+    pub high_pass_hz: f32,
+    /// Narrow notches at the mains frequency and its second harmonic
+    pub hum_notch: bool,
+    /// Mains frequency (50 or 60 Hz)
+    pub hum_hz: f32,
+    /// First-order pre-emphasis, `y[n] = x[n] - k * x[n-1]`
+    pub pre_emphasis: bool,
+    /// Pre-emphasis coefficient `k`
+    pub pre_emphasis_coeff: f32,
+}
+
+impl Default for PreprocessConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[wasm_bindgen]
+impl PreprocessConfig {
+    /// Default chain: DC blocker and a 40 Hz high-pass; notch and
+    /// pre-emphasis off
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> PreprocessConfig {
+        PreprocessConfig {
+            dc_blocker: true,
+            high_pass: true,
+            high_pass_hz: 40.0,
+            hum_notch: false,
+            hum_hz: 60.0,
+            pre_emphasis: false,
+            pre_emphasis_coeff: 0.97,
+        }
+    }
+
+    /// Every stage disabled: samples pass through unchanged (non-finite ones
+    /// are still zeroed)
+    #[wasm_bindgen]
+    pub fn bypass() -> PreprocessConfig {
+        PreprocessConfig {
+            dc_blocker: false,
+            high_pass: false,
+            hum_notch: false,
+            pre_emphasis: false,
+            ..PreprocessConfig::new()
+        }
+    }
+
+    /// Build a config from a plain JS object with camelCase keys (e.g.
+    /// `{ humNotch: true, humHz: 50 }`). Missing or invalid keys keep their
+    /// defaults.
+    #[wasm_bindgen]
+    pub fn from_js(value: &JsValue) -> PreprocessConfig {
+        let mut config = PreprocessConfig::new();
+        if !value.is_object() {
+            return config;
+        }
+        if let Some(v) = read_bool(value, "dcBlocker") { config.dc_blocker = v; }
+        if let Some(v) = read_bool(value, "highPass") { config.high_pass = v; }
+        if let Some(v) = read_f32(value, "highPassHz") { config.high_pass_hz = v; }
+        if let Some(v) = read_bool(value, "humNotch") { config.hum_notch = v; }
+        if let Some(v) = read_f32(value, "humHz") { config.hum_hz = v; }
+        if let Some(v) = read_bool(value, "preEmphasis") { config.pre_emphasis = v; }
+        if let Some(v) = read_f32(value, "preEmphasisCoeff") { config.pre_emphasis_coeff = v; }
+        config.validated()
+    }
+
+    /// Serialise to a plain JS object with camelCase keys (inverse of `from_js`)
+    #[wasm_bindgen]
+    pub fn to_js(&self) -> JsValue {
+        let object = Object::new();
+        write(&object, "dcBlocker", self.dc_blocker.into());
+        write(&object, "highPass", self.high_pass.into());
+        write(&object, "highPassHz", self.high_pass_hz.into());
+        write(&object, "humNotch", self.hum_notch.into());
+        write(&object, "humHz", self.hum_hz.into());
+        write(&object, "preEmphasis", self.pre_emphasis.into());
+        write(&object, "preEmphasisCoeff", self.pre_emphasis_coeff.into());
+        object.into()
+    }
+
+    /// Copy with every value clamped to its supported range
+    #[wasm_bindgen]
+    pub fn validated(&self) -> PreprocessConfig {
+        let defaults = PreprocessConfig::new();
+        // clamp() passes NaN through, which would make every filter output NaN
+        let finite = |value: f32, default: f32| if value.is_finite() { value } else { default };
+        PreprocessConfig {
+            dc_blocker: self.dc_blocker,
+            high_pass: self.high_pass,
+            high_pass_hz: finite(self.high_pass_hz, defaults.high_pass_hz).clamp(10.0, 200.0),
+            hum_notch: self.hum_notch,
+            hum_hz: finite(self.hum_hz, defaults.hum_hz).clamp(45.0, 65.0),
+            pre_emphasis: self.pre_emphasis,
+            pre_emphasis_coeff: finite(self.pre_emphasis_coeff, defaults.pre_emphasis_coeff).clamp(0.0, 0.99),
+        }
+    }
+
+    /// Whether any stage is enabled
+    #[wasm_bindgen(getter)]
+    pub fn active(&self) -> bool {
+        self.dc_blocker || self.high_pass || self.hum_notch || self.pre_emphasis
+    }
+}
+
+/// Direct-form I biquad section
+#[derive(Clone, Debug, Default)]
+struct Biquad {
+    b0: f32,
+    b1: f32,
+    b2: f32,
+    a1: f32,
+    a2: f32,
+    x1: f32,
+    x2: f32,
+    y1: f32,
+    y2: f32,
+}
+
+impl Biquad {
+    /// Coefficients from the RBJ audio-EQ cookbook, normalised by a0
+    fn from_cookbook(b: [f32; 3], a: [f32; 3]) -> Biquad {
+        Biquad {
+            b0: b[0] / a[0],
+            b1: b[1] / a[0],
+            b2: b[2] / a[0],
+            a1: a[1] / a[0],
+            a2: a[2] / a[0],
+            ..Biquad::default()
+        }
+    }
+
+    fn high_pass(frequency: f32, q: f32, sample_rate: f32) -> Biquad {
+        let w0 = 2.0 * PI * frequency / sample_rate;
+        let (sin, cos) = w0.sin_cos();
+        let alpha = sin / (2.0 * q);
+        Biquad::from_cookbook(
+            [(1.0 + cos) / 2.0, -(1.0 + cos), (1.0 + cos) / 2.0],
+            [1.0 + alpha, -2.0 * cos, 1.0 - alpha],
+        )
+    }
+
+    fn notch(frequency: f32, q: f32, sample_rate: f32) -> Biquad {
+        let w0 = 2.0 * PI * frequency / sample_rate;
+        let (sin, cos) = w0.sin_cos();
+        let alpha = sin / (2.0 * q);
+        Biquad::from_cookbook([1.0, -2.0 * cos, 1.0], [1.0 + alpha, -2.0 * cos, 1.0 - alpha])
+    }
+
+    #[inline]
+    fn process(&mut self, x: f32) -> f32 {
+        let y = self.b0 * x + self.b1 * self.x1 + self.b2 * self.x2 - self.a1 * self.y1 - self.a2 * self.y2;
+        self.x2 = self.x1;
+        self.x1 = x;
+        self.y2 = self.y1;
+        self.y1 = y;
+        y
+    }
+
+    fn reset(&mut self) {
+        self.x1 = 0.0;
+        self.x2 = 0.0;
+        self.y1 = 0.0;
+        self.y2 = 0.0;
+    }
+}
+
+/// Stateful pre-processing chain for a contiguous stream of buffers
+#[derive(Clone, Debug)]
+pub(crate) struct Preprocessor {
+    config: PreprocessConfig,
+    /// Running-mean weight of the newest sample
+    dc_alpha: f32,
+    /// Running mean; `None` until the first buffer primes it
+    dc_mean: Option<f32>,
+    high_pass: Biquad,
+    notches: Vec<Biquad>,
+    /// Previous input sample of the pre-emphasis stage
+    last_input: f32,
+}
+
+impl Preprocessor {
+    pub(crate) fn new(config: &PreprocessConfig, sample_rate: f32) -> Preprocessor {
+        let config = config.validated();
+        let sample_rate = clamp_sample_rate(sample_rate);
+        let nyquist = sample_rate / 2.0;
+        let notches = (1..=NOTCH_HARMONICS)
+            .map(|h| config.hum_hz * h as f32)
+            .filter(|&f| f < nyquist)
+            .map(|f| Biquad::notch(f, NOTCH_Q, sample_rate))
+            .collect();
+        Preprocessor {
+            dc_alpha: 1.0 - (-1.0 / (DC_TIME_CONSTANT_SECONDS * sample_rate)).exp(),
+            dc_mean: None,
+            high_pass: Biquad::high_pass(config.high_pass_hz.min(nyquist * 0.9), HIGH_PASS_Q, sample_rate),
+            notches,
+            last_input: 0.0,
+            config,
+        }
+    }
+
+    pub(crate) fn config(&self) -> &PreprocessConfig {
+        &self.config
+    }
+
+    pub(crate) fn is_active(&self) -> bool {
+        self.config.active()
+    }
+
+    /// Forget filter state (the next buffer starts a new stream)
+    pub(crate) fn reset(&mut self) {
+        self.dc_mean = None;
+        self.high_pass.reset();
+        self.notches.iter_mut().for_each(Biquad::reset);
+        self.last_input = 0.0;
+    }
+
+    /// Filter `input`, the continuation of the previous buffer, into `out`.
+    /// Samples are sanitised first: one NaN in the running mean or a biquad's
+    /// state would silence everything after it.
+    pub(crate) fn process(&mut self, input: &[f32], out: &mut Vec<f32>) {
+        out.clear();
+        out.extend(input.iter().map(|&s| sanitize_sample(s)));
+        if self.config.dc_blocker && !out.is_empty() {
+            // Prime with the first buffer's mean so a constant offset does
+            // not ring through the first buffer
+            let mut mean = self
+                .dc_mean
+                .unwrap_or_else(|| out.iter().sum::<f32>() / out.len() as f32);
+            for x in out.iter_mut() {
+                mean += self.dc_alpha * (*x - mean);
+                *x -= mean;
+            }
+            self.dc_mean = Some(mean);
+        }
+        if self.config.high_pass {
+            out.iter_mut().for_each(|x| *x = self.high_pass.process(*x));
+        }
+        if self.config.hum_notch {
+            for notch in &mut self.notches {
+                out.iter_mut().for_each(|x| *x = notch.process(*x));
+            }
+        }
+        if self.config.pre_emphasis {
+            let k = self.config.pre_emphasis_coeff;
+            for x in out.iter_mut() {
+                let input = *x;
+                *x = input - k * self.last_input;
+                self.last_input = input;
+            }
+        }
+    }
+}
+
+/// Run the pre-processing chain over a complete recording
+#[wasm_bindgen]
+pub fn preprocess_recording(samples: &[f32], sample_rate: f32, config: &PreprocessConfig) -> Vec<f32> {
+    let mut out = Vec::with_capacity(samples.len());
+    Preprocessor::new(config, sample_rate).process(samples, &mut out);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_signals::sine;
+
+    const SAMPLE_RATE: f32 = 48000.0;
+
+    /// RMS of the second half (after filters have settled)
+    fn settled_rms(samples: &[f32]) -> f32 {
+        crate::compute_rms(&samples[samples.len() / 2..])
+    }
+
+    fn only(configure: impl Fn(&mut PreprocessConfig)) -> PreprocessConfig {
+        let mut config = PreprocessConfig::bypass();
+        configure(&mut config);
+        config
+    }
+
+    #[test]
+    fn test_each_stage() {
+        let len = SAMPLE_RATE as usize;
+        let tone = sine(440.0, 0.3, len, SAMPLE_RATE);
+
+        // DC blocker: the offset is gone from the very first buffer
+        let offset: Vec<f32> = tone.iter().map(|s| s + 0.2).collect();
+        let mut dc = Preprocessor::new(&only(|c| c.dc_blocker = true), SAMPLE_RATE);
+        let mut out = Vec::new();
+        dc.process(&offset[..2048], &mut out);
+        assert!((out.iter().sum::<f32>() / out.len() as f32).abs() < 0.01);
+
+        // High-pass: 15 Hz rumble removed, 196 Hz (G3) kept
+        let config = only(|c| c.high_pass = true);
+        let rumble = preprocess_recording(&sine(15.0, 0.3, len, SAMPLE_RATE), SAMPLE_RATE, &config);
+        let g3 = preprocess_recording(&sine(196.0, 0.3, len, SAMPLE_RATE), SAMPLE_RATE, &config);
+        assert!(settled_rms(&rumble) < 0.03, "{}", settled_rms(&rumble));
+        assert!(settled_rms(&g3) > 0.2);
+
+        // Notch: 50 Hz hum and its harmonic removed, the tone kept
+        let config = only(|c| {
+            c.hum_notch = true;
+            c.hum_hz = 50.0;
+        });
+        let hum = preprocess_recording(&sine(100.0, 0.1, len, SAMPLE_RATE), SAMPLE_RATE, &config);
+        let kept = preprocess_recording(&tone, SAMPLE_RATE, &config);
+        assert!(settled_rms(&hum) < 0.005, "{}", settled_rms(&hum));
+        assert!((settled_rms(&kept) - settled_rms(&tone)).abs() < 0.01);
+
+        // Pre-emphasis: high frequencies gain relative to low ones
+        let config = only(|c| c.pre_emphasis = true);
+        let low = settled_rms(&preprocess_recording(&sine(200.0, 0.3, len, SAMPLE_RATE), SAMPLE_RATE, &config));
+        let high = settled_rms(&preprocess_recording(&sine(4000.0, 0.3, len, SAMPLE_RATE), SAMPLE_RATE, &config));
+        assert!(high > 5.0 * low);
+
+        // Bypass leaves samples untouched
+        assert_eq!(preprocess_recording(&offset, SAMPLE_RATE, &PreprocessConfig::bypass()), offset);
+    }
+
+    #[test]
+    fn test_validation_clamps_values() {
+        let mut config = PreprocessConfig::new();
+        config.high_pass_hz = 1.0;
+        config.hum_hz = 400.0;
+        config.pre_emphasis_coeff = 2.0;
+        let valid = config.validated();
+        assert_eq!((valid.high_pass_hz, valid.hum_hz, valid.pre_emphasis_coeff), (10.0, 65.0, 0.99));
+        assert_eq!(PreprocessConfig::new().validated(), PreprocessConfig::new());
+
+        for value in [f32::NAN, f32::INFINITY, f32::NEG_INFINITY] {
+            let mut config = PreprocessConfig::new();
+            config.high_pass_hz = value;
+            config.hum_hz = value;
+            config.pre_emphasis_coeff = value;
+            assert_eq!(config.validated(), PreprocessConfig::new(), "{value}");
+        }
+    }
+
+    #[test]
+    fn test_non_finite_sample_does_not_poison_filters() {
+        let mut config = PreprocessConfig::new();
+        config.hum_notch = true;
+        config.pre_emphasis = true;
+        let tone = sine(440.0, 0.3, SAMPLE_RATE as usize, SAMPLE_RATE);
+        for bad in [f32::NAN, f32::INFINITY] {
+            let mut glitched = tone.clone();
+            glitched[100] = bad;
+            let out = preprocess_recording(&glitched, SAMPLE_RATE, &config);
+            assert!(out.iter().all(|x| x.is_finite()), "{bad}");
+            let clean = preprocess_recording(&tone, SAMPLE_RATE, &config);
+            assert!((settled_rms(&out) - settled_rms(&clean)).abs() < 1e-3);
+        }
+
+        // Offline analysis still finds the note behind the glitch
+        #[cfg(feature = "offline")]
+        {
+            let mut glitched = tone.clone();
+            glitched[100] = f32::NAN;
+            assert_eq!(crate::analyze_recording(&glitched, SAMPLE_RATE).notes().len(), 1);
+        }
+    }
+}
@@ -0,0 +1,588 @@
+use wasm_bindgen::prelude::*;
+use js_sys::{ArrayBuffer, Atomics, Int32Array, SharedArrayBuffer};
+use crate::{InputHealth, PitchResult};
+
+// ============================================================================
+// Binary Result Ring (worklet -> main thread)
+// ============================================================================
+//
+// A ring of fixed-size records in one (Shared)ArrayBuffer, viewed as 32-bit
+// words. The worklet writes records with `ResultRingWriter`; the main thread
+// reads them with `ResultRingReader` into a reused `FrameRecord`, so neither
+// side allocates or structured-clones per frame.
+//
+// Header (HEADER_WORDS words):
+//   0 magic "PNDA"   1 version   2 record words   3 capacity
+//   4 write sequence (number of records written, published with Atomics)
+//   5-7 reserved
+//
+// Record (RECORD_WORDS words, floats stored as IEEE-754 bits):
+//   0 sequence          1-2 timestamp_ms (f64, low word first)
+//   3 frequency         4 midi note (-1 if none)   5 cents (i32)
+//   6 volume            7 confidence               8 voicing_probability
+//   9 flags             10 onset_strength          11 tempo_bpm
+//   12 rhythm_offset_ms 13 raw_frequency           14 clipping_percent
+//   15 reserved
+//
+// A record is valid when its sequence word matches the slot the reader
+// expects and the writer has not lapped it while it was being read.
+
+/// "PNDA" in ASCII
+const MAGIC: u32 = 0x504E_4441;
+/// Bump whenever the header or record layout changes
+const PROTOCOL_VERSION: u32 = 2;
+const HEADER_WORDS: usize = 8;
+const RECORD_WORDS: usize = 16;
+
+const H_MAGIC: usize = 0;
+const H_VERSION: usize = 1;
+const H_RECORD_WORDS: usize = 2;
+const H_CAPACITY: usize = 3;
+const H_WRITE_SEQ: usize = 4;
+
+const R_SEQ: usize = 0;
+const R_TIME_LO: usize = 1;
+const R_TIME_HI: usize = 2;
+const R_FREQUENCY: usize = 3;
+const R_MIDI: usize = 4;
+const R_CENTS: usize = 5;
+const R_VOLUME: usize = 6;
+const R_CONFIDENCE: usize = 7;
+const R_VOICING: usize = 8;
+const R_FLAGS: usize = 9;
+const R_ONSET_STRENGTH: usize = 10;
+const R_TEMPO: usize = 11;
+const R_RHYTHM_OFFSET: usize = 12;
+const R_RAW_FREQUENCY: usize = 13;
+const R_CLIPPING: usize = 14;
+
+const FLAG_IN_TUNE: u32 = 1;
+const FLAG_CORRECTED: u32 = 2;
+const FLAG_ONSET: u32 = 4;
+const FLAG_HAS_SIGNAL: u32 = 8;
+const FLAG_CLIPPING: u32 = 16;
+const FLAG_INVALID_INPUT: u32 = 32;
+
+/// Layout version written to (and required in) the ring header
+#[wasm_bindgen]
+pub fn ring_protocol_version() -> u32 {
+    PROTOCOL_VERSION
+}
+
+/// Bytes needed for a ring holding `capacity` records
+#[wasm_bindgen]
+pub fn ring_byte_length(capacity: usize) -> usize {
+    (HEADER_WORDS + capacity * RECORD_WORDS) * 4
+}
+
+/// 32-bit word storage backing a ring
+pub(crate) trait RingWords {
+    fn len(&self) -> usize;
+    fn get(&self, index: usize) -> u32;
+    fn set(&mut self, index: usize, value: u32);
+    /// Read the write sequence with acquire semantics
+    fn load_seq(&self) -> u32;
+    /// Publish the write sequence with release semantics
+    fn store_seq(&mut self, value: u32);
+}
+
+impl RingWords for Vec<u32> {
+    fn len(&self) -> usize { self.as_slice().len() }
+    fn get(&self, index: usize) -> u32 { self[index] }
+    fn set(&mut self, index: usize, value: u32) { self[index] = value; }
+    fn load_seq(&self) -> u32 { self[H_WRITE_SEQ] }
+    fn store_seq(&mut self, value: u32) { self[H_WRITE_SEQ] = value; }
+}
+
+impl RingWords for Int32Array {
+    fn len(&self) -> usize { self.length() as usize }
+    fn get(&self, index: usize) -> u32 { self.get_index(index as u32) as u32 }
+    fn set(&mut self, index: usize, value: u32) { self.set_index(index as u32, value as i32); }
+    fn load_seq(&self) -> u32 {
+        Atomics::load(self, H_WRITE_SEQ as u32).unwrap_or(0) as u32
+    }
+    fn store_seq(&mut self, value: u32) {
+        // Atomics only fails on non-integer arrays, which Int32Array is not
+        let _ = Atomics::store(self, H_WRITE_SEQ as u32, value as i32);
+    }
+}
+
+/// 32-bit view of `buffer`, which must be an ArrayBuffer or SharedArrayBuffer
+/// (`Int32Array::new` would also accept a length or another array)
+fn ring_words(buffer: &JsValue) -> Result<Int32Array, JsValue> {
+    let byte_length = if let Some(buffer) = buffer.dyn_ref::<SharedArrayBuffer>() {
+        buffer.byte_length()
+    } else if let Some(buffer) = buffer.dyn_ref::<ArrayBuffer>() {
+        buffer.byte_length()
+    } else {
+        return Err(JsValue::from_str("expected an ArrayBuffer or SharedArrayBuffer"));
+    };
+    if byte_length % 4 != 0 {
+        return Err(JsValue::from_str("ring buffer length must be a multiple of 4 bytes"));
+    }
+    Ok(Int32Array::new(buffer))
+}
+
+/// Capacity of a buffer with `words` 32-bit words
+fn capacity_for(words: usize) -> usize {
+    words.saturating_sub(HEADER_WORDS) / RECORD_WORDS
+}
+
+/// Write a fresh header; returns the capacity in records
+pub(crate) fn init_ring<S: RingWords>(words: &mut S) -> usize {
+    let capacity = capacity_for(words.len());
+    words.set(H_MAGIC, MAGIC);
+    words.set(H_VERSION, PROTOCOL_VERSION);
+    words.set(H_RECORD_WORDS, RECORD_WORDS as u32);
+    words.set(H_CAPACITY, capacity as u32);
+    words.store_seq(0);
+    capacity
+}
+
+/// Check the header; returns the capacity if the layout is understood
+pub(crate) fn validate_ring<S: RingWords>(words: &S) -> Option<usize> {
+    if words.len() < HEADER_WORDS
+        || words.get(H_MAGIC) != MAGIC
+        || words.get(H_VERSION) != PROTOCOL_VERSION
+        || words.get(H_RECORD_WORDS) != RECORD_WORDS as u32
+    {
+        return None;
+    }
+    let capacity = words.get(H_CAPACITY) as usize;
+    (capacity > 0 && capacity <= capacity_for(words.len())).then_some(capacity)
+}
+
+/// Append `record` and publish it; returns its sequence number
+pub(crate) fn write_record<S: RingWords>(words: &mut S, capacity: usize, record: &FrameRecord) -> u32 {
+    let seq = words.load_seq();
+    let base = HEADER_WORDS + (seq as usize % capacity) * RECORD_WORDS;
+    let time_bits = record.timestamp_ms.to_bits();
+
+    words.set(base + R_SEQ, seq);
+    words.set(base + R_TIME_LO, time_bits as u32);
+    words.set(base + R_TIME_HI, (time_bits >> 32) as u32);
+    words.set(base + R_FREQUENCY, record.frequency.to_bits());
+    words.set(base + R_MIDI, record.midi as u32);
+    words.set(base + R_CENTS, record.cents as u32);
+    words.set(base + R_VOLUME, record.volume.to_bits());
+    words.set(base + R_CONFIDENCE, record.confidence.to_bits());
+    words.set(base + R_VOICING, record.voicing_probability.to_bits());
+    words.set(base + R_FLAGS, record.flags);
+    words.set(base + R_ONSET_STRENGTH, record.onset_strength.to_bits());
+    words.set(base + R_TEMPO, record.tempo_bpm.to_bits());
+    words.set(base + R_RHYTHM_OFFSET, record.rhythm_offset_ms.to_bits());
+    words.set(base + R_RAW_FREQUENCY, record.raw_frequency.to_bits());
+    words.set(base + R_CLIPPING, record.clipping_percent.to_bits());
+
+    words.store_seq(seq.wrapping_add(1));
+    seq
+}
+
+/// Outcome of reading the record with sequence number `seq`
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum ReadOutcome {
+    /// The record was copied into the output
+    Read,
+    /// The writer has not produced it yet
+    Pending,
+    /// The writer lapped the reader; resume from the contained sequence
+    Overrun(u32),
+}
+
+/// Copy record `seq` into `out` if it is still available
+pub(crate) fn read_record<S: RingWords>(words: &S, capacity: usize, seq: u32, out: &mut FrameRecord) -> ReadOutcome {
+    let written = words.load_seq();
+    let ahead = written.wrapping_sub(seq);
+    if ahead == 0 || ahead > u32::MAX / 2 {
+        return ReadOutcome::Pending;
+    }
+    let oldest = written.wrapping_sub(capacity as u32);
+    if ahead as usize > capacity {
+        return ReadOutcome::Overrun(oldest);
+    }
+
+    let base = HEADER_WORDS + (seq as usize % capacity) * RECORD_WORDS;
+    if words.get(base + R_SEQ) != seq {
+        return ReadOutcome::Overrun(oldest);
+    }
+    let time_bits = words.get(base + R_TIME_LO) as u64 | ((words.get(base + R_TIME_HI) as u64) << 32);
+    out.sequence = seq;
+    out.timestamp_ms = f64::from_bits(time_bits);
+    out.frequency = f32::from_bits(words.get(base + R_FREQUENCY));
+    out.midi = words.get(base + R_MIDI) as i32;
+    out.cents = words.get(base + R_CENTS) as i32;
+    out.volume = f32::from_bits(words.get(base + R_VOLUME));
+    out.confidence = f32::from_bits(words.get(base + R_CONFIDENCE));
+    out.voicing_probability = f32::from_bits(words.get(base + R_VOICING));
+    out.flags = words.get(base + R_FLAGS);
+    out.onset_strength = f32::from_bits(words.get(base + R_ONSET_STRENGTH));
+    out.tempo_bpm = f32::from_bits(words.get(base + R_TEMPO));
+    out.rhythm_offset_ms = f32::from_bits(words.get(base + R_RHYTHM_OFFSET));
+    out.raw_frequency = f32::from_bits(words.get(base + R_RAW_FREQUENCY));
+    out.clipping_percent = f32::from_bits(words.get(base + R_CLIPPING));
+
+    // The writer may have started overwriting the slot while it was being
+    // copied. It stamps the slot's sequence word before anything else, so
+    // that catches a write still in progress as well as a finished one.
+    let written = words.load_seq();
+    if written.wrapping_sub(seq) as usize > capacity {
+        return ReadOutcome::Overrun(written.wrapping_sub(capacity as u32));
+    }
+    if words.get(base + R_SEQ) != seq {
+        return ReadOutcome::Overrun(seq.wrapping_add(1));
+    }
+    ReadOutcome::Read
+}
+
+/// One decoded analysis frame. Reuse a single instance on the main thread.
+#[wasm_bindgen]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FrameRecord {
+    sequence: u32,
+    timestamp_ms: f64,
+    frequency: f32,
+    /// Nearest MIDI note, or -1 when no pitch was detected
+    midi: i32,
+    cents: i32,
+    volume: f32,
+    confidence: f32,
+    voicing_probability: f32,
+    flags: u32,
+    onset_strength: f32,
+    tempo_bpm: f32,
+    rhythm_offset_ms: f32,
+    raw_frequency: f32,
+    clipping_percent: f32,
+}
+
+impl FrameRecord {
+    /// Record for a detection result plus the worklet's onset/rhythm state
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn from_result(
+        timestamp_ms: f64,
+        result: &PitchResult,
+        onset: bool,
+        onset_strength: f32,
+        tempo_bpm: f32,
+        rhythm_offset_ms: f32,
+        has_signal: bool,
+    ) -> FrameRecord {
+        let mut flags = 0;
+        if result.in_tune { flags |= FLAG_IN_TUNE; }
+        if result.corrected { flags |= FLAG_CORRECTED; }
+        if onset { flags |= FLAG_ONSET; }
+        if has_signal { flags |= FLAG_HAS_SIGNAL; }
+        match result.input_health {
+            InputHealth::Good => {}
+            InputHealth::Clipping => flags |= FLAG_CLIPPING,
+            InputHealth::Invalid => flags |= FLAG_INVALID_INPUT,
+        }
+
+        let midi = if result.frequency > 0.0 {
+            crate::frequency_to_midi(result.frequency).round() as i32
+        } else {
+            -1
+        };
+
+        FrameRecord {
+            sequence: 0,
+            timestamp_ms,
+            frequency: result.frequency,
+            midi,
+            cents: result.cents,
+            volume: result.volume,
+            confidence: result.confidence,
+            voicing_probability: result.voicing_probability,
+            flags,
+            onset_strength,
+            tempo_bpm,
+            rhythm_offset_ms,
+            raw_frequency: result.raw_frequency,
+            clipping_percent: result.clipping_percent,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl FrameRecord {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> FrameRecord {
+        FrameRecord { midi: -1, ..FrameRecord::default() }
+    }
+
+    /// Note name (e.g., "A4"), or "-" when no pitch was detected
+    #[wasm_bindgen(getter)]
+    pub fn note(&self) -> String {
+        if self.midi < 0 { "-".to_string() } else { crate::midi_to_note_name(self.midi) }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn sequence(&self) -> u32 { self.sequence }
+
+    #[wasm_bindgen(getter)]
+    pub fn timestamp_ms(&self) -> f64 { self.timestamp_ms }
+
+    #[wasm_bindgen(getter)]
+    pub fn frequency(&self) -> f32 { self.frequency }
+
+    #[wasm_bindgen(getter)]
+    pub fn midi(&self) -> i32 { self.midi }
+
+    #[wasm_bindgen(getter)]
+    pub fn cents(&self) -> i32 { self.cents }
+
+    #[wasm_bindgen(getter)]
+    pub fn volume(&self) -> f32 { self.volume }
+
+    #[wasm_bindgen(getter)]
+    pub fn confidence(&self) -> f32 { self.confidence }
+
+    #[wasm_bindgen(getter)]
+    pub fn voicing_probability(&self) -> f32 { self.voicing_probability }
+
+    #[wasm_bindgen(getter)]
+    pub fn in_tune(&self) -> bool { self.flags & FLAG_IN_TUNE != 0 }
+
+    #[wasm_bindgen(getter)]
+    pub fn corrected(&self) -> bool { self.flags & FLAG_CORRECTED != 0 }
+
+    #[wasm_bindgen(getter)]
+    pub fn onset(&self) -> bool { self.flags & FLAG_ONSET != 0 }
+
+    #[wasm_bindgen(getter)]
+    pub fn has_signal(&self) -> bool { self.flags & FLAG_HAS_SIGNAL != 0 }
+
+    #[wasm_bindgen(getter)]
+    pub fn onset_strength(&self) -> f32 { self.onset_strength }
+
+    #[wasm_bindgen(getter)]
+    pub fn tempo_bpm(&self) -> f32 { self.tempo_bpm }
+
+    #[wasm_bindgen(getter)]
+    pub fn rhythm_offset_ms(&self) -> f32 { self.rhythm_offset_ms }
+
+    #[wasm_bindgen(getter)]
+    pub fn raw_frequency(&self) -> f32 { self.raw_frequency }
+
+    #[wasm_bindgen(getter)]
+    pub fn clipping_percent(&self) -> f32 { self.clipping_percent }
+
+    #[wasm_bindgen(getter)]
+    pub fn input_health(&self) -> InputHealth {
+        if self.flags & FLAG_INVALID_INPUT != 0 {
+            InputHealth::Invalid
+        } else if self.flags & FLAG_CLIPPING != 0 {
+            InputHealth::Clipping
+        } else {
+            InputHealth::Good
+        }
+    }
+}
+
+/// Worklet side: writes frames into a ring allocated with `ring_byte_length`
+#[wasm_bindgen]
+pub struct ResultRingWriter {
+    words: Int32Array,
+    capacity: usize,
+}
+
+#[wasm_bindgen]
+impl ResultRingWriter {
+    /// Initialise the header of `buffer` (a SharedArrayBuffer or ArrayBuffer).
+    /// Fails for any other value or a buffer too small for one record.
+    #[wasm_bindgen(constructor)]
+    pub fn new(buffer: &JsValue) -> Result<ResultRingWriter, JsValue> {
+        let mut words = ring_words(buffer)?;
+        let capacity = init_ring(&mut words);
+        if capacity == 0 {
+            return Err(JsValue::from_str("buffer too small for one record"));
+        }
+        Ok(ResultRingWriter { words, capacity })
+    }
+
+    /// Publish a detection result with the worklet's onset/rhythm state.
+    /// Returns the record's sequence number.
+    #[wasm_bindgen]
+    #[allow(clippy::too_many_arguments)]
+    pub fn write(
+        &mut self,
+        timestamp_ms: f64,
+        result: &PitchResult,
+        onset: bool,
+        onset_strength: f32,
+        tempo_bpm: f32,
+        rhythm_offset_ms: f32,
+        has_signal: bool,
+    ) -> u32 {
+        let record = FrameRecord::from_result(
+            timestamp_ms, result, onset, onset_strength, tempo_bpm, rhythm_offset_ms, has_signal,
+        );
+        write_record(&mut self.words, self.capacity, &record)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn capacity(&self) -> usize { self.capacity }
+}
+
+/// Main-thread side: reads frames in order from a ring shared with the worklet
+#[wasm_bindgen]
+pub struct ResultRingReader {
+    words: Int32Array,
+    capacity: usize,
+    next_seq: u32,
+    dropped: u32,
+}
+
+#[wasm_bindgen]
+impl ResultRingReader {
+    /// Attach to a ring already initialised by a `ResultRingWriter`.
+    /// Fails if `buffer` is not an ArrayBuffer or SharedArrayBuffer, or if the
+    /// header's magic, version or record size do not match.
+    #[wasm_bindgen(constructor)]
+    pub fn new(buffer: &JsValue) -> Result<ResultRingReader, JsValue> {
+        let words = ring_words(buffer)?;
+        let Some(capacity) = validate_ring(&words) else {
+            return Err(JsValue::from_str("unsupported result ring layout"));
+        };
+        let next_seq = words.load_seq();
+        Ok(ResultRingReader { words, capacity, next_seq, dropped: 0 })
+    }
+
+    /// Copy the next unread frame into `out`. Returns false when caught up.
+    /// Frames overwritten before they were read are skipped and counted.
+    #[wasm_bindgen]
+    pub fn read_next(&mut self, out: &mut FrameRecord) -> bool {
+        loop {
+            match read_record(&self.words, self.capacity, self.next_seq, out) {
+                ReadOutcome::Read => {
+                    self.next_seq = self.next_seq.wrapping_add(1);
+                    return true;
+                }
+                ReadOutcome::Pending => return false,
+                ReadOutcome::Overrun(resume) => {
+                    self.dropped = self.dropped.wrapping_add(resume.wrapping_sub(self.next_seq));
+                    self.next_seq = resume;
+                }
+            }
+        }
+    }
+
+    /// Copy only the newest frame into `out`, skipping any backlog
+    #[wasm_bindgen]
+    pub fn read_latest(&mut self, out: &mut FrameRecord) -> bool {
+        let written = self.words.load_seq();
+        if written == self.next_seq {
+            return false;
+        }
+        let latest = written.wrapping_sub(1);
+        self.dropped = self.dropped.wrapping_add(latest.wrapping_sub(self.next_seq));
+        self.next_seq = latest;
+        self.read_next(out)
+    }
+
+    /// Frames that were overwritten or skipped before being read
+    #[wasm_bindgen(getter)]
+    pub fn dropped(&self) -> u32 { self.dropped }
+
+    #[wasm_bindgen(getter)]
+    pub fn capacity(&self) -> usize { self.capacity }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::empty_pitch_result;
+    use std::cell::RefCell;
+
+    type Race = Box<dyn FnOnce(&mut Vec<u32>)>;
+
+    /// Ring storage where a simulated writer runs when `trigger` is read,
+    /// i.e. part-way through the reader's copy
+    struct RacingWords {
+        words: RefCell<Vec<u32>>,
+        trigger: usize,
+        race: RefCell<Option<Race>>,
+    }
+
+    impl RingWords for RacingWords {
+        fn len(&self) -> usize { self.words.borrow().len() }
+        fn get(&self, index: usize) -> u32 {
+            if index == self.trigger && let Some(race) = self.race.borrow_mut().take() {
+                race(&mut self.words.borrow_mut());
+            }
+            self.words.borrow()[index]
+        }
+        fn set(&mut self, index: usize, value: u32) { self.words.get_mut()[index] = value; }
+        fn load_seq(&self) -> u32 { self.words.borrow()[H_WRITE_SEQ] }
+        fn store_seq(&mut self, value: u32) { self.words.get_mut()[H_WRITE_SEQ] = value; }
+    }
+
+    fn record(frequency: f32, timestamp_ms: f64) -> FrameRecord {
+        let mut result = empty_pitch_result(0.2, 0.9, 0.95);
+        result.frequency = frequency;
+        result.cents = -7;
+        result.in_tune = true;
+        result.clipping_percent = 3.5;
+        result.input_health = InputHealth::Clipping;
+        FrameRecord::from_result(timestamp_ms, &result, true, 0.5, 96.0, -12.5, true)
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut words = vec![0u32; ring_byte_length(4) / 4];
+        let capacity = init_ring(&mut words);
+        assert_eq!(validate_ring(&words), Some(4));
+
+        let written = record(440.0, 3_600_000.125);
+        assert_eq!(write_record(&mut words, capacity, &written), 0);
+
+        let mut out = FrameRecord::new();
+        assert_eq!(read_record(&words, capacity, 0, &mut out), ReadOutcome::Read);
+        assert_eq!(out, written);
+        assert_eq!(out.note(), "A4");
+        assert!(out.in_tune() && out.onset() && out.has_signal() && !out.corrected());
+        assert_eq!((out.clipping_percent(), out.input_health()), (3.5, InputHealth::Clipping));
+        assert_eq!(read_record(&words, capacity, 1, &mut out), ReadOutcome::Pending);
+    }
+
+    #[test]
+    fn test_overrun_and_version_check() {
+        let mut words = vec![0u32; ring_byte_length(4) / 4];
+        let capacity = init_ring(&mut words);
+        for i in 0..10 {
+            write_record(&mut words, capacity, &record(440.0, i as f64));
+        }
+        let mut out = FrameRecord::new();
+        assert_eq!(read_record(&words, capacity, 2, &mut out), ReadOutcome::Overrun(6));
+        assert_eq!(read_record(&words, capacity, 6, &mut out), ReadOutcome::Read);
+        assert_eq!(out.timestamp_ms(), 6.0);
+
+        words[H_VERSION] = PROTOCOL_VERSION + 1;
+        assert_eq!(validate_ring(&words), None);
+    }
+
+    #[test]
+    fn test_slot_overwritten_during_copy_is_not_read() {
+        let mut words = vec![0u32; ring_byte_length(4) / 4];
+        let capacity = init_ring(&mut words);
+        for i in 0..4 {
+            write_record(&mut words, capacity, &record(440.0, i as f64));
+        }
+        // Exactly one lap ahead: the writer starts record 4 in record 0's
+        // slot while it is being copied, and has not published it yet
+        let slot = HEADER_WORDS;
+        let racing = RacingWords {
+            words: RefCell::new(words),
+            trigger: slot + R_VOLUME,
+            race: RefCell::new(Some(Box::new(move |words: &mut Vec<u32>| {
+                words[slot + R_SEQ] = 4;
+                words[slot + R_FREQUENCY] = 880.0f32.to_bits();
+            }))),
+        };
+
+        let mut out = FrameRecord::new();
+        assert_eq!(read_record(&racing, capacity, 0, &mut out), ReadOutcome::Overrun(1));
+        assert_eq!(read_record(&racing, capacity, 1, &mut out), ReadOutcome::Read);
+        assert_eq!(out.timestamp_ms(), 1.0);
+    }
+}
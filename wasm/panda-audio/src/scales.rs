@@ -0,0 +1,469 @@
+use wasm_bindgen::prelude::*;
+use crate::{midi_to_note_name, AnalysisReport, NOTE_NAMES};
+
+// ============================================================================
+// Key and Scale Detection
+// ============================================================================
+
+/// Scales checked by scale practice
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScaleType {
+    Major,
+    NaturalMinor,
+    HarmonicMinor,
+    /// Raised 6th and 7th ascending, natural minor descending
+    MelodicMinor,
+}
+
+const ALL_SCALES: [ScaleType; 4] = [
+    ScaleType::Major,
+    ScaleType::NaturalMinor,
+    ScaleType::HarmonicMinor,
+    ScaleType::MelodicMinor,
+];
+
+impl ScaleType {
+    /// Semitones above the tonic when ascending
+    fn ascending(self) -> [i32; 7] {
+        match self {
+            ScaleType::Major => [0, 2, 4, 5, 7, 9, 11],
+            ScaleType::NaturalMinor => [0, 2, 3, 5, 7, 8, 10],
+            ScaleType::HarmonicMinor => [0, 2, 3, 5, 7, 8, 11],
+            ScaleType::MelodicMinor => [0, 2, 3, 5, 7, 9, 11],
+        }
+    }
+
+    /// Semitones above the tonic when descending (listed low to high)
+    fn descending(self) -> [i32; 7] {
+        match self {
+            ScaleType::MelodicMinor => ScaleType::NaturalMinor.ascending(),
+            other => other.ascending(),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            ScaleType::Major => "major",
+            ScaleType::NaturalMinor => "natural minor",
+            ScaleType::HarmonicMinor => "harmonic minor",
+            ScaleType::MelodicMinor => "melodic minor",
+        }
+    }
+}
+
+/// Krumhansl-Kessler key profiles (tonic first)
+const MAJOR_PROFILE: [f32; 12] = [6.35, 2.23, 3.48, 2.33, 4.38, 4.09, 2.52, 5.19, 2.39, 3.66, 2.29, 2.88];
+const MINOR_PROFILE: [f32; 12] = [6.33, 2.68, 3.52, 5.38, 2.60, 3.53, 2.54, 4.75, 3.98, 2.69, 3.34, 3.17];
+
+/// Notes shorter than this (seconds) are treated as slips between notes
+const MIN_SCALE_NOTE_SECONDS: f32 = 0.08;
+
+/// Pearson correlation of two equal-length profiles
+fn correlation(a: &[f32], b: &[f32]) -> f32 {
+    let n = a.len().min(b.len()) as f32;
+    let mean_a = a.iter().sum::<f32>() / n;
+    let mean_b = b.iter().sum::<f32>() / n;
+    let (mut cov, mut var_a, mut var_b) = (0.0f32, 0.0f32, 0.0f32);
+    for (&x, &y) in a.iter().zip(b) {
+        cov += (x - mean_a) * (y - mean_b);
+        var_a += (x - mean_a) * (x - mean_a);
+        var_b += (y - mean_b) * (y - mean_b);
+    }
+    let denom = (var_a * var_b).sqrt();
+    if denom > 0.0 { cov / denom } else { 0.0 }
+}
+
+/// A played note reduced to what scale checking needs
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct PlayedNote {
+    pub(crate) midi: i32,
+    /// Deviation from `midi` (cents)
+    pub(crate) cents: f32,
+    pub(crate) duration: f32,
+}
+
+/// Played notes from a report, with slips dropped and re-bowed repeats merged
+fn played_notes(report: &AnalysisReport) -> Vec<PlayedNote> {
+    let mut played: Vec<PlayedNote> = Vec::new();
+    for note in report.notes() {
+        if note.duration() < MIN_SCALE_NOTE_SECONDS {
+            continue;
+        }
+        match played.last_mut() {
+            Some(last) if last.midi == note.midi() => last.duration += note.duration(),
+            _ => played.push(PlayedNote { midi: note.midi(), cents: note.cents(), duration: note.duration() }),
+        }
+    }
+    played
+}
+
+/// Estimated key of a passage
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct KeyEstimate {
+    /// Tonic pitch class (0 = C)
+    tonic: i32,
+    major: bool,
+    /// Correlation of the pitch-class profile with the key profile (-1 to 1)
+    correlation: f32,
+    /// Margin over the next best key (0 = ambiguous)
+    confidence: f32,
+}
+
+#[wasm_bindgen]
+impl KeyEstimate {
+    #[wasm_bindgen(getter)]
+    pub fn tonic(&self) -> i32 { self.tonic }
+
+    #[wasm_bindgen(getter)]
+    pub fn major(&self) -> bool { self.major }
+
+    #[wasm_bindgen(getter)]
+    pub fn correlation(&self) -> f32 { self.correlation }
+
+    #[wasm_bindgen(getter)]
+    pub fn confidence(&self) -> f32 { self.confidence }
+
+    /// Key name (e.g., "A minor")
+    #[wasm_bindgen(getter)]
+    pub fn name(&self) -> String {
+        let mode = if self.major { "major" } else { "minor" };
+        format!("{} {}", NOTE_NAMES[self.tonic as usize], mode)
+    }
+}
+
+/// Estimate the key from a 12-bin chroma profile (C first), e.g. the
+/// duration-weighted pitch classes of a passage or a summed chromagram
+#[wasm_bindgen]
+pub fn estimate_key_from_chroma(chroma: &[f32]) -> KeyEstimate {
+    let mut scores: Vec<(f32, i32, bool)> = Vec::with_capacity(24);
+    if chroma.len() == 12 {
+        for tonic in 0..12 {
+            let rotated: Vec<f32> = (0..12).map(|i| chroma[(i + tonic) % 12]).collect();
+            scores.push((correlation(&rotated, &MAJOR_PROFILE), tonic as i32, true));
+            scores.push((correlation(&rotated, &MINOR_PROFILE), tonic as i32, false));
+        }
+    }
+    scores.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    match scores.as_slice() {
+        [(best, tonic, major), (second, ..), ..] => KeyEstimate {
+            tonic: *tonic,
+            major: *major,
+            correlation: *best,
+            confidence: (best - second).max(0.0),
+        },
+        _ => KeyEstimate { tonic: 0, major: true, correlation: 0.0, confidence: 0.0 },
+    }
+}
+
+/// Estimate the key of an analysed recording from its notes
+#[wasm_bindgen]
+pub fn estimate_key(report: &AnalysisReport) -> KeyEstimate {
+    let mut chroma = [0.0f32; 12];
+    for note in played_notes(report) {
+        chroma[note.midi.rem_euclid(12) as usize] += note.duration;
+    }
+    estimate_key_from_chroma(&chroma)
+}
+
+/// Outcome of checking a played scale
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct ScaleCheck {
+    tonic_midi: i32,
+    scale_type: ScaleType,
+    octaves: u32,
+    /// Expected MIDI notes (ascending, plus the descent if it was played)
+    expected: Vec<i32>,
+    /// Played MIDI notes after merging repeats
+    played: Vec<i32>,
+    /// Expected notes played in order
+    matched: usize,
+    /// Expected notes that were skipped
+    missing: Vec<i32>,
+    /// Played notes outside the scale
+    wrong_notes: Vec<i32>,
+    /// Whether the descent was played (and therefore checked)
+    descending: bool,
+    /// Every expected pitch was played and nothing outside the scale
+    notes_correct: bool,
+    /// Every expected note was played in the expected order with nothing extra
+    order_correct: bool,
+    /// Mean signed cents per scale degree (1st to 7th; 0 if unplayed)
+    degree_cents: Vec<f32>,
+    /// Number of matched notes per scale degree
+    degree_counts: Vec<u32>,
+    mean_abs_cents: f32,
+}
+
+#[wasm_bindgen]
+impl ScaleCheck {
+    #[wasm_bindgen(getter)]
+    pub fn tonic_midi(&self) -> i32 { self.tonic_midi }
+
+    #[wasm_bindgen(getter)]
+    pub fn scale_type(&self) -> ScaleType { self.scale_type }
+
+    /// Scale name (e.g., "D harmonic minor")
+    #[wasm_bindgen(getter)]
+    pub fn name(&self) -> String {
+        format!("{} {}", NOTE_NAMES[self.tonic_midi.rem_euclid(12) as usize], self.scale_type.name())
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn octaves(&self) -> u32 { self.octaves }
+
+    #[wasm_bindgen(getter)]
+    pub fn expected(&self) -> Vec<i32> { self.expected.clone() }
+
+    #[wasm_bindgen(getter)]
+    pub fn played(&self) -> Vec<i32> { self.played.clone() }
+
+    #[wasm_bindgen(getter)]
+    pub fn matched(&self) -> usize { self.matched }
+
+    #[wasm_bindgen(getter)]
+    pub fn missing(&self) -> Vec<i32> { self.missing.clone() }
+
+    /// Names of the missing notes (e.g., "F#4")
+    #[wasm_bindgen(getter)]
+    pub fn missing_names(&self) -> Vec<String> {
+        self.missing.iter().map(|&m| midi_to_note_name(m)).collect()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn wrong_notes(&self) -> Vec<i32> { self.wrong_notes.clone() }
+
+    #[wasm_bindgen(getter)]
+    pub fn descending(&self) -> bool { self.descending }
+
+    #[wasm_bindgen(getter)]
+    pub fn notes_correct(&self) -> bool { self.notes_correct }
+
+    #[wasm_bindgen(getter)]
+    pub fn order_correct(&self) -> bool { self.order_correct }
+
+    #[wasm_bindgen(getter)]
+    pub fn correct(&self) -> bool { self.notes_correct && self.order_correct }
+
+    #[wasm_bindgen(getter)]
+    pub fn degree_cents(&self) -> Vec<f32> { self.degree_cents.clone() }
+
+    #[wasm_bindgen(getter)]
+    pub fn degree_counts(&self) -> Vec<u32> { self.degree_counts.clone() }
+
+    #[wasm_bindgen(getter)]
+    pub fn mean_abs_cents(&self) -> f32 { self.mean_abs_cents }
+
+    /// Fraction of expected notes played in order (0.0 to 1.0)
+    #[wasm_bindgen(getter)]
+    pub fn score(&self) -> f32 {
+        if self.expected.is_empty() {
+            return 0.0;
+        }
+        let extra = self.played.len().saturating_sub(self.matched);
+        (self.matched as f32 - 0.5 * extra as f32).max(0.0) / self.expected.len() as f32
+    }
+}
+
+/// Expected notes going up `octaves` octaves from `tonic`, optionally back down
+fn expected_notes(tonic: i32, scale: ScaleType, octaves: u32, descending: bool) -> Vec<i32> {
+    let mut notes = Vec::new();
+    for octave in 0..octaves as i32 {
+        notes.extend(scale.ascending().iter().map(|d| tonic + 12 * octave + d));
+    }
+    notes.push(tonic + 12 * octaves as i32);
+    if descending {
+        for octave in (0..octaves as i32).rev() {
+            notes.extend(scale.descending().iter().rev().map(|d| tonic + 12 * octave + d));
+        }
+    }
+    notes
+}
+
+/// Pairs (played index, expected index) of a minimum-edit alignment
+fn align(played: &[i32], expected: &[i32]) -> Vec<(usize, usize)> {
+    let (n, m) = (played.len(), expected.len());
+    let mut cost = vec![vec![0usize; m + 1]; n + 1];
+    for (i, row) in cost.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in cost[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=n {
+        for j in 1..=m {
+            let substitution = if played[i - 1] == expected[j - 1] { 0 } else { 2 };
+            cost[i][j] = (cost[i - 1][j - 1] + substitution)
+                .min(cost[i - 1][j] + 1)
+                .min(cost[i][j - 1] + 1);
+        }
+    }
+
+    let mut pairs = Vec::new();
+    let (mut i, mut j) = (n, m);
+    while i > 0 && j > 0 {
+        if played[i - 1] == expected[j - 1] && cost[i][j] == cost[i - 1][j - 1] {
+            pairs.push((i - 1, j - 1));
+            i -= 1;
+            j -= 1;
+        } else if cost[i][j] == cost[i - 1][j] + 1 {
+            i -= 1;
+        } else if cost[i][j] == cost[i][j - 1] + 1 {
+            j -= 1;
+        } else {
+            i -= 1;
+            j -= 1;
+        }
+    }
+    pairs.reverse();
+    pairs
+}
+
+/// Check played notes against one scale, up only or up and back down
+/// (whichever the student attempted)
+pub(crate) fn check_played(played: &[PlayedNote], tonic: i32, scale: ScaleType, octaves: u32) -> ScaleCheck {
+    let octaves = octaves.clamp(1, 2);
+    let midis: Vec<i32> = played.iter().map(|n| n.midi).collect();
+    let peak = midis.iter().position(|&m| m == tonic + 12 * octaves as i32);
+    let descending = peak.is_some_and(|p| p + 1 < midis.len());
+    let expected = expected_notes(tonic, scale, octaves, descending);
+    let pairs = align(&midis, &expected);
+
+    let mut in_scale = [false; 12];
+    for d in scale.ascending().iter().chain(scale.descending().iter()) {
+        in_scale[(tonic + d).rem_euclid(12) as usize] = true;
+    }
+    let wrong_notes: Vec<i32> = midis.iter().copied().filter(|m| !in_scale[m.rem_euclid(12) as usize]).collect();
+
+    let mut degree_sum = [0.0f32; 7];
+    let mut degree_counts = [0u32; 7];
+    let mut abs_sum = 0.0;
+    let mut matched_expected = vec![false; expected.len()];
+    for &(p, e) in &pairs {
+        matched_expected[e] = true;
+        let semitones = (expected[e] - tonic).rem_euclid(12);
+        let degree = scale
+            .ascending()
+            .iter()
+            .chain(scale.descending().iter())
+            .position(|&d| d == semitones)
+            .map_or(0, |i| i % 7);
+        degree_sum[degree] += played[p].cents;
+        degree_counts[degree] += 1;
+        abs_sum += played[p].cents.abs();
+    }
+    let missing: Vec<i32> = expected
+        .iter()
+        .zip(&matched_expected)
+        .filter(|&(_, &hit)| !hit)
+        .map(|(&m, _)| m)
+        .collect();
+
+    let all_pitches_played = expected.iter().all(|m| midis.contains(m));
+    ScaleCheck {
+        tonic_midi: tonic,
+        scale_type: scale,
+        octaves,
+        matched: pairs.len(),
+        notes_correct: all_pitches_played && wrong_notes.is_empty(),
+        order_correct: pairs.len() == expected.len() && midis.len() == expected.len(),
+        missing,
+        wrong_notes,
+        descending,
+        degree_cents: degree_sum
+            .iter()
+            .zip(&degree_counts)
+            .map(|(&sum, &count)| if count > 0 { sum / count as f32 } else { 0.0 })
+            .collect(),
+        degree_counts: degree_counts.to_vec(),
+        mean_abs_cents: if pairs.is_empty() { 0.0 } else { abs_sum / pairs.len() as f32 },
+        expected,
+        played: midis,
+    }
+}
+
+/// Identify which scale was played, assuming it starts on its tonic
+pub(crate) fn identify_played(played: &[PlayedNote]) -> Option<ScaleCheck> {
+    let tonic = played.first()?.midi;
+    let top = played.iter().map(|n| n.midi).max()?;
+    let octaves = if top - tonic > 18 { 2 } else { 1 };
+    ALL_SCALES
+        .iter()
+        .map(|&scale| check_played(played, tonic, scale, octaves))
+        // Earlier (simpler) scales win ties
+        .reduce(|best, next| if next.score() > best.score() { next } else { best })
+}
+
+/// Check a recorded scale against the one that was asked for
+///
+/// # Arguments
+/// * `tonic_midi` - Starting note (e.g., 62 for D4)
+/// * `octaves` - 1 or 2
+#[wasm_bindgen]
+pub fn check_scale(report: &AnalysisReport, tonic_midi: i32, scale_type: ScaleType, octaves: u32) -> ScaleCheck {
+    check_played(&played_notes(report), tonic_midi, scale_type, octaves)
+}
+
+/// Work out which scale was actually played (undefined if nothing was played)
+#[wasm_bindgen]
+pub fn identify_scale(report: &AnalysisReport) -> Option<ScaleCheck> {
+    identify_played(&played_notes(report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn notes(midis: &[i32]) -> Vec<PlayedNote> {
+        midis.iter().map(|&midi| PlayedNote { midi, cents: 5.0, duration: 0.4 }).collect()
+    }
+
+    #[test]
+    fn test_key_from_profile() {
+        // A natural minor scale, tonic weighted as it would be in practice
+        let mut chroma = [0.0f32; 12];
+        for (d, weight) in [(0, 3.0), (2, 1.0), (3, 1.5), (5, 1.0), (7, 2.0), (8, 1.0), (10, 1.0)] {
+            chroma[(9 + d) % 12] = weight;
+        }
+        let key = estimate_key_from_chroma(&chroma);
+        assert_eq!(key.name(), "A minor");
+        assert!(key.confidence() > 0.0);
+    }
+
+    #[test]
+    fn test_correct_two_octave_scale_up_and_down() {
+        let expected = expected_notes(55, ScaleType::Major, 2, true);
+        let check = check_played(&notes(&expected), 55, ScaleType::Major, 2);
+        assert!(check.correct() && check.descending());
+        assert_eq!(check.degree_counts()[0], 5);
+        assert!((check.degree_cents()[3] - 5.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_wrong_note_and_order_are_reported() {
+        // D major with C natural instead of C#
+        let played = notes(&[62, 64, 66, 67, 69, 71, 72, 74]);
+        let check = check_played(&played, 62, ScaleType::Major, 1);
+        assert!(!check.notes_correct());
+        assert_eq!(check.wrong_notes(), vec![72]);
+        assert_eq!(check.missing_names(), vec!["C#5"]);
+
+        // Right notes, wrong order
+        let swapped = notes(&[62, 64, 67, 66, 69, 71, 73, 74]);
+        let check = check_played(&swapped, 62, ScaleType::Major, 1);
+        assert!(check.notes_correct() && !check.order_correct());
+    }
+
+    #[test]
+    fn test_identify_minor_variants() {
+        for scale in [ScaleType::NaturalMinor, ScaleType::HarmonicMinor, ScaleType::MelodicMinor] {
+            let played = notes(&expected_notes(57, scale, 1, true));
+            let found = identify_played(&played).unwrap();
+            assert_eq!(found.scale_type(), scale);
+            assert!(found.correct(), "{}", found.name());
+        }
+    }
+}
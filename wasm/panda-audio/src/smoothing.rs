@@ -0,0 +1,194 @@
+use wasm_bindgen::prelude::*;
+use std::collections::VecDeque;
+use crate::{frequency_to_midi, midi_to_frequency};
+
+// ============================================================================
+// Pitch Smoothing Strategies
+// ============================================================================
+
+/// Pitch smoothing strategy. All strategies work on a semitone (log-frequency)
+/// scale so their parameters mean the same thing across the whole range, and
+/// all of them start afresh after an unvoiced frame.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SmoothingMode {
+    /// Report every frame as detected
+    None,
+    /// Exponential blend with the previous value (the default). Unlike the
+    /// original detector, which blended in Hz and carried the last pitch
+    /// across silence, it blends semitones and does not glide in from the
+    /// previous note.
+    Exponential,
+    /// Median of the last few frames (rejects single-frame outliers)
+    Median,
+    /// One-euro filter: steady when the pitch holds, responsive when it moves
+    OneEuro,
+    /// Exponential blend that restarts as soon as a new note begins
+    NoteAware,
+}
+
+/// Stateful pitch smoother used by `PitchDetector`
+#[derive(Clone, Debug)]
+pub(crate) struct PitchSmoother {
+    pub(crate) mode: SmoothingMode,
+    /// Exponential weight of the newest frame (0.0 to 1.0)
+    pub(crate) alpha: f32,
+    /// Median window length in frames (odd)
+    pub(crate) median_window: usize,
+    /// One-euro minimum cutoff frequency (Hz)
+    pub(crate) min_cutoff: f32,
+    /// One-euro speed coefficient
+    pub(crate) beta: f32,
+    /// Jump (cents) treated as a new note by `NoteAware`
+    pub(crate) note_change_cents: f32,
+    /// Previous smoothed value (semitones)
+    prev: Option<f32>,
+    /// Previous one-euro derivative estimate (semitones per second)
+    prev_derivative: f32,
+    /// Recent raw values for the median filter (semitones)
+    recent: VecDeque<f32>,
+}
+
+/// One-euro derivative cutoff (Hz)
+const DERIVATIVE_CUTOFF: f32 = 1.0;
+
+fn smoothing_factor(cutoff: f32, dt: f32) -> f32 {
+    let tau = 1.0 / (2.0 * std::f32::consts::PI * cutoff);
+    1.0 / (1.0 + tau / dt)
+}
+
+impl PitchSmoother {
+    pub(crate) fn new() -> PitchSmoother {
+        PitchSmoother {
+            mode: SmoothingMode::Exponential,
+            alpha: 0.7,
+            median_window: 5,
+            min_cutoff: 1.0,
+            beta: 0.3,
+            note_change_cents: 50.0,
+            prev: None,
+            prev_derivative: 0.0,
+            recent: VecDeque::new(),
+        }
+    }
+
+    /// Forget all state (call on silence or between exercises)
+    pub(crate) fn reset(&mut self) {
+        self.prev = None;
+        self.prev_derivative = 0.0;
+        self.recent.clear();
+    }
+
+    /// Smooth one detected `frequency`; `dt` is the time since the last frame (seconds)
+    pub(crate) fn process(&mut self, frequency: f32, dt: f32) -> f32 {
+        let value = frequency_to_midi(frequency);
+        let smoothed = match (self.mode, self.prev) {
+            (SmoothingMode::None, _) => value,
+            (SmoothingMode::Median, _) => self.median(value),
+            (_, None) => value,
+            (SmoothingMode::Exponential, Some(prev)) => self.alpha * value + (1.0 - self.alpha) * prev,
+            (SmoothingMode::NoteAware, Some(prev)) => {
+                if (value - prev).abs() * 100.0 > self.note_change_cents {
+                    value
+                } else {
+                    self.alpha * value + (1.0 - self.alpha) * prev
+                }
+            }
+            (SmoothingMode::OneEuro, Some(prev)) => self.one_euro(value, prev, dt),
+        };
+        self.prev = Some(smoothed);
+        midi_to_frequency(smoothed)
+    }
+
+    fn median(&mut self, value: f32) -> f32 {
+        if self.recent.len() >= self.median_window {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(value);
+        let mut sorted: Vec<f32> = self.recent.iter().copied().collect();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        sorted[sorted.len() / 2]
+    }
+
+    fn one_euro(&mut self, value: f32, prev: f32, dt: f32) -> f32 {
+        let dt = dt.max(1e-4);
+        let derivative = (value - prev) / dt;
+        let d_alpha = smoothing_factor(DERIVATIVE_CUTOFF, dt);
+        let derivative = d_alpha * derivative + (1.0 - d_alpha) * self.prev_derivative;
+        self.prev_derivative = derivative;
+        let cutoff = self.min_cutoff + self.beta * derivative.abs();
+        let a = smoothing_factor(cutoff, dt);
+        a * value + (1.0 - a) * prev
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(smoother: &mut PitchSmoother, frequencies: &[f32]) -> Vec<f32> {
+        frequencies.iter().map(|&f| smoother.process(f, 0.0427)).collect()
+    }
+
+    #[test]
+    fn test_none_passes_through() {
+        let mut smoother = PitchSmoother::new();
+        smoother.mode = SmoothingMode::None;
+        let out = run(&mut smoother, &[440.0, 660.0]);
+        assert!((out[1] - 660.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_median_rejects_single_outlier() {
+        let mut smoother = PitchSmoother::new();
+        smoother.mode = SmoothingMode::Median;
+        smoother.median_window = 3;
+        let out = run(&mut smoother, &[440.0, 440.0, 880.0, 440.0]);
+        assert!((out[2] - 440.0).abs() < 0.01);
+        assert!((out[3] - 440.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_note_aware_snaps_to_new_note() {
+        let mut smoother = PitchSmoother::new();
+        smoother.mode = SmoothingMode::NoteAware;
+        let out = run(&mut smoother, &[440.0, 442.0, 587.33]);
+        assert!(out[1] > 440.0 && out[1] < 442.0);
+        assert!((out[2] - 587.33).abs() < 0.01);
+
+        let mut exponential = PitchSmoother::new();
+        let lagging = run(&mut exponential, &[440.0, 442.0, 587.33]);
+        assert!(lagging[2] < 580.0);
+    }
+
+    #[test]
+    fn test_one_euro_is_steady_and_responsive() {
+        let mut smoother = PitchSmoother::new();
+        smoother.mode = SmoothingMode::OneEuro;
+        // Small jitter is damped
+        let jitter = run(&mut smoother, &[440.0, 441.0, 439.0, 441.0, 439.0]);
+        assert!(jitter[1..].iter().all(|f| (f - 440.0).abs() < 0.8));
+
+        // A sustained new note is reached within a few frames
+        let mut moved = run(&mut smoother, &[660.0; 6]);
+        let last = moved.pop().unwrap();
+        assert!((last - 660.0).abs() < 5.0);
+    }
+
+    #[test]
+    fn test_exponential_blends_semitones() {
+        let mut smoother = PitchSmoother::new();
+        let out = run(&mut smoother, &[440.0, 880.0]);
+        // 0.7 of an octave above A4, not 0.7 of the way in Hz (748 Hz)
+        assert!((out[1] - 440.0 * 2f32.powf(0.7)).abs() < 0.1, "{}", out[1]);
+    }
+
+    #[test]
+    fn test_reset_forgets_previous_pitch() {
+        let mut smoother = PitchSmoother::new();
+        run(&mut smoother, &[440.0, 440.0]);
+        smoother.reset();
+        let out = run(&mut smoother, &[330.0]);
+        assert!((out[0] - 330.0).abs() < 0.01);
+    }
+}
@@ -0,0 +1,329 @@
+use wasm_bindgen::prelude::*;
+use crate::fft::SpectrumAnalyzer;
+use crate::preprocess::{preprocess_recording, PreprocessConfig};
+use crate::{clamp_sample_rate, frequency_to_midi};
+
+// ============================================================================
+// Spectrogram & Chroma Features
+// ============================================================================
+
+/// STFT window duration used for visual features (seconds)
+const WINDOW_SECONDS: f32 = 0.085;
+
+/// Dynamic range mapped onto the 0-255 spectrogram scale (dB)
+const SPECTROGRAM_RANGE_DB: f32 = 80.0;
+
+/// Largest STFT window; beyond 192 kHz the window covers less than 0.085 s
+const MAX_WINDOW_SIZE: usize = 16384;
+
+/// Largest number of columns and bands accepted from callers (far beyond any
+/// canvas), so the output stays a few MB however large the request
+const MAX_TIME_BINS: usize = 4096;
+const MAX_FREQ_BINS: usize = 1024;
+
+/// Frequency range folded into chroma (Hz)
+const CHROMA_MIN_FREQ: f32 = 60.0;
+const CHROMA_MAX_FREQ: f32 = 5000.0;
+
+/// Magnitude spectrogram on a log-frequency axis, quantized to bytes
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct Spectrogram {
+    time_bins: usize,
+    freq_bins: usize,
+    /// Seconds between successive time bins
+    hop_seconds: f32,
+    /// Lower edge of the first band (Hz)
+    min_freq: f32,
+    /// Upper edge of the last band (Hz)
+    max_freq: f32,
+    /// Row-major `[time][freq]` levels: 0 = -80 dB or quieter, 255 = loudest cell
+    values: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl Spectrogram {
+    #[wasm_bindgen(getter)]
+    pub fn time_bins(&self) -> usize { self.time_bins }
+
+    #[wasm_bindgen(getter)]
+    pub fn freq_bins(&self) -> usize { self.freq_bins }
+
+    #[wasm_bindgen(getter)]
+    pub fn hop_seconds(&self) -> f32 { self.hop_seconds }
+
+    #[wasm_bindgen(getter)]
+    pub fn values(&self) -> Vec<u8> { self.values.clone() }
+
+    /// Centre frequency (Hz) of band `index`
+    #[wasm_bindgen]
+    pub fn band_frequency(&self, index: usize) -> f32 {
+        let ratio = self.max_freq / self.min_freq;
+        self.min_freq * ratio.powf((index as f32 + 0.5) / self.freq_bins as f32)
+    }
+}
+
+/// 12-bin pitch-class energy per time bin (C = 0 ... B = 11)
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct Chromagram {
+    time_bins: usize,
+    hop_seconds: f32,
+    /// Row-major `[time][pitch class]`, each row scaled so its maximum is 1.0
+    values: Vec<f32>,
+    /// Per time bin: whether any energy was present
+    voiced: Vec<bool>,
+}
+
+#[wasm_bindgen]
+impl Chromagram {
+    #[wasm_bindgen(getter)]
+    pub fn time_bins(&self) -> usize { self.time_bins }
+
+    #[wasm_bindgen(getter)]
+    pub fn hop_seconds(&self) -> f32 { self.hop_seconds }
+
+    #[wasm_bindgen(getter)]
+    pub fn values(&self) -> Vec<f32> { self.values.clone() }
+
+    /// Strongest pitch class per time bin (-1 for silent bins)
+    #[wasm_bindgen]
+    pub fn dominant_pitch_classes(&self) -> Vec<i8> {
+        (0..self.time_bins)
+            .map(|t| {
+                if !self.voiced[t] {
+                    return -1;
+                }
+                let row = &self.values[t * 12..(t + 1) * 12];
+                row.iter()
+                    .enumerate()
+                    .max_by(|a, b| a.1.total_cmp(b.1))
+                    .map_or(-1, |(pc, _)| pc as i8)
+            })
+            .collect()
+    }
+
+    /// Mean cosine similarity (0.0 to 1.0) against another chromagram, such as
+    /// a teacher's demo, after stretching both to the same number of time bins.
+    /// Bins that are silent in both are ignored.
+    #[wasm_bindgen]
+    pub fn similarity(&self, other: &Chromagram) -> f32 {
+        let bins = self.time_bins.max(other.time_bins);
+        if bins == 0 || self.time_bins == 0 || other.time_bins == 0 {
+            return 0.0;
+        }
+        let mut total = 0.0;
+        let mut counted = 0;
+        for t in 0..bins {
+            let a = t * self.time_bins / bins;
+            let b = t * other.time_bins / bins;
+            if !self.voiced[a] && !other.voiced[b] {
+                continue;
+            }
+            let row_a = &self.values[a * 12..(a + 1) * 12];
+            let row_b = &other.values[b * 12..(b + 1) * 12];
+            total += cosine_similarity(row_a, row_b);
+            counted += 1;
+        }
+        if counted == 0 { 0.0 } else { total / counted as f32 }
+    }
+}
+
+pub(crate) fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b: f32 = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a > 0.0 && norm_b > 0.0 { dot / (norm_a * norm_b) } else { 0.0 }
+}
+
+/// Window size for visual features at this sample rate
+fn window_size(sample_rate: f32) -> usize {
+    ((sample_rate * WINDOW_SECONDS) as usize).next_power_of_two().min(MAX_WINDOW_SIZE)
+}
+
+/// Sample index at the centre of time bin `t` of `time_bins`
+fn bin_center(t: usize, time_bins: usize, len: usize) -> usize {
+    ((t as f64 + 0.5) * len as f64 / time_bins as f64) as usize
+}
+
+/// Compute a log-frequency magnitude spectrogram of `samples` (after the
+/// default pre-processing, so DC and rumble do not dominate the low bands)
+///
+/// # Arguments
+/// * `samples` - Mono PCM samples
+/// * `sample_rate` - Sample rate of `samples`
+/// * `time_bins` - Number of columns (e.g., canvas width), at most 4096
+/// * `freq_bins` - Number of log-spaced bands (e.g., canvas height), at most 1024
+/// * `min_freq` - Lower edge of the lowest band (Hz)
+/// * `max_freq` - Upper edge of the highest band (Hz)
+#[wasm_bindgen]
+pub fn compute_spectrogram(
+    samples: &[f32],
+    sample_rate: f32,
+    time_bins: usize,
+    freq_bins: usize,
+    min_freq: f32,
+    max_freq: f32,
+) -> Spectrogram {
+    let sample_rate = clamp_sample_rate(sample_rate);
+    let time_bins = time_bins.min(MAX_TIME_BINS);
+    let freq_bins = freq_bins.min(MAX_FREQ_BINS);
+    let Some(cells) = time_bins.checked_mul(freq_bins) else {
+        return Spectrogram { time_bins: 0, freq_bins: 0, hop_seconds: 0.0, min_freq: 1.0, max_freq: 1.01, values: Vec::new() };
+    };
+    let samples = &preprocess_recording(samples, sample_rate, &PreprocessConfig::new());
+    let nyquist = sample_rate / 2.0;
+    // `max`/`min` rather than `clamp`: a NaN bound falls back to the limit
+    let min_freq = min_freq.max(1.0).min(nyquist);
+    let max_freq = max_freq.max(min_freq * 1.01).min(nyquist);
+    let hop_seconds = if time_bins > 0 {
+        samples.len() as f32 / sample_rate / time_bins as f32
+    } else {
+        0.0
+    };
+
+    let mut analyzer = SpectrumAnalyzer::new(window_size(sample_rate));
+    let bin_hz = sample_rate / analyzer.fft_size() as f32;
+
+    // Band edges in FFT bins; every band covers at least one bin
+    let ratio = max_freq / min_freq;
+    let edges: Vec<usize> = (0..=freq_bins)
+        .map(|i| (min_freq * ratio.powf(i as f32 / freq_bins as f32) / bin_hz).round() as usize)
+        .collect();
+
+    let mut levels = vec![0.0f32; cells];
+    let mut mags = Vec::new();
+    let mut loudest = 0.0f32;
+    for t in 0..time_bins {
+        analyzer.magnitudes(samples, bin_center(t, time_bins, samples.len()), &mut mags);
+        for band in 0..freq_bins {
+            let lo = edges[band].min(mags.len() - 1);
+            let hi = edges[band + 1].clamp(lo + 1, mags.len());
+            let peak = mags[lo..hi].iter().copied().fold(0.0, f32::max);
+            levels[t * freq_bins + band] = peak;
+            loudest = loudest.max(peak);
+        }
+    }
+
+    let values = levels
+        .iter()
+        .map(|&mag| {
+            if loudest <= 0.0 || mag <= 0.0 {
+                return 0;
+            }
+            let db = 20.0 * (mag / loudest).log10();
+            ((1.0 + db / SPECTROGRAM_RANGE_DB).clamp(0.0, 1.0) * 255.0).round() as u8
+        })
+        .collect();
+
+    Spectrogram { time_bins, freq_bins, hop_seconds, min_freq, max_freq, values }
+}
+
+/// Compute a 12-bin chromagram of `samples` with `time_bins` columns (at
+/// most 4096), after the default pre-processing
+#[wasm_bindgen]
+pub fn compute_chroma(samples: &[f32], sample_rate: f32, time_bins: usize) -> Chromagram {
+    let sample_rate = clamp_sample_rate(sample_rate);
+    let time_bins = time_bins.min(MAX_TIME_BINS);
+    let Some(cells) = time_bins.checked_mul(12) else {
+        return Chromagram { time_bins: 0, hop_seconds: 0.0, values: Vec::new(), voiced: Vec::new() };
+    };
+    let samples = &preprocess_recording(samples, sample_rate, &PreprocessConfig::new());
+    let hop_seconds = if time_bins > 0 {
+        samples.len() as f32 / sample_rate / time_bins as f32
+    } else {
+        0.0
+    };
+
+    let mut analyzer = SpectrumAnalyzer::new(window_size(sample_rate));
+    let bin_hz = sample_rate / analyzer.fft_size() as f32;
+    let max_freq = CHROMA_MAX_FREQ.min(sample_rate / 2.0);
+
+    // Pitch class of each FFT bin inside the chroma range
+    let half = analyzer.fft_size() / 2;
+    let pitch_classes: Vec<Option<usize>> = (0..=half)
+        .map(|k| {
+            let freq = k as f32 * bin_hz;
+            if (CHROMA_MIN_FREQ..=max_freq).contains(&freq) {
+                Some((frequency_to_midi(freq).round() as i32).rem_euclid(12) as usize)
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    let mut values = vec![0.0f32; cells];
+    let mut voiced = vec![false; time_bins];
+    let mut mags = Vec::new();
+    for t in 0..time_bins {
+        analyzer.magnitudes(samples, bin_center(t, time_bins, samples.len()), &mut mags);
+        let row = &mut values[t * 12..(t + 1) * 12];
+        for (k, &mag) in mags.iter().enumerate() {
+            if let Some(pc) = pitch_classes[k] {
+                row[pc] += mag * mag;
+            }
+        }
+        let peak = row.iter().copied().fold(0.0, f32::max);
+        // Ignore numerical noise in digital silence
+        if peak > 1e-9 {
+            voiced[t] = true;
+            row.iter_mut().for_each(|x| *x /= peak);
+        } else {
+            row.iter_mut().for_each(|x| *x = 0.0);
+        }
+    }
+
+    Chromagram { time_bins, hop_seconds, values, voiced }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_signals::sine;
+
+    fn tone(frequency: f32, seconds: f32, sample_rate: f32) -> Vec<f32> {
+        sine(frequency, 0.3, (seconds * sample_rate) as usize, sample_rate)
+    }
+
+    #[test]
+    fn test_spectrogram_peak_band() {
+        let samples = tone(440.0, 1.0, 48000.0);
+        let spec = compute_spectrogram(&samples, 48000.0, 20, 64, 100.0, 4000.0);
+        assert_eq!(spec.values().len(), 20 * 64);
+
+        let row = &spec.values()[10 * 64..11 * 64];
+        let peak_band = row.iter().enumerate().max_by_key(|(_, v)| **v).unwrap().0;
+        let centre = spec.band_frequency(peak_band);
+        assert!((centre / 440.0).log2().abs() < 0.1, "peak band at {centre} Hz");
+    }
+
+    #[test]
+    fn test_chroma_pitch_class_and_similarity() {
+        let a = compute_chroma(&tone(440.0, 1.0, 44100.0), 44100.0, 10);
+        let a_octave = compute_chroma(&tone(880.0, 0.5, 44100.0), 44100.0, 5);
+        let d = compute_chroma(&tone(587.33, 1.0, 44100.0), 44100.0, 10);
+
+        assert!(a.dominant_pitch_classes().iter().all(|&pc| pc == 9));
+        assert!(d.dominant_pitch_classes().iter().all(|&pc| pc == 2));
+        assert!(a.similarity(&a_octave) > 0.9);
+        assert!(a.similarity(&d) < 0.5);
+    }
+
+    #[test]
+    fn test_oversized_requests_are_clamped() {
+        let samples = tone(440.0, 0.1, 8000.0);
+        let spec = compute_spectrogram(&samples, 8000.0, usize::MAX, usize::MAX, 100.0, 4000.0);
+        assert_eq!((spec.time_bins(), spec.freq_bins()), (MAX_TIME_BINS, MAX_FREQ_BINS));
+        assert_eq!(spec.values().len(), MAX_TIME_BINS * MAX_FREQ_BINS);
+
+        let chroma = compute_chroma(&samples, 8000.0, usize::MAX);
+        assert_eq!(chroma.time_bins(), MAX_TIME_BINS);
+        assert_eq!(window_size(384_000.0), MAX_WINDOW_SIZE);
+    }
+
+    #[test]
+    fn test_silence_chroma() {
+        let chroma = compute_chroma(&vec![0.0; 4800], 48000.0, 4);
+        assert_eq!(chroma.dominant_pitch_classes(), vec![-1; 4]);
+    }
+}
@@ -0,0 +1,180 @@
+//! Synthetic test signals shared by the unit tests, the integration tests,
+//! the benchmarks and the corpus generator.
+//!
+//! Self-contained (no `crate::` paths) so the integration tests, benchmarks
+//! and `panda-cli`'s `make_corpus` example can include it with `#[path]`.
+
+#![allow(dead_code)]
+
+use std::f32::consts::PI;
+
+/// Deterministic pseudo-random generator (LCG) so failures are reproducible
+#[derive(Clone, Debug)]
+pub struct Noise(u32);
+
+impl Noise {
+    pub fn new(seed: u32) -> Noise {
+        Noise(seed)
+    }
+
+    /// Uniform value in -1.0..1.0
+    pub fn next(&mut self) -> f32 {
+        self.0 = self.0.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+        (self.0 >> 8) as f32 / (1u32 << 23) as f32 - 1.0
+    }
+}
+
+/// `len` samples of uniform noise in `-amplitude..amplitude`
+pub fn noise(len: usize, amplitude: f32, seed: u32) -> Vec<f32> {
+    let mut noise = Noise::new(seed);
+    (0..len).map(|_| noise.next() * amplitude).collect()
+}
+
+/// `len` samples of a pure sine
+pub fn sine(frequency: f32, amplitude: f32, len: usize, sample_rate: f32) -> Vec<f32> {
+    (0..len)
+        .map(|i| amplitude * (2.0 * PI * frequency * i as f32 / sample_rate).sin())
+        .collect()
+}
+
+/// Parameters of a synthetic bowed-string tone
+#[derive(Clone, Debug)]
+pub struct ViolinTone {
+    pub frequency: f32,
+    pub sample_rate: f32,
+    /// Peak amplitude of the summed harmonics
+    pub amplitude: f32,
+    /// Vibrato rate (Hz) and depth (cents, peak)
+    pub vibrato_rate: f32,
+    pub vibrato_cents: f32,
+    /// White noise level relative to `amplitude`
+    pub noise: f32,
+    /// Bow-scrape level: bursts of band-limited noise with jittered period
+    pub scrape: f32,
+    pub seed: u32,
+}
+
+impl ViolinTone {
+    pub fn new(frequency: f32, sample_rate: f32) -> ViolinTone {
+        ViolinTone {
+            frequency,
+            sample_rate,
+            amplitude: 0.3,
+            vibrato_rate: 0.0,
+            vibrato_cents: 0.0,
+            noise: 0.0,
+            scrape: 0.0,
+            seed: 1,
+        }
+    }
+
+    pub fn vibrato(mut self, rate: f32, cents: f32) -> ViolinTone {
+        self.vibrato_rate = rate;
+        self.vibrato_cents = cents;
+        self
+    }
+
+    pub fn noise(mut self, level: f32) -> ViolinTone {
+        self.noise = level;
+        self
+    }
+
+    pub fn scrape(mut self, level: f32) -> ViolinTone {
+        self.scrape = level;
+        self
+    }
+
+    pub fn seed(mut self, seed: u32) -> ViolinTone {
+        self.seed = seed;
+        self
+    }
+
+    /// Harmonic amplitudes: sawtooth-like (Helmholtz motion) with a body
+    /// resonance boost around 3 kHz and nothing above Nyquist
+    fn harmonics(&self, frequency: f32) -> Vec<f32> {
+        let nyquist = self.sample_rate / 2.0;
+        (1..=20)
+            .map(|n| {
+                let f = frequency * n as f32;
+                if f >= nyquist * 0.95 {
+                    return 0.0;
+                }
+                let body = 1.0 + 0.8 * (-((f - 3000.0) / 1200.0).powi(2)).exp();
+                body / n as f32
+            })
+            .collect()
+    }
+
+    /// Instantaneous frequency at time `t` (seconds)
+    pub fn frequency_at(&self, t: f32) -> f32 {
+        let cents = self.vibrato_cents * (2.0 * PI * self.vibrato_rate * t).sin();
+        self.frequency * 2f32.powf(cents / 1200.0)
+    }
+
+    /// Mean pitch over `len` samples starting at `start`, in cents relative
+    /// to `frequency` (what a detector should report for that window)
+    pub fn mean_cents(&self, start: usize, len: usize) -> f32 {
+        let sum: f32 = (start..start + len)
+            .map(|i| 1200.0 * (self.frequency_at(i as f32 / self.sample_rate) / self.frequency).log2())
+            .sum();
+        sum / len as f32
+    }
+
+    /// Render `len` samples starting `start` samples into the tone
+    pub fn render(&self, start: usize, len: usize) -> Vec<f32> {
+        // Leave headroom so vibrato never pushes a partial past Nyquist
+        let harmonics = self.harmonics(self.frequency * 1.06);
+        let norm: f32 = harmonics.iter().sum::<f32>().max(1e-6);
+        let mut noise = Noise::new(self.seed);
+        let mut phase = 0.0f32;
+        let mut scrape_env = 0.0f32;
+        let mut scrape_lp = 0.0f32;
+        let mut out = Vec::with_capacity(len);
+
+        for i in 0..start + len {
+            let t = i as f32 / self.sample_rate;
+            phase += 2.0 * PI * self.frequency_at(t) / self.sample_rate;
+            if phase > 2.0 * PI * 1000.0 {
+                phase -= 2.0 * PI * 1000.0;
+            }
+            let white = noise.next();
+            let jitter = noise.next();
+            if i < start {
+                continue;
+            }
+
+            let mut sample = 0.0;
+            for (n, &a) in harmonics.iter().enumerate() {
+                if a > 0.0 {
+                    sample += a * ((n + 1) as f32 * phase).sin();
+                }
+            }
+            sample *= self.amplitude / norm;
+
+            if self.noise > 0.0 {
+                sample += self.noise * self.amplitude * white;
+            }
+            if self.scrape > 0.0 {
+                // Short slip-stick bursts roughly every 30 ms with random spacing
+                if jitter > 0.9993 {
+                    scrape_env = 1.0;
+                }
+                scrape_env *= 0.995;
+                scrape_lp += 0.3 * (white - scrape_lp);
+                sample += self.scrape * self.amplitude * scrape_env * scrape_lp * 3.0;
+            }
+            out.push(sample);
+        }
+        out
+    }
+}
+
+/// MIDI note to frequency (A4 = 440 Hz)
+pub fn midi_to_hz(midi: i32) -> f32 {
+    440.0 * 2f32.powf((midi - 69) as f32 / 12.0)
+}
+
+/// Signed cents from `reference` to `frequency`
+pub fn cents_between(frequency: f32, reference: f32) -> f32 {
+    1200.0 * (frequency / reference).log2()
+}
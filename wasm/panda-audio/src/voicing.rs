@@ -0,0 +1,290 @@
+use crate::fft::SpectrumAnalyzer;
+
+// ============================================================================
+// Calibrated Voicing Probability
+// ============================================================================
+
+/// Logistic model weights: bias, NSDF peak, loudness, spectral flatness and
+/// its square. Flatness enters as a quadratic because a tone the pitch search
+/// misses under bow noise sits between tonal and white spectra, while room
+/// noise is flatter and breath (low-passed noise) less flat.
+///
+/// Maximum-likelihood fit (`fit_voicing_weights` in the tests) on 12000
+/// synthetic frames at 1024-4096 samples and 22.05-48 kHz: violin tones from
+/// G3 to E6, -46 to -6 dBFS, with random vibrato, bow scrape and white noise
+/// up to 0.8x the tone level (voiced), against white and low-passed noise at
+/// the same levels (unvoiced). About 7000 frames clear the volume threshold;
+/// quieter frames are reported as 0 without the model.
+const BIAS: f32 = -16.98;
+const W_PEAK: f32 = 28.71;
+const W_LOUDNESS: f32 = 1.87;
+const W_FLATNESS: f32 = 100.63;
+const W_FLATNESS_SQ: f32 = -154.86;
+
+/// Band used for spectral flatness (Hz)
+const FLATNESS_MIN_FREQ: f32 = 100.0;
+const FLATNESS_MAX_FREQ: f32 = 5000.0;
+
+fn sigmoid(z: f32) -> f32 {
+    1.0 / (1.0 + (-z).exp())
+}
+
+/// Computes the probability that a buffer contains a sounding note
+pub(crate) struct VoicingModel {
+    analyzer: SpectrumAnalyzer,
+    magnitudes: Vec<f32>,
+}
+
+impl VoicingModel {
+    pub(crate) fn new(buffer_size: usize) -> VoicingModel {
+        VoicingModel {
+            analyzer: SpectrumAnalyzer::new(buffer_size),
+            magnitudes: Vec::new(),
+        }
+    }
+
+    /// Spectral flatness (0 = pure tone, 1 = white noise) between 100 Hz and
+    /// 5 kHz, or Nyquist if lower
+    pub(crate) fn spectral_flatness(&mut self, buffer: &[f32], sample_rate: f32) -> f32 {
+        if self.analyzer.fft_size() < buffer.len() {
+            self.analyzer = SpectrumAnalyzer::new(buffer.len());
+        }
+        self.analyzer.magnitudes(buffer, buffer.len() / 2, &mut self.magnitudes);
+
+        let bin_hz = sample_rate / self.analyzer.fft_size() as f32;
+        let lo = ((FLATNESS_MIN_FREQ / bin_hz) as usize).max(1);
+        let hi = ((FLATNESS_MAX_FREQ / bin_hz) as usize).min(self.magnitudes.len() - 1);
+        if lo >= hi {
+            return 1.0;
+        }
+
+        let band = &self.magnitudes[lo..=hi];
+        let count = band.len() as f32;
+        let mut log_sum = 0.0f32;
+        let mut sum = 0.0f32;
+        for &mag in band {
+            let power = mag * mag + 1e-12;
+            log_sum += power.ln();
+            sum += power;
+        }
+        ((log_sum / count).exp() / (sum / count)).clamp(0.0, 1.0)
+    }
+
+    /// Model inputs: NSDF peak, loudness and spectral flatness
+    fn features(&mut self, buffer: &[f32], sample_rate: f32, rms: f32, nsdf_peak: f32) -> [f32; 3] {
+        let db = 20.0 * rms.log10();
+        // -40 dBFS maps to 0, each further 20 dB adds 1
+        let loudness = ((db + 40.0) / 20.0).clamp(-2.0, 2.0);
+        let flatness = self.spectral_flatness(buffer, sample_rate);
+        [nsdf_peak.clamp(0.0, 1.0), loudness, flatness]
+    }
+
+    /// Probability (0.0 to 1.0) that a note is sounding
+    ///
+    /// # Arguments
+    /// * `buffer` - The frame, or a decimated copy of it (flatness only needs
+    ///   the band up to 5 kHz, so the detector passes its 1/4-rate buffer)
+    /// * `sample_rate` - Sample rate of `buffer`
+    /// * `rms` - RMS volume of the frame
+    /// * `nsdf_peak` - Autocorrelation peak (0 if none was found)
+    pub(crate) fn probability(&mut self, buffer: &[f32], sample_rate: f32, rms: f32, nsdf_peak: f32) -> f32 {
+        if rms <= 0.0 || buffer.is_empty() {
+            return 0.0;
+        }
+        let [peak, loudness, flatness] = self.features(buffer, sample_rate, rms, nsdf_peak);
+        sigmoid(
+            BIAS + W_PEAK * peak + W_LOUDNESS * loudness + W_FLATNESS * flatness + W_FLATNESS_SQ * flatness * flatness,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_signals::{midi_to_hz, noise, sine, Noise, ViolinTone};
+    use crate::{PitchDetector, DOWNSAMPLE_STRIDE};
+
+    /// One frame of the labelled voicing set
+    struct Frame {
+        samples: Vec<f32>,
+        sample_rate: f32,
+        voiced: bool,
+    }
+
+    /// Uniform value in 0.0..1.0
+    fn unit(rng: &mut Noise) -> f32 {
+        (rng.next() + 1.0) / 2.0
+    }
+
+    fn pick<T: Copy>(rng: &mut Noise, values: &[T]) -> T {
+        values[((unit(rng) * values.len() as f32) as usize).min(values.len() - 1)]
+    }
+
+    /// `count` labelled frames at 1024/2048/4096 samples and 22.05/44.1/48 kHz.
+    /// Half are violin tones from G3 to E6 between -46 and -6 dBFS peak, with
+    /// random vibrato, bow scrape and white noise from none up to 0.8x the
+    /// tone's level (voiced). The rest are white or low-passed noise (room
+    /// noise, breath) over the same levels (unvoiced).
+    fn labelled_frames(count: usize, seed: u32) -> Vec<Frame> {
+        let mut rng = Noise::new(seed);
+        (0..count)
+            .map(|i| {
+                let sample_rate = pick(&mut rng, &[22050.0, 44100.0, 48000.0]);
+                let size = pick(&mut rng, &[1024, 2048, 4096]);
+                let level = 10f32.powf(-2.3 + 2.0 * unit(&mut rng));
+                let seed = rng.next().to_bits();
+                if i % 2 == 0 {
+                    let midi = 55 + (unit(&mut rng) * 34.0) as i32;
+                    let mut tone = ViolinTone::new(midi_to_hz(midi), sample_rate)
+                        .vibrato(5.0 + 2.0 * unit(&mut rng), 30.0 * unit(&mut rng))
+                        .scrape(0.5 * unit(&mut rng))
+                        .noise(pick(&mut rng, &[0.0, 0.05, 0.1, 0.2, 0.5, 0.8]))
+                        .seed(seed);
+                    tone.amplitude = level;
+                    Frame { samples: tone.render(size, size), sample_rate, voiced: true }
+                } else {
+                    let mut samples = noise(size, level, seed);
+                    if unit(&mut rng) < 0.5 {
+                        let alpha = 0.02 + 0.3 * unit(&mut rng);
+                        let mut state = 0.0;
+                        for x in &mut samples {
+                            state += alpha * (*x - state);
+                            *x = state;
+                        }
+                    }
+                    Frame { samples, sample_rate, voiced: false }
+                }
+            })
+            .collect()
+    }
+
+    /// Model features and voicing probability of `frame` as the detector sees
+    /// it, or None if it is below the volume threshold (reported as p = 0
+    /// before the model runs)
+    fn evaluate(frame: &Frame) -> Option<([f32; 3], f32)> {
+        let mut detector = PitchDetector::new(frame.sample_rate, frame.samples.len());
+        let result = detector.detect(&frame.samples);
+        if result.volume() < detector.volume_threshold {
+            return None;
+        }
+        let downsampled = &detector.downsampled[..frame.samples.len() / DOWNSAMPLE_STRIDE];
+        let features = detector.voicing.features(
+            downsampled,
+            frame.sample_rate / DOWNSAMPLE_STRIDE as f32,
+            result.volume(),
+            result.confidence(),
+        );
+        Some((features, result.voicing_probability()))
+    }
+
+    /// Terms of the model: bias, peak, loudness, flatness and flatness squared
+    const TERMS: usize = 5;
+
+    fn terms([peak, loudness, flatness]: [f32; 3]) -> [f64; TERMS] {
+        let (peak, loudness, flatness) = (peak as f64, loudness as f64, flatness as f64);
+        [1.0, peak, loudness, flatness, flatness * flatness]
+    }
+
+    /// Solve `a x = b` (Gaussian elimination, partial pivoting)
+    fn solve(mut a: [[f64; TERMS]; TERMS], mut b: [f64; TERMS]) -> [f64; TERMS] {
+        for col in 0..TERMS {
+            let pivot = (col..TERMS).max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs())).unwrap();
+            a.swap(col, pivot);
+            b.swap(col, pivot);
+            let pivot_row = a[col];
+            for row in col + 1..TERMS {
+                let factor = a[row][col] / pivot_row[col];
+                for (x, &p) in a[row][col..].iter_mut().zip(&pivot_row[col..]) {
+                    *x -= factor * p;
+                }
+                b[row] -= factor * b[col];
+            }
+        }
+        let mut x = [0.0; TERMS];
+        for row in (0..TERMS).rev() {
+            let tail: f64 = (row + 1..TERMS).map(|k| a[row][k] * x[k]).sum();
+            x[row] = (b[row] - tail) / a[row][row];
+        }
+        x
+    }
+
+    /// Maximum-likelihood logistic regression (Newton's method)
+    fn fit_logistic(samples: &[([f32; 3], bool)]) -> [f64; TERMS] {
+        let mut w = [0.0f64; TERMS];
+        for _ in 0..30 {
+            let mut gradient = [0.0; TERMS];
+            let mut hessian = [[0.0; TERMS]; TERMS];
+            for &(features, voiced) in samples {
+                let v = terms(features);
+                let z: f64 = (0..TERMS).map(|k| w[k] * v[k]).sum();
+                let p = 1.0 / (1.0 + (-z).exp());
+                let target = if voiced { 1.0 } else { 0.0 };
+                for j in 0..TERMS {
+                    gradient[j] += (p - target) * v[j];
+                    for k in 0..TERMS {
+                        hessian[j][k] += p * (1.0 - p) * v[j] * v[k];
+                    }
+                }
+            }
+            // A little ridge keeps the fit finite if the set separates
+            for j in 0..TERMS {
+                gradient[j] += 1e-3 * w[j];
+                hessian[j][j] += 1e-3;
+            }
+            let step = solve(hessian, gradient);
+            for j in 0..TERMS {
+                w[j] -= step[j];
+            }
+        }
+        w
+    }
+
+    /// Refit the model weights on the labelled set:
+    /// `cargo test -p panda-audio --lib fit_voicing_weights -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    fn fit_voicing_weights() {
+        let samples: Vec<([f32; 3], bool)> = labelled_frames(12000, 2024)
+            .iter()
+            .filter_map(|frame| evaluate(frame).map(|(features, _)| (features, frame.voiced)))
+            .collect();
+        let [bias, peak, loudness, flatness, flatness_sq] = fit_logistic(&samples);
+        println!("{} frames above the volume threshold", samples.len());
+        println!("const BIAS: f32 = {bias:.2};");
+        println!("const W_PEAK: f32 = {peak:.2};");
+        println!("const W_LOUDNESS: f32 = {loudness:.2};");
+        println!("const W_FLATNESS: f32 = {flatness:.2};");
+        println!("const W_FLATNESS_SQ: f32 = {flatness_sq:.2};");
+    }
+
+    #[test]
+    fn test_probability_is_calibrated() {
+        // On frames held out from the fit (another seed), the frames given
+        // p in each fifth of 0..1 are voiced about that often
+        let mut bins = [(0usize, 0.0f32, 0usize); 5];
+        for frame in labelled_frames(4000, 77) {
+            let Some((_, p)) = evaluate(&frame) else { continue };
+            let bin = &mut bins[((p * 5.0) as usize).min(4)];
+            bin.0 += 1;
+            bin.1 += p;
+            bin.2 += frame.voiced as usize;
+        }
+        for (i, &(frames, p_sum, voiced)) in bins.iter().enumerate() {
+            assert!(frames >= 40, "bin {i}: only {frames} frames");
+            let mean_p = p_sum / frames as f32;
+            let voiced_rate = voiced as f32 / frames as f32;
+            assert!(
+                (mean_p - voiced_rate).abs() <= 0.1,
+                "bin {i}: mean p {mean_p:.2} but {voiced_rate:.2} voiced over {frames} frames"
+            );
+        }
+    }
+
+    #[test]
+    fn test_flatness_separates_tone_and_noise() {
+        let tone = sine(440.0, 1.0, 2048, 48000.0);
+        let mut model = VoicingModel::new(2048);
+        assert!(model.spectral_flatness(&tone, 48000.0) < 0.05);
+        assert!(model.spectral_flatness(&noise(2048, 0.5, 12345), 48000.0) > 0.4);
+    }
+}
@@ -0,0 +1,123 @@
+//! Accuracy harness: runs `PitchDetector::detect` on synthetic violin-like
+//! signals across the whole violin range and checks gross-error rate and
+//! cents accuracy per condition.
+//!
+//! To print the per-condition summary when tuning the detector:
+//!
+//! ```text
+//! PANDA_ACCURACY_REPORT=1 cargo test -p panda-audio --test accuracy -- --nocapture
+//! ```
+
+mod common;
+
+use common::{cents_between, midi_to_hz, ViolinTone};
+use panda_audio::{PitchDetector, SmoothingMode};
+
+/// G3 to E6, the range the default detector is tuned for
+const LOWEST_MIDI: i32 = 55;
+const HIGHEST_MIDI: i32 = 88;
+/// Frames analysed per note (at different offsets into the tone)
+const FRAMES_PER_NOTE: usize = 4;
+/// An estimate this far from the truth is a gross (octave/fifth/garbage) error
+const GROSS_ERROR_CENTS: f32 = 50.0;
+
+#[derive(Default, Debug)]
+struct Stats {
+    frames: usize,
+    unvoiced: usize,
+    gross: usize,
+    abs_cents: Vec<f32>,
+    worst: Vec<String>,
+}
+
+impl Stats {
+    fn gross_rate(&self) -> f32 {
+        (self.gross + self.unvoiced) as f32 / self.frames.max(1) as f32
+    }
+
+    fn percentile_cents(&self, p: f32) -> f32 {
+        let mut sorted = self.abs_cents.clone();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        sorted.get(((sorted.len() as f32 - 1.0) * p) as usize).copied().unwrap_or(0.0)
+    }
+}
+
+/// Detect every note in the range under one condition
+fn run(sample_rate: f32, make: impl Fn(f32) -> ViolinTone) -> Stats {
+    let buffer_size = if sample_rate > 50_000.0 { 4096 } else { 2048 };
+    let mut detector = PitchDetector::new(sample_rate, buffer_size);
+    detector.set_smoothing(SmoothingMode::None);
+    let mut stats = Stats::default();
+
+    for midi in LOWEST_MIDI..=HIGHEST_MIDI {
+        let frequency = midi_to_hz(midi);
+        let tone = make(frequency).seed(midi as u32 * 7919);
+        detector.reset();
+        for k in 0..FRAMES_PER_NOTE {
+            let start = buffer_size + k * (sample_rate as usize / 11);
+            let buffer = tone.render(start, buffer_size);
+            let truth = tone.mean_cents(start, buffer_size);
+            let result = detector.detect(&buffer);
+
+            stats.frames += 1;
+            if result.frequency() <= 0.0 {
+                stats.unvoiced += 1;
+                stats.worst.push(format!("midi {midi} frame {k}: unvoiced"));
+                continue;
+            }
+            let error = cents_between(result.frequency(), frequency) - truth;
+            if error.abs() > GROSS_ERROR_CENTS {
+                stats.gross += 1;
+                stats.worst.push(format!("midi {midi} frame {k}: {:.1} Hz ({error:+.0} cents)", result.frequency()));
+            } else {
+                stats.abs_cents.push(error.abs());
+            }
+        }
+    }
+    stats
+}
+
+/// Assert the gross-error rate and the median/95th-percentile cents error
+fn check(name: &str, stats: &Stats, max_gross_rate: f32, max_median: f32, max_p95: f32) {
+    let median = stats.percentile_cents(0.5);
+    let p95 = stats.percentile_cents(0.95);
+    let summary = format!(
+        "{name}: gross {:.1}% ({} of {}), median {median:.2} cents, p95 {p95:.2} cents\n  {}",
+        stats.gross_rate() * 100.0,
+        stats.gross + stats.unvoiced,
+        stats.frames,
+        stats.worst.join("\n  "),
+    );
+    if std::env::var_os("PANDA_ACCURACY_REPORT").is_some() {
+        eprintln!("{}", summary.lines().next().unwrap_or_default());
+    }
+    assert!(stats.gross_rate() <= max_gross_rate, "{summary}");
+    assert!(median <= max_median, "{summary}");
+    assert!(p95 <= max_p95, "{summary}");
+}
+
+#[test]
+fn clean_tones_across_sample_rates() {
+    for sample_rate in [22050.0, 44100.0, 48000.0, 96000.0] {
+        let stats = run(sample_rate, |f| ViolinTone::new(f, sample_rate));
+        check(&format!("clean @ {sample_rate}"), &stats, 0.0, 1.0, 4.0);
+    }
+}
+
+#[test]
+fn vibrato() {
+    let stats = run(48000.0, |f| ViolinTone::new(f, 48000.0).vibrato(5.5, 25.0));
+    check("vibrato", &stats, 0.0, 3.0, 8.0);
+}
+
+#[test]
+fn background_noise() {
+    let stats = run(48000.0, |f| ViolinTone::new(f, 48000.0).noise(0.1));
+    check("noise -20 dB", &stats, 0.02, 2.0, 6.0);
+}
+
+#[test]
+fn bow_scrape() {
+    let stats = run(44100.0, |f| ViolinTone::new(f, 44100.0).scrape(0.5).vibrato(6.0, 15.0));
+    check("bow scrape", &stats, 0.05, 3.0, 10.0);
+}
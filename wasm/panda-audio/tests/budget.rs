@@ -0,0 +1,82 @@
+//! Realtime budget: the worklet runs `detect` (and feeds the echo buffer)
+//! inside a single 128-sample render quantum once every 2048 samples, so that
+//! work must fit in a fraction of one quantum or the audio thread glitches.
+//! `detect` includes the voicing model's spectrum, so noise above the volume
+//! threshold is timed alongside the tones.
+//!
+//! Wall-clock timings depend on the host, so the budget is only enforced when
+//! the `PANDA_BUDGET_FRACTION` environment variable sets the fraction of a
+//! quantum to allow (0.25 is a good value on a developer machine); otherwise
+//! the timings are just printed. Native timings are a proxy for WASM, which
+//! typically runs 1.5-2x slower.
+//!
+//! ```text
+//! PANDA_BUDGET_FRACTION=0.25 cargo test -p panda-audio --test budget -- --nocapture
+//! ```
+
+mod common;
+
+use std::time::{Duration, Instant};
+
+use common::{noise, ViolinTone};
+use panda_audio::{EchoBuffer, PitchDetector};
+
+const SAMPLE_RATE: f32 = 48000.0;
+const QUANTUM_SAMPLES: f32 = 128.0;
+const BUFFER_SIZE: usize = 2048;
+const RUNS: usize = 60;
+
+/// The enforced budget, if `PANDA_BUDGET_FRACTION` is set
+fn budget() -> Option<Duration> {
+    let fraction = std::env::var("PANDA_BUDGET_FRACTION")
+        .ok()
+        .and_then(|v| v.parse::<f64>().ok())
+        .filter(|f| *f > 0.0)?;
+    Some(Duration::from_secs_f64(QUANTUM_SAMPLES as f64 / SAMPLE_RATE as f64 * fraction))
+}
+
+/// Median time of `work` over `RUNS` runs after a warm-up
+fn median_time(mut work: impl FnMut()) -> Duration {
+    for _ in 0..5 {
+        work();
+    }
+    let mut times: Vec<Duration> = (0..RUNS)
+        .map(|_| {
+            let start = Instant::now();
+            work();
+            start.elapsed()
+        })
+        .collect();
+    times.sort();
+    times[RUNS / 2]
+}
+
+#[test]
+fn worklet_frame_fits_render_budget() {
+    let budget = budget();
+    let tone = |frequency| ViolinTone::new(frequency, SAMPLE_RATE).vibrato(5.5, 20.0).noise(0.02).render(0, BUFFER_SIZE);
+    let frames = [
+        ("G3", tone(196.0)),
+        ("A4", tone(440.0)),
+        ("E6", tone(1318.5)),
+        ("noise", noise(BUFFER_SIZE, 0.1, 5)),
+    ];
+    for (name, buffer) in frames {
+        let mut detector = PitchDetector::new(SAMPLE_RATE, BUFFER_SIZE);
+        let mut echo = EchoBuffer::new(SAMPLE_RATE as usize * 30);
+        echo.set_recording(true);
+
+        let elapsed = median_time(|| {
+            let result = detector.detect(&buffer);
+            std::hint::black_box(result);
+            if echo.push_chunk(&buffer) {
+                echo.reset();
+                echo.set_recording(true);
+            }
+        });
+        match budget {
+            Some(budget) => assert!(elapsed <= budget, "{name}: worklet frame took {elapsed:?}, budget is {budget:?}"),
+            None => eprintln!("{name}: worklet frame took {elapsed:?} (set PANDA_BUDGET_FRACTION to enforce)"),
+        }
+    }
+}
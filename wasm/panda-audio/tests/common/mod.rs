@@ -0,0 +1,7 @@
+//! The crate's synthetic test signals (`src/test_signals.rs`), shared with
+//! the integration tests and benchmarks.
+
+#[path = "../../src/test_signals.rs"]
+mod test_signals;
+
+pub use test_signals::*;
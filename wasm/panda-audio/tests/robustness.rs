@@ -0,0 +1,248 @@
+//! Property tests: arbitrary buffer lengths, non-finite samples and extreme
+//! sample rates / bin counts must never panic. Release builds use
+//! `panic = "abort"`, so a panic in the worklet kills the audio thread.
+//!
+//! Increase the case count with `PROPTEST_CASES=10000`.
+
+use panda_audio::{
+    analyze_recording, classify_articulation, compute_chroma, compute_spectrogram, diagnose_input, ChannelLayout,
+    ChannelMode, ChannelSelector, ContourRecorder, DetectorConfig, EchoBuffer, LatencyCalibrator, Metronome,
+    PitchDetector, PreprocessConfig,
+};
+use proptest::prelude::*;
+
+/// Any sample, weighted towards the values a broken input actually produces
+fn sample() -> impl Strategy<Value = f32> {
+    prop_oneof![
+        6 => -1.0f32..1.0,
+        1 => Just(f32::NAN),
+        1 => Just(f32::INFINITY),
+        1 => Just(f32::NEG_INFINITY),
+        1 => prop::sample::select(vec![0.0, -0.0, 1.0, -1.0, f32::MAX, f32::MIN, f32::MIN_POSITIVE, 1e-42]),
+        1 => any::<f32>(),
+    ]
+}
+
+fn samples(max_len: usize) -> impl Strategy<Value = Vec<f32>> {
+    prop::collection::vec(sample(), 0..max_len)
+}
+
+/// A sine, optionally with one corrupted sample, so the voiced code paths run
+fn tone(max_len: usize) -> impl Strategy<Value = Vec<f32>> {
+    (0..max_len, 50.0f32..2000.0, 8000.0f32..96000.0, prop::option::of((any::<prop::sample::Index>(), sample())))
+        .prop_map(|(len, frequency, sample_rate, glitch)| {
+            let mut out: Vec<f32> = (0..len)
+                .map(|i| 0.5 * (std::f32::consts::TAU * frequency * i as f32 / sample_rate).sin())
+                .collect();
+            if let (Some((index, value)), false) = (glitch, out.is_empty()) {
+                out[index.index(len)] = value;
+            }
+            out
+        })
+}
+
+fn sample_rate() -> impl Strategy<Value = f32> {
+    prop_oneof![
+        4 => 8000.0f32..192_000.0,
+        1 => prop::sample::select(vec![0.0, -48000.0, 1.0, 1e-6, 1e9, f32::MAX, f32::INFINITY, f32::NAN]),
+    ]
+}
+
+/// Any value a JS caller can pass for a numeric setting
+fn setting() -> impl Strategy<Value = f32> {
+    prop_oneof![
+        2 => -10_000.0f32..10_000.0,
+        1 => prop::sample::select(vec![f32::NAN, f32::INFINITY, f32::NEG_INFINITY, 0.0, -1.0]),
+        1 => any::<f32>(),
+    ]
+}
+
+proptest! {
+    #[test]
+    fn detect_never_panics(sample_rate in sample_rate(), buffer_size in 0usize..5000, buffer in samples(2500)) {
+        let mut detector = PitchDetector::new(sample_rate, buffer_size);
+        for _ in 0..2 {
+            let result = detector.detect(&buffer);
+            // Invalid samples are sanitised, never propagated
+            for value in [
+                result.frequency(),
+                result.volume(),
+                result.confidence(),
+                result.voicing_probability(),
+                result.raw_frequency(),
+                result.clipping_percent(),
+            ] {
+                prop_assert!(value.is_finite(), "{value} in {result:?}");
+            }
+            prop_assert!((0.0..=100.0).contains(&result.clipping_percent()));
+        }
+    }
+
+    #[test]
+    fn detect_voiced_input_never_panics(sample_rate in sample_rate(), buffer in tone(5000), all_stages in any::<bool>()) {
+        let mut detector = PitchDetector::new(sample_rate, 2048);
+        if all_stages {
+            let mut config = PreprocessConfig::new();
+            config.hum_notch = true;
+            config.pre_emphasis = true;
+            detector.set_preprocessing(&config);
+        }
+        for _ in 0..3 {
+            let _ = detector.detect(&buffer);
+        }
+    }
+
+    #[test]
+    fn detector_config_never_panics(
+        values in prop::collection::vec(setting(), 10),
+        tune_tolerance in any::<i32>(),
+        median_window in any::<usize>(),
+        buffer in tone(3000),
+    ) {
+        let mut config = DetectorConfig::new();
+        for (field, value) in [
+            &mut config.min_freq,
+            &mut config.max_freq,
+            &mut config.volume_threshold,
+            &mut config.confidence_threshold,
+            &mut config.coarse_threshold,
+            &mut config.subharmonic_ratio,
+            &mut config.smoothing_alpha,
+            &mut config.one_euro_min_cutoff,
+            &mut config.one_euro_beta,
+            &mut config.note_change_cents,
+        ]
+        .into_iter()
+        .zip(&values)
+        {
+            *field = *value;
+        }
+        config.tune_tolerance = tune_tolerance;
+        config.median_window = median_window;
+
+        let valid = config.validated();
+        prop_assert!(valid.min_freq.is_finite() && valid.max_freq > valid.min_freq, "{valid:?}");
+        let mut detector = PitchDetector::new(48000.0, 2048);
+        detector.apply_config(&config);
+        for _ in 0..2 {
+            let result = detector.detect(&buffer);
+            prop_assert!(result.frequency().is_finite() && result.confidence().is_finite(), "{result:?}");
+        }
+    }
+
+    #[test]
+    fn echo_buffer_never_panics(
+        capacity in 0usize..5000,
+        chunks in prop::collection::vec(samples(400), 0..8),
+        bins in 0usize..6000,
+    ) {
+        let mut echo = EchoBuffer::new(capacity);
+        echo.set_recording(true);
+        for chunk in &chunks {
+            echo.push_chunk(chunk);
+        }
+        // More bins than recorded samples included
+        prop_assert_eq!(echo.extract_envelope(bins).len(), bins);
+    }
+
+    #[test]
+    fn offline_analysis_never_panics(sample_rate in sample_rate(), recording in prop_oneof![samples(4000), tone(6000)]) {
+        let report = analyze_recording(&recording, sample_rate);
+        for value in report.frequencies().into_iter().chain(report.confidences()) {
+            prop_assert!(value.is_finite(), "{value}");
+        }
+        let _ = classify_articulation(&recording, sample_rate);
+        let _ = diagnose_input(&recording, sample_rate);
+    }
+
+    #[test]
+    fn glitched_tone_is_still_analysed(
+        frequency in 200.0f32..1200.0,
+        glitch in (0usize..48000, prop::sample::select(vec![f32::NAN, f32::INFINITY, f32::NEG_INFINITY, f32::MAX])),
+    ) {
+        // One bad sample is sanitised, not allowed to silence the rest of the take
+        let mut recording: Vec<f32> = (0..48000)
+            .map(|i| 0.5 * (std::f32::consts::TAU * frequency * i as f32 / 48000.0).sin())
+            .collect();
+        recording[glitch.0] = glitch.1;
+        let report = analyze_recording(&recording, 48000.0);
+        prop_assert!(!report.notes().is_empty());
+        prop_assert!(report.voiced_ratio() > 0.5, "{}", report.voiced_ratio());
+        prop_assert!(classify_articulation(&recording, 48000.0).note_count() > 0);
+    }
+
+    #[test]
+    fn metronome_never_panics(
+        sample_rate in sample_rate(),
+        bpm in setting(),
+        tempo in setting(),
+        volume in setting(),
+        start in prop_oneof![0usize..1_000_000, Just(usize::MAX - 100)],
+        length in 0usize..4096,
+    ) {
+        let mut metronome = Metronome::new(sample_rate, bpm);
+        prop_assert!(metronome.bpm().is_finite());
+        metronome.set_tempo(tempo);
+        metronome.set_volume(volume);
+        prop_assert!(metronome.bpm().is_finite());
+        let mut output = vec![0.0; length];
+        metronome.render_into(start, &mut output);
+        prop_assert!(output.iter().all(|x| x.is_finite()));
+    }
+
+    #[test]
+    fn spectrogram_never_panics(
+        sample_rate in sample_rate(),
+        recording in samples(3000),
+        time_bins in prop_oneof![8 => 0usize..40, 1 => 40usize..600],
+        bands in prop_oneof![8 => 0usize..300, 1 => Just(usize::MAX / 2)],
+        min_freq in prop_oneof![-100.0f32..20000.0, Just(f32::NAN)],
+        max_freq in prop_oneof![-100.0f32..40000.0, Just(f32::INFINITY)],
+    ) {
+        let _ = compute_spectrogram(&recording, sample_rate, time_bins, bands, min_freq, max_freq);
+        let _ = compute_chroma(&recording, sample_rate, time_bins);
+    }
+
+    #[test]
+    fn contour_and_calibration_never_panic(
+        sample_rate in sample_rate(),
+        buffer in tone(3000),
+        max_frames in 0usize..50,
+        points in prop_oneof![8 => 0usize..200, 1 => Just(usize::MAX)],
+        timestamp in prop_oneof![any::<f64>(), 0.0f64..1e7],
+    ) {
+        let mut detector = PitchDetector::new(sample_rate, buffer.len());
+        let mut contour = ContourRecorder::new(max_frames);
+        for k in 0..4 {
+            contour.push(timestamp + k as f64 * 20.0, &detector.detect(&buffer));
+        }
+        let _ = contour.export_packed();
+        prop_assert!(contour.export_downsampled(points).len() <= contour.len() * contour.downsampled_stride());
+
+        let mut calibrator = LatencyCalibrator::new(sample_rate);
+        let _ = calibrator.push_input(&buffer);
+    }
+
+    #[test]
+    fn channel_selection_never_panics(
+        sample_rate in sample_rate(),
+        data in samples(3000),
+        channels in 0usize..9,
+        out_len in 0usize..600,
+        planar in any::<bool>(),
+        mode in prop::sample::select(vec![ChannelMode::Auto, ChannelMode::Downmix, ChannelMode::Fixed]),
+        fixed in 0usize..12,
+    ) {
+        let layout = if planar { ChannelLayout::Planar } else { ChannelLayout::Interleaved };
+        let mut selector = ChannelSelector::new(sample_rate);
+        selector.set_channel(fixed);
+        selector.set_mode(mode);
+        let mut out = vec![0.0; out_len];
+        for _ in 0..2 {
+            let frames = selector.mix_into(&data, channels, layout, &mut out);
+            prop_assert_eq!(frames, (data.len() / channels.max(1)).min(out_len));
+        }
+        prop_assert!(selector.channel_levels().iter().all(|level| level.is_finite()));
+        prop_assert!(selector.selected_channel() < channels.max(1) as i32);
+    }
+}
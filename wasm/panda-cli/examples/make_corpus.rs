@@ -0,0 +1,104 @@
+//! Writes the synthetic WAV fixtures of the golden regression corpus.
+//!
+//! ```text
+//! cargo run -p panda-cli --example make_corpus
+//! ```
+//!
+//! The fixtures are checked in; only re-run this when adding or changing a
+//! synthetic fixture, then regenerate the goldens (see `tests/golden.rs`).
+
+#[path = "../../panda-audio/src/test_signals.rs"]
+mod test_signals;
+
+use std::f32::consts::PI;
+use std::path::Path;
+
+use test_signals::Noise;
+
+const SAMPLE_RATE: u32 = 22050;
+
+/// One note of a fixture
+struct Note {
+    midi: f32,
+    seconds: f32,
+    /// Sharp (+) or flat (-) offset in cents
+    cents: f32,
+    vibrato_cents: f32,
+    pluck: bool,
+}
+
+fn note(midi: i32, seconds: f32) -> Note {
+    Note { midi: midi as f32, seconds, cents: 0.0, vibrato_cents: 0.0, pluck: false }
+}
+
+/// Render notes back to back with a bowed-string timbre plus `noise` level
+fn render(notes: &[Note], noise: f32) -> Vec<f32> {
+    let sr = SAMPLE_RATE as f32;
+    let mut rng = Noise::new(2024);
+    let mut out = Vec::new();
+    for n in notes {
+        let frequency = 440.0 * 2f32.powf((n.midi - 69.0 + n.cents / 100.0) / 12.0);
+        let len = (n.seconds * sr) as usize;
+        let mut phase = 0.0f32;
+        for i in 0..len {
+            let t = i as f32 / sr;
+            let vibrato = n.vibrato_cents * (2.0 * PI * 6.0 * t).sin();
+            phase += 2.0 * PI * frequency * 2f32.powf(vibrato / 1200.0) / sr;
+            let envelope = if n.pluck {
+                (t / 0.002).min(1.0) * (-t / 0.12).exp()
+            } else {
+                (t / 0.04).min(1.0) * ((n.seconds - t) / 0.02).min(1.0)
+            };
+            let mut sample = 0.0;
+            for h in 1..=8 {
+                if frequency * h as f32 <= sr * 0.45 {
+                    sample += (h as f32 * phase).sin() / h as f32;
+                }
+            }
+            out.push(0.2 * envelope * sample + noise * rng.next());
+        }
+    }
+    out
+}
+
+fn write(dir: &Path, name: &str, samples: &[f32]) {
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate: SAMPLE_RATE,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let path = dir.join(name);
+    let mut writer = hound::WavWriter::create(&path, spec).expect("create fixture");
+    for &s in samples {
+        writer.write_sample((s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16).expect("write sample");
+    }
+    writer.finalize().expect("finalize fixture");
+    println!("wrote {}", path.display());
+}
+
+fn main() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/corpus");
+    std::fs::create_dir_all(&dir).expect("create corpus directory");
+
+    write(&dir, "open-strings.wav", &render(&[note(55, 0.5), note(62, 0.5), note(69, 0.5), note(76, 0.5)], 0.0));
+
+    let vibrato = Note { vibrato_cents: 30.0, ..note(69, 1.2) };
+    write(&dir, "vibrato-a4.wav", &render(&[vibrato], 0.0));
+
+    let d_major: Vec<Note> = [62, 64, 66, 67, 69, 71, 73, 74].iter().map(|&m| note(m, 0.3)).collect();
+    write(&dir, "scale-d4-major.wav", &render(&d_major, 0.0));
+
+    let plucks: Vec<Note> = [67, 69, 71].iter().map(|&m| Note { pluck: true, ..note(m, 0.5) }).collect();
+    write(&dir, "pizzicato.wav", &render(&plucks, 0.0));
+
+    let sharp = Note { cents: 25.0, ..note(69, 0.6) };
+    let flat = Note { cents: -30.0, ..note(76, 0.6) };
+    write(&dir, "out-of-tune.wav", &render(&[sharp, flat], 0.0));
+
+    write(&dir, "noisy-g3.wav", &render(&[note(55, 1.0)], 0.01));
+
+    // A faint tone under the volume gate: should report nothing
+    let quiet: Vec<f32> = render(&[note(69, 0.6)], 0.002).iter().map(|s| s * 0.01).collect();
+    write(&dir, "near-silence.wav", &quiet);
+}
@@ -0,0 +1,361 @@
+//! Panda CLI - Native analysis of WAV recordings
+//!
+//! Runs the same `panda-audio` code paths the browser uses on WAV files and
+//! produces JSON, so teachers' sample recordings can be analysed and
+//! regression-checked on a laptop or in tests.
+
+use std::path::Path;
+
+use panda_audio::{
+    analyze_recording_with, check_scale, classify_articulation, estimate_key, AnalysisReport, Articulation,
+    ChannelLayout, ChannelMode, ChannelSelector, ScaleType,
+};
+use serde_json::{json, Value};
+
+// ============================================================================
+// WAV Input
+// ============================================================================
+
+/// Which channel of a multi-channel WAV file to analyse
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Channel {
+    /// Average all channels
+    #[default]
+    Downmix,
+    /// Follow the loudest live channel, as the worklet does
+    Auto,
+    /// One channel, 0-based
+    Index(usize),
+}
+
+/// Read a WAV file as mono f32 samples, reducing multi-channel files as
+/// `channel` says
+///
+/// Returns the samples and the sample rate.
+pub fn read_wav(path: &Path, channel: Channel) -> Result<(Vec<f32>, f32), String> {
+    let mut reader = hound::WavReader::open(path).map_err(|e| format!("{}: {e}", path.display()))?;
+    let spec = reader.spec();
+    let channels = spec.channels.max(1) as usize;
+
+    let interleaved: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => reader.samples::<f32>().collect::<Result<_, _>>(),
+        hound::SampleFormat::Int => {
+            let scale = 1.0 / (1u64 << (spec.bits_per_sample - 1)) as f32;
+            reader.samples::<i32>().map(|s| s.map(|v| v as f32 * scale)).collect()
+        }
+    }
+    .map_err(|e| format!("{}: {e}", path.display()))?;
+
+    let sample_rate = spec.sample_rate as f32;
+    let mut selector = ChannelSelector::new(sample_rate);
+    match channel {
+        Channel::Downmix => selector.set_mode(ChannelMode::Downmix),
+        Channel::Auto => selector.set_mode(ChannelMode::Auto),
+        Channel::Index(index) => selector.set_channel(index),
+    }
+    let mono = selector.mix(&interleaved, channels, ChannelLayout::Interleaved);
+    Ok((mono, sample_rate))
+}
+
+// ============================================================================
+// Analysis Options
+// ============================================================================
+
+/// Scale the recording is expected to contain
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ScaleSpec {
+    pub tonic_midi: i32,
+    pub scale_type: ScaleType,
+    pub octaves: u32,
+}
+
+/// What to analyse and include in the JSON output
+#[derive(Clone, Debug, PartialEq)]
+pub struct Options {
+    pub frame_size: usize,
+    pub hop_size: usize,
+    /// Include the per-frame pitch track
+    pub include_frames: bool,
+    /// Include per-note articulation
+    pub include_articulation: bool,
+    /// Check the recording against this scale
+    pub scale: Option<ScaleSpec>,
+    /// Channel to analyse in multi-channel files
+    pub channel: Channel,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            frame_size: 2048,
+            hop_size: 512,
+            include_frames: false,
+            include_articulation: false,
+            scale: None,
+            channel: Channel::Downmix,
+        }
+    }
+}
+
+/// Parse a note name such as "D4", "F#3" or "Bb5" into a MIDI note
+pub fn parse_note(name: &str) -> Option<i32> {
+    let name = name.trim();
+    let mut chars = name.chars();
+    let letter = chars.next()?.to_ascii_uppercase();
+    let base = match letter {
+        'C' => 0,
+        'D' => 2,
+        'E' => 4,
+        'F' => 5,
+        'G' => 7,
+        'A' => 9,
+        'B' => 11,
+        _ => return None,
+    };
+    let rest = &name[1..];
+    let (accidental, octave) = match rest.chars().next()? {
+        '#' => (1, &rest[1..]),
+        'b' => (-1, &rest[1..]),
+        _ => (0, rest),
+    };
+    let octave: i32 = octave.parse().ok()?;
+    Some(base + accidental + (octave + 1) * 12)
+}
+
+/// Parse a scale spec "TONIC:TYPE[:OCTAVES]", e.g. "D4:major" or
+/// "A3:harmonic-minor:2"
+pub fn parse_scale(spec: &str) -> Option<ScaleSpec> {
+    let mut parts = spec.split(':');
+    let tonic_midi = parse_note(parts.next()?)?;
+    let scale_type = match parts.next()?.trim().to_ascii_lowercase().as_str() {
+        "major" => ScaleType::Major,
+        "minor" | "natural-minor" => ScaleType::NaturalMinor,
+        "harmonic-minor" => ScaleType::HarmonicMinor,
+        "melodic-minor" => ScaleType::MelodicMinor,
+        _ => return None,
+    };
+    let octaves = match parts.next() {
+        Some(n) => n.trim().parse().ok().filter(|n| (1..=2).contains(n))?,
+        None => 1,
+    };
+    if parts.next().is_some() {
+        return None;
+    }
+    Some(ScaleSpec { tonic_midi, scale_type, octaves })
+}
+
+/// Parse a channel spec: "auto", or a 1-based channel number such as "2"
+pub fn parse_channel(spec: &str) -> Option<Channel> {
+    match spec.trim().to_ascii_lowercase().as_str() {
+        "auto" => Some(Channel::Auto),
+        number => number.parse::<usize>().ok().filter(|&n| n > 0).map(|n| Channel::Index(n - 1)),
+    }
+}
+
+// ============================================================================
+// JSON Output
+// ============================================================================
+
+/// Round to 4 decimal places so output is stable and readable
+fn round(value: f32) -> f64 {
+    (value as f64 * 1e4).round() / 1e4
+}
+
+fn round_all(values: &[f32]) -> Vec<f64> {
+    values.iter().map(|&v| round(v)).collect()
+}
+
+fn articulation_name(articulation: Articulation) -> &'static str {
+    match articulation {
+        Articulation::Pizzicato => "pizzicato",
+        Articulation::Legato => "legato",
+        Articulation::Staccato => "staccato",
+    }
+}
+
+fn summary_json(report: &AnalysisReport) -> Value {
+    json!({
+        "voicedRatio": round(report.voiced_ratio()),
+        "inTuneRatio": round(report.in_tune_ratio()),
+        "meanCents": round(report.mean_cents()),
+        "meanAbsCents": round(report.mean_abs_cents()),
+        "centsStdDev": round(report.cents_std_dev()),
+        "meanLoudnessDb": round(report.mean_loudness_db()),
+        "peakLoudnessDb": round(report.peak_loudness_db()),
+    })
+}
+
+/// Analyse mono samples and build the JSON document
+pub fn analyze(samples: &[f32], sample_rate: f32, options: &Options) -> Value {
+    let report = analyze_recording_with(samples, sample_rate, options.frame_size, options.hop_size);
+    let key = estimate_key(&report);
+
+    let notes: Vec<Value> = report
+        .notes()
+        .iter()
+        .map(|n| {
+            json!({
+                "start": round(n.start()),
+                "end": round(n.end()),
+                "note": n.note(),
+                "midi": n.midi(),
+                "frequency": round(n.frequency()),
+                "cents": round(n.cents()),
+                "confidence": round(n.confidence()),
+                "volume": round(n.volume()),
+            })
+        })
+        .collect();
+
+    let mut output = json!({
+        "sampleRate": sample_rate,
+        "duration": round(report.duration()),
+        "summary": summary_json(&report),
+        "key": {
+            "name": key.name(),
+            "tonic": key.tonic(),
+            "major": key.major(),
+            "confidence": round(key.confidence()),
+        },
+        "notes": notes,
+        "onsets": round_all(&report.onsets()),
+    });
+
+    if let Some(spec) = options.scale {
+        let check = check_scale(&report, spec.tonic_midi, spec.scale_type, spec.octaves);
+        output["scale"] = json!({
+            "name": check.name(),
+            "octaves": check.octaves(),
+            "correct": check.correct(),
+            "notesCorrect": check.notes_correct(),
+            "orderCorrect": check.order_correct(),
+            "descending": check.descending(),
+            "score": round(check.score()),
+            "missing": check.missing_names(),
+            "wrongNotes": check.wrong_notes(),
+            "degreeCents": round_all(&check.degree_cents()),
+            "meanAbsCents": round(check.mean_abs_cents()),
+        });
+    }
+
+    if options.include_articulation {
+        let articulation = classify_articulation(samples, sample_rate);
+        output["articulation"] = articulation
+            .notes()
+            .iter()
+            .map(|n| {
+                json!({
+                    "start": round(n.start()),
+                    "end": round(n.end()),
+                    "note": n.note(),
+                    "articulation": articulation_name(n.articulation()),
+                    "attackMs": round(n.attack_ms()),
+                    "decayDbPerS": round(n.decay_db_per_s()),
+                })
+            })
+            .collect();
+    }
+
+    if options.include_frames {
+        output["frames"] = json!({
+            "times": round_all(&report.times()),
+            "frequencies": round_all(&report.frequencies()),
+            "cents": report.cents(),
+            "confidences": round_all(&report.confidences()),
+            "loudnessDb": round_all(&report.loudness_db()),
+        });
+    }
+
+    output
+}
+
+/// Read `path` and analyse it
+pub fn analyze_file(path: &Path, options: &Options) -> Result<Value, String> {
+    let (samples, sample_rate) = read_wav(path, options.channel)?;
+    let mut output = analyze(&samples, sample_rate, options);
+    output["file"] = json!(path.file_name().map(|n| n.to_string_lossy()).unwrap_or_default());
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_note_and_scale() {
+        assert_eq!(parse_note("A4"), Some(69));
+        assert_eq!(parse_note("F#3"), Some(54));
+        assert_eq!(parse_note("Bb5"), Some(82));
+        assert_eq!(parse_note("H2"), None);
+        assert_eq!(
+            parse_scale("D4:harmonic-minor:2"),
+            Some(ScaleSpec { tonic_midi: 62, scale_type: ScaleType::HarmonicMinor, octaves: 2 })
+        );
+        assert_eq!(parse_scale("D4:major:3"), None);
+        assert_eq!(parse_scale("D4"), None);
+        assert_eq!(parse_channel("Auto"), Some(Channel::Auto));
+        assert_eq!(parse_channel("2"), Some(Channel::Index(1)));
+        assert_eq!(parse_channel("0"), None);
+        assert_eq!(parse_channel("left"), None);
+    }
+
+    #[test]
+    fn test_wav_round_trip_analysis() {
+        let path = std::env::temp_dir().join(format!("panda-cli-test-{}.wav", std::process::id()));
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: 44100,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+        for i in 0..44100 {
+            let value = (2.0 * std::f32::consts::PI * 440.0 * i as f32 / 44100.0).sin() * 0.4;
+            let sample = (value * i16::MAX as f32) as i16;
+            writer.write_sample(sample).unwrap();
+            writer.write_sample(sample).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let output = analyze_file(&path, &Options { include_frames: true, ..Options::default() }).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(output["sampleRate"], 44100.0);
+        assert_eq!(output["notes"][0]["note"], "A4");
+        assert!(output["summary"]["voicedRatio"].as_f64().unwrap() > 0.9);
+        assert!(!output["frames"]["times"].as_array().unwrap().is_empty());
+        assert!(output.get("scale").is_none());
+    }
+
+    #[test]
+    fn test_wav_channel_selection() {
+        // An interface that puts the mic on input 2 and leaves input 1 silent
+        let path = std::env::temp_dir().join(format!("panda-cli-channel-{}.wav", std::process::id()));
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: 48000,
+            bits_per_sample: 32,
+            sample_format: hound::SampleFormat::Float,
+        };
+        let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+        for i in 0..4800 {
+            writer.write_sample(0.0f32).unwrap();
+            writer.write_sample((2.0 * std::f32::consts::PI * 440.0 * i as f32 / 48000.0).sin() * 0.4).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let peak = |channel| {
+            let (mono, sample_rate) = read_wav(&path, channel).unwrap();
+            assert_eq!((mono.len(), sample_rate), (4800, 48000.0));
+            mono.iter().fold(0.0f32, |peak, s| peak.max(s.abs()))
+        };
+        let (downmix, auto, first, second) =
+            (peak(Channel::Downmix), peak(Channel::Auto), peak(Channel::Index(0)), peak(Channel::Index(1)));
+        std::fs::remove_file(&path).ok();
+
+        // Downmixing halves the level; auto and an explicit channel do not
+        assert!((downmix - 0.2).abs() < 1e-3, "{downmix}");
+        assert!((auto - 0.4).abs() < 1e-3, "{auto}");
+        assert_eq!((first, second), (0.0, auto));
+    }
+}
@@ -0,0 +1,121 @@
+//! `panda-analyze` - analyse WAV recordings and print JSON
+//!
+//! ```text
+//! panda-analyze [OPTIONS] <FILE.wav>...
+//! ```
+
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use panda_cli::{analyze_file, parse_channel, parse_scale, Options};
+use serde_json::Value;
+
+const USAGE: &str = "\
+Usage: panda-analyze [OPTIONS] <FILE.wav>...
+
+Analyse WAV recordings with panda-audio and print JSON.
+
+Options:
+  -o, --out <FILE>       Write JSON to FILE instead of stdout
+      --frame <N>        Analysis frame size in samples (default 2048)
+      --hop <N>          Hop size in samples (default 512)
+      --frames           Include the per-frame pitch track
+      --articulation     Include per-note articulation (pizzicato/legato/staccato)
+      --scale <SPEC>     Check against a scale, e.g. D4:major or A3:melodic-minor:2
+      --channel <auto|N> Analyse channel N (1-based) of multi-channel files, or
+                         the loudest live one (default: average all channels)
+      --compact          Print JSON on one line
+  -h, --help             Show this help";
+
+struct Args {
+    files: Vec<PathBuf>,
+    out: Option<PathBuf>,
+    compact: bool,
+    options: Options,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Option<Args>, String> {
+    let mut parsed = Args { files: Vec::new(), out: None, compact: false, options: Options::default() };
+
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or_else(|| format!("{name} needs a value"));
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "-o" | "--out" => parsed.out = Some(PathBuf::from(value(&arg)?)),
+            "--frame" => parsed.options.frame_size = parse_count(&arg, &value(&arg)?)?,
+            "--hop" => parsed.options.hop_size = parse_count(&arg, &value(&arg)?)?,
+            "--frames" => parsed.options.include_frames = true,
+            "--articulation" => parsed.options.include_articulation = true,
+            "--scale" => {
+                let spec = value(&arg)?;
+                parsed.options.scale = Some(parse_scale(&spec).ok_or_else(|| format!("invalid scale '{spec}'"))?);
+            }
+            "--channel" => {
+                let spec = value(&arg)?;
+                parsed.options.channel = parse_channel(&spec).ok_or_else(|| format!("invalid channel '{spec}'"))?;
+            }
+            "--compact" => parsed.compact = true,
+            flag if flag.starts_with('-') => return Err(format!("unknown option '{flag}'")),
+            file => parsed.files.push(PathBuf::from(file)),
+        }
+    }
+
+    if parsed.files.is_empty() {
+        return Err("no input files".to_string());
+    }
+    Ok(Some(parsed))
+}
+
+fn parse_count(name: &str, value: &str) -> Result<usize, String> {
+    value
+        .parse()
+        .ok()
+        .filter(|&n| n > 0)
+        .ok_or_else(|| format!("{name} must be a positive integer"))
+}
+
+fn run(args: Args) -> Result<(), String> {
+    let mut results = Vec::with_capacity(args.files.len());
+    for file in &args.files {
+        results.push(analyze_file(file, &args.options)?);
+    }
+    // One file prints a single object, several print an array
+    let output = if results.len() == 1 { results.remove(0) } else { Value::Array(results) };
+
+    let text = if args.compact {
+        serde_json::to_string(&output)
+    } else {
+        serde_json::to_string_pretty(&output)
+    }
+    .map_err(|e| e.to_string())?;
+
+    match &args.out {
+        Some(path) => std::fs::write(path, text + "\n").map_err(|e| format!("{}: {e}", path.display())),
+        None => match writeln!(io::stdout(), "{text}") {
+            // Output piped into e.g. `head` that exited early
+            Err(e) if e.kind() != io::ErrorKind::BrokenPipe => Err(e.to_string()),
+            _ => Ok(()),
+        },
+    }
+}
+
+fn main() -> ExitCode {
+    match parse_args(std::env::args().skip(1)) {
+        Ok(None) => {
+            println!("{USAGE}");
+            ExitCode::SUCCESS
+        }
+        Ok(Some(args)) => match run(args) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("error: {e}");
+                ExitCode::FAILURE
+            }
+        },
+        Err(e) => {
+            eprintln!("error: {e}\n\n{USAGE}");
+            ExitCode::from(2)
+        }
+    }
+}
@@ -0,0 +1,141 @@
+//! Golden-file regression corpus.
+//!
+//! Every fixture listed in `tests/corpus/manifest.json` is analysed with the
+//! same code path as `panda-analyze --frames` and compared with its checked-in
+//! `<name>.json` golden, using per-field tolerances.
+//!
+//! After an intentional behaviour change, regenerate the goldens and review
+//! the diff before committing:
+//!
+//! ```text
+//! PANDA_UPDATE_GOLDENS=1 cargo test -p panda-cli --test golden
+//! ```
+//!
+//! To add a real recording, copy the WAV into `tests/corpus`, add it to the
+//! manifest and regenerate.
+
+use std::path::{Path, PathBuf};
+
+use panda_cli::{analyze_file, parse_scale, Options};
+use serde_json::Value;
+
+/// Differences reported per fixture before truncating
+const MAX_REPORTED_DIFFS: usize = 20;
+
+fn corpus_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/corpus")
+}
+
+/// Absolute tolerance for a numeric field, chosen by its key
+fn tolerance(key: &str, expected: f64) -> f64 {
+    match key {
+        "start" | "end" | "onsets" | "times" | "duration" => 0.015,
+        "frequency" | "frequencies" => (expected.abs() * 0.002).max(0.01),
+        "cents" | "meanCents" | "meanAbsCents" | "centsStdDev" | "degreeCents" => 1.0,
+        "loudnessDb" | "meanLoudnessDb" | "peakLoudnessDb" => 0.2,
+        "attackMs" => 5.0,
+        "decayDbPerS" => 3.0,
+        "confidence" | "confidences" | "volume" | "voicedRatio" | "inTuneRatio" | "score" => 0.02,
+        _ => 1e-3,
+    }
+}
+
+/// Collect differences between `actual` and `expected` under `path`
+fn diff(path: &str, key: &str, actual: &Value, expected: &Value, diffs: &mut Vec<String>) {
+    match (actual, expected) {
+        (Value::Number(a), Value::Number(e)) => {
+            let (a, e) = (a.as_f64().unwrap_or(f64::NAN), e.as_f64().unwrap_or(f64::NAN));
+            // False for NaN, so a NaN on either side is reported
+            let within = (a - e).abs() <= tolerance(key, e);
+            if !within {
+                diffs.push(format!("{path}: {a} != {e} (tolerance {})", tolerance(key, e)));
+            }
+        }
+        (Value::Array(a), Value::Array(e)) => {
+            if a.len() != e.len() {
+                diffs.push(format!("{path}: length {} != {}", a.len(), e.len()));
+                return;
+            }
+            for (i, (a, e)) in a.iter().zip(e).enumerate() {
+                diff(&format!("{path}[{i}]"), key, a, e, diffs);
+            }
+        }
+        (Value::Object(a), Value::Object(e)) => {
+            for (k, e_value) in e {
+                match a.get(k) {
+                    Some(a_value) => diff(&format!("{path}.{k}"), k, a_value, e_value, diffs),
+                    None => diffs.push(format!("{path}.{k}: missing")),
+                }
+            }
+            for k in a.keys().filter(|k| !e.contains_key(*k)) {
+                diffs.push(format!("{path}.{k}: unexpected field"));
+            }
+        }
+        _ if actual != expected => diffs.push(format!("{path}: {actual} != {expected}")),
+        _ => {}
+    }
+}
+
+fn options_for(entry: &Value) -> Options {
+    Options {
+        include_frames: true,
+        include_articulation: entry["articulation"].as_bool().unwrap_or(false),
+        scale: entry["scale"].as_str().map(|s| parse_scale(s).expect("valid scale in manifest")),
+        ..Options::default()
+    }
+}
+
+#[test]
+fn corpus_matches_goldens() {
+    let dir = corpus_dir();
+    let update = std::env::var_os("PANDA_UPDATE_GOLDENS").is_some();
+    let manifest: Value =
+        serde_json::from_str(&std::fs::read_to_string(dir.join("manifest.json")).expect("read manifest"))
+            .expect("parse manifest");
+
+    let mut failures = Vec::new();
+    for entry in manifest.as_array().expect("manifest is an array") {
+        let file = entry["file"].as_str().expect("manifest entry has a file");
+        let actual = analyze_file(&dir.join(file), &options_for(entry)).expect("analyse fixture");
+        let golden_path = dir.join(file).with_extension("json");
+
+        if update {
+            let text = serde_json::to_string_pretty(&actual).expect("serialise golden");
+            std::fs::write(&golden_path, text + "\n").expect("write golden");
+            continue;
+        }
+
+        let expected: Value = match std::fs::read_to_string(&golden_path) {
+            Ok(text) => serde_json::from_str(&text).expect("parse golden"),
+            Err(_) => {
+                failures.push(format!("{file}: no golden (run with PANDA_UPDATE_GOLDENS=1)"));
+                continue;
+            }
+        };
+        let mut diffs = Vec::new();
+        diff("$", "", &actual, &expected, &mut diffs);
+        if !diffs.is_empty() {
+            let shown = diffs.len().min(MAX_REPORTED_DIFFS);
+            failures.push(format!("{file}: {} difference(s)\n    {}", diffs.len(), diffs[..shown].join("\n    ")));
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "golden mismatch (if intended, rerun with PANDA_UPDATE_GOLDENS=1 and review the diff):\n  {}",
+        failures.join("\n  ")
+    );
+}
+
+#[test]
+fn diff_applies_tolerances() {
+    let expected: Value = serde_json::json!({ "notes": [{ "cents": 3.0, "note": "A4" }], "duration": 1.0 });
+    let close: Value = serde_json::json!({ "notes": [{ "cents": 3.6, "note": "A4" }], "duration": 1.01 });
+    let far: Value = serde_json::json!({ "notes": [{ "cents": 9.0, "note": "A#4" }], "duration": 1.0 });
+
+    let mut diffs = Vec::new();
+    diff("$", "", &close, &expected, &mut diffs);
+    assert!(diffs.is_empty(), "{diffs:?}");
+    diff("$", "", &far, &expected, &mut diffs);
+    assert_eq!(diffs.len(), 2, "{diffs:?}");
+}
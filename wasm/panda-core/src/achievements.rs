@@ -18,6 +18,12 @@ pub struct AchievementTracker {
     achievements: Vec<Achievement>,
 }
 
+impl Default for AchievementTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[wasm_bindgen]
 impl AchievementTracker {
     #[wasm_bindgen(constructor)]
@@ -26,6 +26,12 @@ pub struct SkillProfile {
     reading: f32,
 }
 
+impl Default for SkillProfile {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[wasm_bindgen]
 impl SkillProfile {
     #[wasm_bindgen(constructor)]
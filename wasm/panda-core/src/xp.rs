@@ -49,6 +49,12 @@ pub struct PlayerProgress {
     game_scores: HashMap<String, u32>,
 }
 
+impl Default for PlayerProgress {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[wasm_bindgen]
 impl PlayerProgress {
     #[wasm_bindgen(constructor)]
@@ -101,11 +107,7 @@ impl PlayerProgress {
             return 0;
         }
         let next_level_xp = LEVEL_XP[self.level as usize];
-        if self.xp >= next_level_xp {
-            0
-        } else {
-            next_level_xp - self.xp
-        }
+        next_level_xp.saturating_sub(self.xp)
     }
 
     /// Get progress percentage to next level (0-100)